@@ -0,0 +1,91 @@
+use chrono::{Months, NaiveDate};
+use std::collections::HashMap;
+
+use crate::models::{
+    in_currency, Budget, Category, CurrencyCode, Money, MonthlyLimitData, RateProvider, Transaction, TransactionKind,
+};
+
+// Limity wszystkich miesięcy razem z ustawieniami budżetu
+pub struct MonthLimits<'a> {
+    pub limits: &'a HashMap<String, MonthlyLimitData>,
+    pub budget: &'a Budget,
+    // Bieżący miesiąc "RRRR-MM" - od niego miesiące bez limitów dostają szablon
+    pub current: &'a str,
+}
+
+impl MonthLimits<'_> {
+    pub fn get(&self, month: &str) -> Option<MonthlyLimitData> {
+        self.budget.limits_for(self.limits, month, self.current)
+    }
+
+    // Miesiące (od najwcześniejszego), z których limit kategorii przechodzi do `month`:
+    // poprzednie miesiące z limitem tej kategorii, aż do pierwszego bez niego
+    fn chain(&self, category: &str, month: &str) -> Vec<String> {
+        let mut chain = vec![];
+        let mut previous = previous_month(month);
+        while let Some(m) = previous {
+            let limited = self.get(&m).is_some_and(|data| data.categories.get(category).is_some_and(Money::is_positive));
+            if !limited {
+                break;
+            }
+            previous = previous_month(&m);
+            chain.push(m);
+        }
+        chain.reverse();
+        chain
+    }
+
+    // Pierwszy dzień najwcześniejszego miesiąca, którego wydatki wpływają na przeniesienia do `month`
+    pub fn rollover_start(&self, month: &str) -> Option<NaiveDate> {
+        self.budget
+            .rollover
+            .iter()
+            .filter_map(|category| self.chain(category, month).into_iter().next())
+            .min()
+            .and_then(|m| first_day(&m))
+    }
+
+    // Kwoty przeniesione do miesiąca `month` w walucie `currency` (bez zerowych).
+    // Niewykorzystany limit kategorii - albo przekroczenie, ze znakiem minus - przechodzi
+    // dalej razem z tym, co przeszło do poprzedniego miesiąca. Wydatek w podkategorii liczy
+    // się też do nadrzędnej. `transactions` muszą obejmować miesiące od rollover_start.
+    pub fn carried(
+        &self,
+        month: &str,
+        transactions: &[Transaction],
+        categories: &[Category],
+        currency: CurrencyCode,
+        rates: &impl RateProvider,
+    ) -> HashMap<String, Money> {
+        let (converted, _) = in_currency(transactions, currency, rates);
+        let mut carried = HashMap::new();
+        for category in &self.budget.rollover {
+            let mut amount: i64 = 0;
+            for m in self.chain(category, month) {
+                let limits = self.get(&m).map(|data| data.in_currency(&m, currency, rates));
+                let limit = limits.and_then(|data| data.categories.get(category).copied()).unwrap_or(Money::zero(currency));
+                let spent: i64 = converted
+                    .iter()
+                    .filter(|t| t.kind == TransactionKind::Expense && t.date.format("%Y-%m").to_string() == m)
+                    .filter(|t| Category::ancestors(categories, &t.category).contains(&category.as_str()))
+                    .map(|t| t.amount.minor)
+                    .fold(0, i64::saturating_add);
+                amount = amount.saturating_add(limit.minor).saturating_sub(spent);
+            }
+            if amount != 0 {
+                carried.insert(category.clone(), Money::new(amount, currency));
+            }
+        }
+        carried
+    }
+}
+
+fn first_day(month: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").ok()
+}
+
+// "RRRR-MM" miesiąca przed `month`
+pub fn previous_month(month: &str) -> Option<String> {
+    let previous = first_day(month)?.checked_sub_months(Months::new(1))?;
+    Some(previous.format("%Y-%m").to_string())
+}
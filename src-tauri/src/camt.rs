@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use crate::csv_import::{parse_amount, parse_date};
+use crate::models::{CurrencyCode, Money};
+use crate::statement::{self, Entry};
+use crate::xml::{attribute, open_lines, tokens, unescape, Token};
+
+// Wyciąg ISO 20022 camt.053 (BankToCustomerStatement), w dowolnej wersji schematu
+pub fn is_camt(text: &str) -> bool {
+    text.contains("BkToCstmrStmt")
+}
+
+// Pola operacji <Ntry> jako ścieżki względem niej, np. "BookgDt/Dt" albo
+// "NtryDtls/TxDtls/RltdPties/Cdtr/Nm". Od wersji 08 nazwa strony jest w dodatkowym
+// <Pty>, który pomijamy, żeby ścieżki były takie same we wszystkich wersjach.
+struct Record {
+    fields: Vec<(String, String)>,
+    currency: Option<String>,
+}
+
+impl Record {
+    fn get(&self, path: &str) -> &str {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == path)
+            .map(|(_, value)| value.as_str())
+            .unwrap_or_default()
+    }
+
+    fn first(&self, paths: &[&str]) -> &str {
+        paths.iter().map(|path| self.get(path)).find(|value| !value.is_empty()).unwrap_or_default()
+    }
+
+    fn all(&self, path: &str) -> Vec<&str> {
+        self.fields
+            .iter()
+            .filter(|(field, _)| field == path)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+}
+
+// Operacje <Ntry> ze wszystkich wyciągów <Stmt> w pliku. Identyfikator łączy rachunek
+// z referencją banku; bez niej tworzymy go z treści operacji.
+pub fn parse(text: &str, currency: CurrencyCode) -> Vec<Entry> {
+    let lines = open_lines(text, "Ntry");
+    let mut entries = vec![];
+    let mut occurrences = HashMap::new();
+    let mut account = String::new();
+    let mut path: Vec<&str> = vec![];
+    let mut record: Option<(usize, Record)> = None;
+
+    for token in tokens(text) {
+        match token {
+            Token::Open("Ntry", _) => {
+                record = Some((path.len() + 1, Record { fields: vec![], currency: None }));
+                path.push("Ntry");
+            }
+            Token::Open(name, attrs) => {
+                path.push(name);
+                if let Some((depth, record)) = record.as_mut() {
+                    if name == "Amt" && path.len() == *depth + 1 {
+                        record.currency = attribute(attrs, "Ccy").map(String::from);
+                    }
+                }
+            }
+            Token::Text(value) => match record.as_mut() {
+                Some((depth, record)) => {
+                    let field: Vec<&str> = path[*depth..].iter().copied().filter(|name| *name != "Pty").collect();
+                    record.fields.push((field.join("/"), unescape(value)));
+                }
+                // Rachunek wyciągu, a nie kontrahenta z operacji
+                None if path.ends_with(&["Stmt", "Acct", "Id", "IBAN"]) || path.ends_with(&["Acct", "Id", "Othr", "Id"]) => {
+                    account = value.trim().to_string();
+                }
+                None => {}
+            },
+            Token::Close(name) => {
+                path.pop();
+                if name == "Ntry" {
+                    if let Some((_, fields)) = record.take() {
+                        let line = lines.get(entries.len()).copied().unwrap_or(0);
+                        entries.push(entry(line, &fields, &account, currency, &mut occurrences));
+                    }
+                }
+            }
+        }
+    }
+    entries
+}
+
+fn entry(
+    line: usize,
+    record: &Record,
+    account: &str,
+    currency: CurrencyCode,
+    occurrences: &mut HashMap<String, usize>,
+) -> Entry {
+    let debit = record.get("CdtDbtInd") == "DBIT";
+    let currency = record.currency.as_deref().and_then(CurrencyCode::new).unwrap_or(currency);
+    // Kwota w <Amt> jest zawsze dodatnia, kierunek podaje <CdtDbtInd>
+    let amount = parse_amount(record.get("Amt"), currency)
+        .map(|a| Money::new(if debit { -a.minor.abs() } else { a.minor.abs() }, currency));
+    let booked = record.first(&["BookgDt/Dt", "BookgDt/DtTm", "ValDt/Dt", "ValDt/DtTm"]);
+    let date = parse_date(booked);
+
+    // Kontrahentem przy obciążeniu jest odbiorca, przy uznaniu - nadawca
+    let party = if debit { "Cdtr" } else { "Dbtr" };
+    let name = record.get(&format!("NtryDtls/TxDtls/RltdPties/{}/Nm", party));
+    let remittance = record.all("NtryDtls/TxDtls/RmtInf/Ustrd").join(" ");
+    let description = if remittance.trim().is_empty() {
+        record.first(&["NtryDtls/TxDtls/AddtlTxInf", "AddtlNtryInf"]).to_string()
+    } else {
+        remittance
+    };
+    let title = statement::title(name, &description);
+
+    let reference = record.first(&["AcctSvcrRef", "NtryDtls/TxDtls/Refs/AcctSvcrRef", "NtryRef"]);
+    let external_id = if !reference.is_empty() {
+        Some(format!("camt:{}:{}", account, reference))
+    } else {
+        date.zip(amount)
+            .map(|(date, amount)| statement::content_id(&format!("camt:{}", account), date, amount, &title, occurrences))
+    };
+
+    // Status to <Sts>BOOK</Sts> albo od wersji 08 <Sts><Cd>BOOK</Cd></Sts>
+    let status = record.first(&["Sts", "Sts/Cd"]);
+    Entry {
+        line,
+        cells: vec![
+            booked.to_string(),
+            format!("{}{} {}", if debit { "-" } else { "" }, record.get("Amt"), currency.as_str()),
+            name.to_string(),
+            description,
+            reference.to_string(),
+        ],
+        date,
+        amount,
+        title,
+        category: String::new(),
+        external_id,
+        unsupported: (!status.is_empty() && status != "BOOK")
+            .then(|| "Operacja nie jest zaksięgowana".to_string()),
+    }
+}
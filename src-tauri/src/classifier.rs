@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use crate::models::{Category, CategorySuggestion, Money, Transaction, TransactionId};
+use crate::rules::is_uncategorized;
+
+// Naiwny klasyfikator Bayesa nad słowami tytułu i przedziałem kwoty, uczony na historii
+// transakcji. Pamięta cechy każdej nauczonej transakcji, więc dodanie, edycję i usunięcie
+// uwzględnia od razu, bez uczenia od nowa.
+#[derive(Default)]
+pub struct Classifier {
+    learned: HashMap<TransactionId, (String, Vec<String>)>,
+    categories: HashMap<String, Counts>,
+    // Ile razy każda cecha wystąpiła we wszystkich kategoriach (rozmiar słownika do wygładzania)
+    vocabulary: HashMap<String, u32>,
+}
+
+#[derive(Default)]
+struct Counts {
+    transactions: u32,
+    features: HashMap<String, u32>,
+    total: u32,
+}
+
+impl Classifier {
+    pub fn train<'a>(transactions: impl IntoIterator<Item = &'a Transaction>) -> Classifier {
+        let mut classifier = Classifier::default();
+        for t in transactions {
+            classifier.learn(t);
+        }
+        classifier
+    }
+
+    // Nowa albo zmieniona transakcja; bez konkretnej kategorii niczego nie uczy
+    pub fn learn(&mut self, t: &Transaction) {
+        self.forget(t.id);
+        if is_uncategorized(t) {
+            return;
+        }
+        let features = features(&t.title, t.amount);
+        let counts = self.categories.entry(t.category.clone()).or_default();
+        counts.transactions += 1;
+        counts.total += features.len() as u32;
+        for feature in &features {
+            *counts.features.entry(feature.clone()).or_default() += 1;
+            *self.vocabulary.entry(feature.clone()).or_default() += 1;
+        }
+        self.learned.insert(t.id, (t.category.clone(), features));
+    }
+
+    pub fn forget(&mut self, id: TransactionId) {
+        let Some((category, features)) = self.learned.remove(&id) else {
+            return;
+        };
+        if let Some(counts) = self.categories.get_mut(&category) {
+            counts.transactions -= 1;
+            counts.total -= features.len() as u32;
+            for feature in &features {
+                decrement(&mut counts.features, feature);
+            }
+            if counts.transactions == 0 {
+                self.categories.remove(&category);
+            }
+        }
+        for feature in &features {
+            decrement(&mut self.vocabulary, feature);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.learned.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.learned.is_empty()
+    }
+
+    // Najbardziej prawdopodobna aktywna kategoria rodzaju transakcji. Bez podpowiedzi, gdy
+    // żadne słowo tytułu nie wystąpiło w historii tej kategorii - sama kwota to za mało.
+    pub fn suggest(&self, t: &Transaction, categories: &[Category]) -> Option<CategorySuggestion> {
+        let features = features(&t.title, t.amount);
+        let known: Vec<&String> = features.iter().filter(|f| self.vocabulary.contains_key(*f)).collect();
+        let candidates: Vec<(&String, &Counts)> = self
+            .categories
+            .iter()
+            .filter(|(id, _)| categories.iter().any(|c| &c.id == *id && c.kind == t.kind && !c.archived))
+            .collect();
+        let examples: u32 = candidates.iter().map(|(_, counts)| counts.transactions).sum();
+        let vocabulary = self.vocabulary.len() as f64;
+
+        // Logarytmy prawdopodobieństw a posteriori (bez wspólnego mianownika)
+        let scores: Vec<(&String, f64, bool)> = candidates
+            .iter()
+            .map(|(id, counts)| {
+                let prior = (counts.transactions as f64 / examples as f64).ln();
+                let likelihood: f64 = known
+                    .iter()
+                    .map(|f| {
+                        let count = counts.features.get(*f).copied().unwrap_or(0) as f64;
+                        ((count + 1.0) / (counts.total as f64 + vocabulary)).ln()
+                    })
+                    .sum();
+                let seen = known.iter().any(|f| !f.starts_with(AMOUNT_PREFIX) && counts.features.contains_key(*f));
+                (*id, prior + likelihood, seen)
+            })
+            .collect();
+
+        let (best, best_score, seen) = scores.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1))?;
+        if !seen {
+            return None;
+        }
+        let total: f64 = scores.iter().map(|(_, score, _)| (score - best_score).exp()).sum();
+        Some(CategorySuggestion {
+            category: best.clone(),
+            confidence: 1.0 / total,
+        })
+    }
+}
+
+fn decrement(counts: &mut HashMap<String, u32>, key: &str) {
+    if let Some(count) = counts.get_mut(key) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(key);
+        }
+    }
+}
+
+const AMOUNT_PREFIX: &str = "kwota:";
+
+// Słowa tytułu (małymi literami, bez samych cyfr - numery sklepów i faktur nic nie mówią)
+// i przedział kwoty: waluta i rząd wielkości co potęgę dwójki
+pub fn features(title: &str, amount: Money) -> Vec<String> {
+    let mut features: Vec<String> = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 2 && !word.chars().all(|c| c.is_ascii_digit()))
+        .map(String::from)
+        .collect();
+    let major = amount.to_major().abs().max(1.0);
+    features.push(format!("{}{}:{}", AMOUNT_PREFIX, amount.currency.as_str(), major.log2().floor() as i32));
+    features
+}
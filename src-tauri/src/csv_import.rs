@@ -0,0 +1,279 @@
+use chrono::NaiveDate;
+
+use crate::models::{Category, ColumnMapping, CsvFile, CsvOptions, CurrencyCode, ImportRow, Money, Transaction};
+use crate::{encoding, statement};
+
+const DELIMITERS: [char; 4] = [';', ',', '\t', '|'];
+// Tyle rekordów z początku pliku wystarcza do zgadnięcia ustawień i pokazania próbki
+const SAMPLE_ROWS: usize = 20;
+
+const DATE_FORMATS: [&str; 7] = ["%Y-%m-%d", "%d-%m-%Y", "%d.%m.%Y", "%d/%m/%Y", "%Y/%m/%d", "%Y.%m.%d", "%Y%m%d"];
+
+// Nagłówki kolumn spotykane w wyciągach (małymi literami, po polsku i angielsku)
+const TITLE_HEADERS: [&str; 9] = ["tytuł", "tytul", "opis", "title", "description", "kontrahent", "odbiorca", "payee", "nazwa"];
+const AMOUNT_HEADERS: [&str; 4] = ["kwota", "amount", "wartość", "value"];
+const DATE_HEADERS: [&str; 2] = ["data", "date"];
+const CATEGORY_HEADERS: [&str; 2] = ["kategoria", "category"];
+
+// Otwarcie pliku w kreatorze: zgadujemy kodowanie, separator, wiersze do pominięcia,
+// nagłówek i przypisanie kolumn. Użytkownik może to wszystko poprawić przed podglądem.
+pub fn inspect(content: &[u8], currency: CurrencyCode) -> Result<CsvFile, String> {
+    let encoding = encoding::detect(content);
+    let text = encoding::decode(content, encoding);
+    let delimiter = detect_delimiter(&text);
+    let records = records(&text, delimiter, Some(SAMPLE_ROWS));
+    if records.is_empty() {
+        return Err("Plik jest pusty".to_string());
+    }
+
+    // Wiersze przed właściwą tabelą mają zwykle inną liczbę pól niż reszta
+    let width = most_common_width(&records);
+    let skip_rows = records.iter().position(|(_, cells)| cells.len() == width).unwrap_or(0);
+    let first = &records[skip_rows].1;
+    let has_header = !first.iter().any(|cell| parse_date(cell).is_some() || parse_amount(cell, currency).is_some());
+
+    let options = CsvOptions {
+        delimiter,
+        encoding,
+        skip_rows,
+        has_header,
+        mapping: ColumnMapping::default(),
+        currency,
+    };
+    let (headers, sample) = split_header(&records, &options);
+    let mapping = guess_mapping(&headers, &sample, currency);
+    Ok(CsvFile {
+        options: CsvOptions { mapping, ..options },
+        headers,
+        sample,
+    })
+}
+
+// Ponowny odczyt po zmianie separatora, kodowania albo wierszy do pominięcia:
+// nowe nazwy kolumn, próbka i zgadnięte na nowo przypisanie kolumn
+pub fn reread(content: &[u8], options: &CsvOptions) -> CsvFile {
+    let text = encoding::decode(content, options.encoding);
+    let records = records(&text, options.delimiter, Some(options.skip_rows + SAMPLE_ROWS + 1));
+    let (headers, sample) = split_header(&records, options);
+    let mapping = guess_mapping(&headers, &sample, options.currency);
+    CsvFile {
+        options: CsvOptions { mapping, ..options.clone() },
+        headers,
+        sample,
+    }
+}
+
+// Wszystkie wiersze danych zamienione na transakcje; wiersze z błędem zostają w podglądzie
+// z opisem problemu i nie są importowane
+pub fn preview(content: &[u8], options: &CsvOptions, categories: &[Category]) -> Result<Vec<ImportRow>, String> {
+    let mapping = &options.mapping;
+    let (Some(title), Some(amount), Some(date)) = (mapping.title, mapping.amount, mapping.date) else {
+        return Err("Wskaż kolumny z tytułem, kwotą i datą".to_string());
+    };
+    let text = encoding::decode(content, options.encoding);
+    let data = records(&text, options.delimiter, None)
+        .into_iter()
+        .skip(options.skip_rows + options.has_header as usize);
+
+    let rows = data
+        .map(|(line, cells)| {
+            let cell = |column: usize| cells.get(column).map(|c| c.trim()).unwrap_or_default();
+            let category = mapping.category.map(cell).unwrap_or_default();
+            let result = to_transaction(cell(title), cell(amount), cell(date), category, options.currency, categories);
+            let (transaction, error) = match result {
+                Ok(t) => (Some(t), None),
+                Err(e) => (None, Some(e)),
+            };
+            ImportRow {
+                line,
+                cells,
+                transaction,
+                error,
+                duplicate_of: None,
+            }
+        })
+        .collect();
+    Ok(rows)
+}
+
+fn to_transaction(
+    title: &str,
+    amount: &str,
+    date: &str,
+    category: &str,
+    currency: CurrencyCode,
+    categories: &[Category],
+) -> Result<Transaction, String> {
+    let amount = parse_amount(amount, currency).ok_or_else(|| format!("Niepoprawna kwota: {}", amount))?;
+    let date = parse_date(date).ok_or_else(|| format!("Niepoprawna data: {}", date))?;
+    statement::transaction(title, amount, date, category, categories)
+}
+
+// Kwoty z wyciągów: "-1 234,56", "1,234.56", "+12.50 PLN", "−7,00" (minus z Unicode)
+pub fn parse_amount(text: &str, currency: CurrencyCode) -> Option<Money> {
+    let text: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\'')
+        .map(|c| if c == '\u{2212}' { '-' } else { c })
+        .collect();
+    // Kod albo symbol waluty przed lub za liczbą
+    let text = text.trim_matches(|c: char| !(c.is_ascii_digit() || matches!(c, ',' | '.' | '-' | '+')));
+    let text = text.strip_prefix('+').unwrap_or(text);
+
+    // Przy obu separatorach dziesiętny jest ostatni; powtórzony separator to separator tysięcy
+    let decimal = match (text.rfind(','), text.rfind('.')) {
+        (Some(comma), Some(dot)) => Some(if comma > dot { ',' } else { '.' }),
+        (Some(_), None) if text.matches(',').count() == 1 => Some(','),
+        (None, Some(_)) if text.matches('.').count() == 1 => Some('.'),
+        _ => None,
+    };
+    let normalized: String = text
+        .chars()
+        .filter(|c| !matches!(c, ',' | '.') || Some(*c) == decimal)
+        .collect();
+    Money::parse(&normalized, currency)
+}
+
+// Data bez godziny: "2024-01-31", "31.01.2024", "2024-01-31 12:00:00", "2024-01-31T12:00"
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    let day = text.trim().split(|c: char| c.is_whitespace() || c == 'T').next()?;
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(day, format).ok())
+}
+
+// Separator, który daje w kolejnych wierszach tę samą (większą niż 1) liczbę pól
+fn detect_delimiter(text: &str) -> char {
+    DELIMITERS
+        .into_iter()
+        .max_by_key(|&delimiter| {
+            let sample = records(text, delimiter, Some(SAMPLE_ROWS));
+            let width = most_common_width(&sample);
+            let rows = sample.iter().filter(|(_, cells)| cells.len() == width).count();
+            (width > 1, rows, width)
+        })
+        .unwrap_or(';')
+}
+
+fn most_common_width(records: &[(usize, Vec<String>)]) -> usize {
+    let mut counts = std::collections::BTreeMap::new();
+    for (_, cells) in records {
+        *counts.entry(cells.len()).or_insert(0) += 1;
+    }
+    // Przy remisie wygrywa szersza tabela
+    counts
+        .into_iter()
+        .max_by_key(|&(width, count)| (count, width))
+        .map(|(width, _)| width)
+        .unwrap_or(0)
+}
+
+fn split_header(records: &[(usize, Vec<String>)], options: &CsvOptions) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut rest = records.iter().skip(options.skip_rows).map(|(_, cells)| cells.clone());
+    let headers = if options.has_header {
+        rest.next().unwrap_or_default()
+    } else {
+        vec![]
+    };
+    (headers, rest.take(SAMPLE_ROWS).collect())
+}
+
+fn guess_mapping(headers: &[String], sample: &[Vec<String>], currency: CurrencyCode) -> ColumnMapping {
+    let by_header = |names: &[&str]| {
+        headers.iter().position(|header| {
+            let header = header.trim().to_lowercase();
+            names.iter().any(|name| header.starts_with(name))
+        })
+    };
+    // Bez nagłówka: pierwsza kolumna, w której wszystkie wartości dają się odczytać
+    let by_content = |parses: &dyn Fn(&str) -> bool, taken: &[Option<usize>]| {
+        let width = sample.iter().map(Vec::len).max().unwrap_or(0);
+        (0..width).find(|column| {
+            !taken.contains(&Some(*column))
+                && !sample.is_empty()
+                && sample
+                    .iter()
+                    .all(|row| row.get(*column).is_some_and(|cell| parses(cell)))
+        })
+    };
+
+    let date = by_header(&DATE_HEADERS).or_else(|| by_content(&|cell| parse_date(cell).is_some(), &[]));
+    let amount = by_header(&AMOUNT_HEADERS)
+        .or_else(|| by_content(&|cell| parse_amount(cell, currency).is_some(), &[date]));
+    let category = by_header(&CATEGORY_HEADERS);
+    // Tytuł: kolumna z nagłówka albo ta z najdłuższymi tekstami (liczby się nie liczą)
+    let title = by_header(&TITLE_HEADERS).or_else(|| {
+        let width = sample.iter().map(Vec::len).max().unwrap_or(0);
+        (0..width)
+            .filter(|column| ![date, amount, category].contains(&Some(*column)))
+            .max_by_key(|column| {
+                sample
+                    .iter()
+                    .filter_map(|row| row.get(*column))
+                    .filter(|cell| parse_amount(cell, currency).is_none())
+                    .map(|cell| cell.trim().chars().count())
+                    .sum::<usize>()
+            })
+    });
+    ColumnMapping {
+        title,
+        amount,
+        date,
+        category,
+    }
+}
+
+// Rekordy CSV z numerem wiersza, w którym się zaczynają. Pola w cudzysłowach mogą
+// zawierać separator, znaki nowej linii i podwojony cudzysłów. Puste wiersze pomijamy.
+fn records(text: &str, delimiter: char, limit: Option<usize>) -> Vec<(usize, Vec<String>)> {
+    let mut records = vec![];
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if limit.is_some_and(|limit| records.len() >= limit) {
+            break;
+        }
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if cell.trim().is_empty() => {
+                cell.clear();
+                quoted = true;
+            }
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                cells.push(std::mem::take(&mut cell));
+                push_record(&mut records, std::mem::take(&mut cells), start);
+                line += 1;
+                start = line;
+            }
+            c if c == delimiter && !quoted => cells.push(std::mem::take(&mut cell)),
+            c => {
+                if c == '\n' {
+                    line += 1;
+                }
+                cell.push(c);
+            }
+        }
+    }
+    if !cell.is_empty() || !cells.is_empty() {
+        cells.push(cell);
+        push_record(&mut records, cells, start);
+    }
+    records.truncate(limit.unwrap_or(usize::MAX));
+    records
+}
+
+fn push_record(records: &mut Vec<(usize, Vec<String>)>, cells: Vec<String>, start: usize) {
+    if cells.iter().any(|c| !c.trim().is_empty()) {
+        records.push((start, cells));
+    }
+}
@@ -0,0 +1,1163 @@
+use chrono::NaiveDate;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, ToSql};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::migrations::legacy_category_id;
+use crate::models::{
+    AppState, Budget, Category, CategoryRule, CurrencyCode, DisplayRates, ExchangeRate, Money, MonthlyLimitData,
+    RateProvider, RateSource, RateTable, RecurringTemplate, Settings, Transaction, TransactionId, TransactionKind,
+    DEFAULT_CATEGORIES, SCHEMA_VERSION,
+};
+
+// Schemat w najnowszej wersji - dla nowo tworzonej bazy
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS transactions (
+        id           TEXT PRIMARY KEY,
+        title        TEXT NOT NULL,
+        amount_minor INTEGER NOT NULL,
+        currency     TEXT NOT NULL,
+        date         TEXT NOT NULL,
+        category     TEXT NOT NULL,
+        kind         TEXT NOT NULL,
+        external_id  TEXT,
+        tags         TEXT NOT NULL DEFAULT '[]'
+    );
+    CREATE INDEX IF NOT EXISTS idx_transactions_date ON transactions(date);
+    CREATE INDEX IF NOT EXISTS idx_transactions_category ON transactions(category, date);
+    CREATE UNIQUE INDEX IF NOT EXISTS idx_transactions_external_id ON transactions(external_id)
+        WHERE external_id IS NOT NULL;
+
+    CREATE TABLE IF NOT EXISTS monthly_limits (
+        month         TEXT PRIMARY KEY,
+        general_minor INTEGER NOT NULL,
+        currency      TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS category_limits (
+        month        TEXT NOT NULL,
+        category     TEXT NOT NULL,
+        amount_minor INTEGER NOT NULL,
+        currency     TEXT NOT NULL,
+        PRIMARY KEY (month, category)
+    );
+
+    CREATE TABLE IF NOT EXISTS categories (
+        id       TEXT PRIMARY KEY,
+        kind     TEXT NOT NULL,
+        labels   TEXT NOT NULL,
+        color    TEXT NOT NULL,
+        icon     TEXT NOT NULL,
+        archived INTEGER NOT NULL,
+        parent   TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS exchange_rates (
+        date   TEXT NOT NULL,
+        base   TEXT NOT NULL,
+        quote  TEXT NOT NULL,
+        rate   REAL NOT NULL,
+        source TEXT NOT NULL,
+        PRIMARY KEY (base, quote, source, date)
+    );
+
+    CREATE TABLE IF NOT EXISTS category_rules (
+        id         TEXT PRIMARY KEY,
+        position   INTEGER NOT NULL,
+        enabled    INTEGER NOT NULL,
+        conditions TEXT NOT NULL,
+        category   TEXT NOT NULL,
+        tags       TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS recurring_templates (
+        id                 TEXT PRIMARY KEY,
+        title              TEXT NOT NULL,
+        amount_minor       INTEGER NOT NULL,
+        currency           TEXT NOT NULL,
+        kind               TEXT NOT NULL,
+        category           TEXT NOT NULL,
+        tags               TEXT NOT NULL,
+        schedule           TEXT NOT NULL,
+        changes            TEXT NOT NULL,
+        materialized_until TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS limit_template (
+        id            INTEGER PRIMARY KEY CHECK (id = 1),
+        general_minor INTEGER NOT NULL,
+        currency      TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS category_limit_template (
+        category     TEXT PRIMARY KEY,
+        amount_minor INTEGER NOT NULL,
+        currency     TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS limit_rollover (
+        category TEXT PRIMARY KEY
+    );
+
+    CREATE TABLE IF NOT EXISTS settings (
+        key   TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+type Upgrade = fn(&rusqlite::Transaction) -> rusqlite::Result<()>;
+
+// Aktualizacje istniejącej bazy: (wersja źródłowa, krok podnoszący ją o jeden).
+// Baza powstała przy wersji 2 dokumentu, więc wcześniejszych kroków nie ma.
+const UPGRADES: &[(u32, Upgrade)] = &[
+    (2, |tx| tx.execute_batch(V2_TO_V3)),
+    (3, |tx| tx.execute_batch(V3_TO_V4)),
+    (4, |tx| tx.execute_batch(V4_TO_V5)),
+    (5, v5_categories),
+    (6, |tx| tx.execute_batch(V6_TO_V7)),
+    (7, |tx| tx.execute_batch(V7_TO_V8)),
+    (8, |tx| tx.execute_batch(V8_TO_V9)),
+    (9, |tx| tx.execute_batch(V9_TO_V10)),
+    (10, |tx| tx.execute_batch(V10_TO_V11)),
+    (11, |tx| tx.execute_batch(V11_TO_V12)),
+];
+
+// v2 -> v3: kwoty REAL -> grosze INTEGER + kod waluty z ustawień
+const V2_TO_V3: &str = "
+    CREATE TEMP TABLE upgrade_currency AS
+        SELECT COALESCE((SELECT value FROM settings WHERE key = 'currency'), 'PLN') AS code;
+
+    ALTER TABLE transactions RENAME TO transactions_v2;
+    CREATE TABLE transactions (
+        id           INTEGER PRIMARY KEY,
+        title        TEXT NOT NULL,
+        amount_minor INTEGER NOT NULL,
+        currency     TEXT NOT NULL,
+        date         TEXT NOT NULL,
+        category     TEXT NOT NULL
+    );
+    INSERT INTO transactions (id, title, amount_minor, currency, date, category)
+        SELECT id, title, CAST(ROUND(amount * 100) AS INTEGER), (SELECT code FROM upgrade_currency), date, category
+        FROM transactions_v2;
+    DROP TABLE transactions_v2;
+    CREATE INDEX idx_transactions_date ON transactions(date);
+    CREATE INDEX idx_transactions_category ON transactions(category, date);
+
+    ALTER TABLE monthly_limits RENAME TO monthly_limits_v2;
+    CREATE TABLE monthly_limits (
+        month         TEXT PRIMARY KEY,
+        general_minor INTEGER NOT NULL,
+        currency      TEXT NOT NULL
+    );
+    INSERT INTO monthly_limits (month, general_minor, currency)
+        SELECT month, CAST(ROUND(general * 100) AS INTEGER), (SELECT code FROM upgrade_currency)
+        FROM monthly_limits_v2;
+    DROP TABLE monthly_limits_v2;
+
+    ALTER TABLE category_limits RENAME TO category_limits_v2;
+    CREATE TABLE category_limits (
+        month        TEXT NOT NULL,
+        category     TEXT NOT NULL,
+        amount_minor INTEGER NOT NULL,
+        currency     TEXT NOT NULL,
+        PRIMARY KEY (month, category)
+    );
+    INSERT INTO category_limits (month, category, amount_minor, currency)
+        SELECT month, category, CAST(ROUND(amount * 100) AS INTEGER), (SELECT code FROM upgrade_currency)
+        FROM category_limits_v2;
+    DROP TABLE category_limits_v2;
+
+    DROP TABLE upgrade_currency;
+";
+
+// v3 -> v4: id INTEGER -> tekst w formacie TransactionId::legacy(id, 0). W bazie id było
+// kluczem głównym, więc powtórzeń tu nie ma (rozwiązuje je migracja dokumentu JSON).
+const V3_TO_V4: &str = "
+    ALTER TABLE transactions RENAME TO transactions_v3;
+    CREATE TABLE transactions (
+        id           TEXT PRIMARY KEY,
+        title        TEXT NOT NULL,
+        amount_minor INTEGER NOT NULL,
+        currency     TEXT NOT NULL,
+        date         TEXT NOT NULL,
+        category     TEXT NOT NULL
+    );
+    INSERT INTO transactions (id, title, amount_minor, currency, date, category)
+        SELECT printf('00000000-0000-0000-0000-%012x', id), title, amount_minor, currency, date, category
+        FROM transactions_v3 ORDER BY rowid;
+    DROP TABLE transactions_v3;
+    CREATE INDEX idx_transactions_date ON transactions(date);
+    CREATE INDEX idx_transactions_category ON transactions(category, date);
+";
+
+// v4 -> v5: rodzaj transakcji; wszystkie dotychczasowe były wydatkami
+const V4_TO_V5: &str = "
+    ALTER TABLE transactions ADD COLUMN kind TEXT NOT NULL DEFAULT 'expense';
+";
+
+// v6 -> v7: kategoria nadrzędna; istniejące zostają kategoriami głównymi
+const V6_TO_V7: &str = "
+    ALTER TABLE categories ADD COLUMN parent TEXT;
+";
+
+// v7 -> v8: tabela kursów walut (wcześniej stałe w kodzie frontendu)
+const V7_TO_V8: &str = "
+    CREATE TABLE exchange_rates (
+        date   TEXT NOT NULL,
+        base   TEXT NOT NULL,
+        quote  TEXT NOT NULL,
+        rate   REAL NOT NULL,
+        source TEXT NOT NULL,
+        PRIMARY KEY (base, quote, source, date)
+    );
+";
+
+// v8 -> v9: identyfikator operacji z banku, unikalny wśród transakcji, które go mają
+const V8_TO_V9: &str = "
+    ALTER TABLE transactions ADD COLUMN external_id TEXT;
+    CREATE UNIQUE INDEX idx_transactions_external_id ON transactions(external_id)
+        WHERE external_id IS NOT NULL;
+";
+
+// v9 -> v10: tagi transakcji i reguły automatycznej kategoryzacji
+const V9_TO_V10: &str = "
+    ALTER TABLE transactions ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+    CREATE TABLE category_rules (
+        id         TEXT PRIMARY KEY,
+        position   INTEGER NOT NULL,
+        enabled    INTEGER NOT NULL,
+        conditions TEXT NOT NULL,
+        category   TEXT NOT NULL,
+        tags       TEXT NOT NULL
+    );
+";
+
+// v10 -> v11: szablony transakcji cyklicznych
+const V10_TO_V11: &str = "
+    CREATE TABLE recurring_templates (
+        id                 TEXT PRIMARY KEY,
+        title              TEXT NOT NULL,
+        amount_minor       INTEGER NOT NULL,
+        currency           TEXT NOT NULL,
+        kind               TEXT NOT NULL,
+        category           TEXT NOT NULL,
+        tags               TEXT NOT NULL,
+        schedule           TEXT NOT NULL,
+        changes            TEXT NOT NULL,
+        materialized_until TEXT
+    );
+";
+
+// v11 -> v12: szablon limitów i kategorie z przenoszeniem niewykorzystanego limitu
+const V11_TO_V12: &str = "
+    CREATE TABLE limit_template (
+        id            INTEGER PRIMARY KEY CHECK (id = 1),
+        general_minor INTEGER NOT NULL,
+        currency      TEXT NOT NULL
+    );
+    CREATE TABLE category_limit_template (
+        category     TEXT PRIMARY KEY,
+        amount_minor INTEGER NOT NULL,
+        currency     TEXT NOT NULL
+    );
+    CREATE TABLE limit_rollover (
+        category TEXT PRIMARY KEY
+    );
+";
+
+// v5 -> v6: tabela kategorii; transakcje i limity zamiast nazwy dostają id kategorii.
+// Nazwy tłumaczy ta sama funkcja, której używa migracja dokumentu JSON.
+fn v5_categories(tx: &rusqlite::Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE categories (
+            id       TEXT PRIMARY KEY,
+            kind     TEXT NOT NULL,
+            labels   TEXT NOT NULL,
+            color    TEXT NOT NULL,
+            icon     TEXT NOT NULL,
+            archived INTEGER NOT NULL
+        );
+        CREATE TEMP TABLE category_map (name TEXT PRIMARY KEY, id TEXT NOT NULL);",
+    )?;
+    let mut insert = tx.prepare(
+        "INSERT OR IGNORE INTO categories (id, kind, labels, color, icon, archived) VALUES (?1, ?2, ?3, ?4, ?5, 0)",
+    )?;
+    let labels = |pl: &str, en: &str| serde_json::json!({ "pl": pl, "en": en }).to_string();
+    for (id, kind, pl, en, color, icon) in DEFAULT_CATEGORIES {
+        insert.execute(params![id, kind, labels(pl, en), color, icon])?;
+    }
+
+    let mut names = tx.prepare(
+        "SELECT category, kind FROM transactions UNION SELECT category, 'expense' FROM category_limits",
+    )?;
+    let names: Vec<(String, String)> = names
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (name, kind) in names {
+        let id = legacy_category_id(&name);
+        insert.execute(params![id, kind, labels(name.trim(), name.trim()), "#94a3b8", "\u{1F3F7}"])?;
+        tx.execute("INSERT OR IGNORE INTO category_map (name, id) VALUES (?1, ?2)", params![name, id])?;
+    }
+
+    tx.execute_batch(
+        "UPDATE transactions SET category = (SELECT id FROM category_map WHERE name = transactions.category);
+        UPDATE OR REPLACE category_limits SET category = (SELECT id FROM category_map WHERE name = category_limits.category);
+        DROP TABLE category_map;",
+    )
+}
+
+#[derive(Debug)]
+pub enum OpenError {
+    Sqlite(rusqlite::Error),
+    Corrupted(String),
+    UnsupportedVersion(u32),
+}
+
+impl From<rusqlite::Error> for OpenError {
+    fn from(e: rusqlite::Error) -> Self {
+        OpenError::Sqlite(e)
+    }
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    // Zwraca bazę i informację, czy została dopiero utworzona. Świeżą bazę trzeba jeszcze
+    // przygotować przez initialize (wtedy można zaimportować stary JSON).
+    pub fn open(path: &Path) -> Result<(Store, bool), OpenError> {
+        let conn = Connection::open(path)?;
+
+        let check: String = conn
+            .query_row("PRAGMA quick_check", [], |row| row.get(0))
+            .map_err(|e| OpenError::Corrupted(e.to_string()))?;
+        if check != "ok" {
+            return Err(OpenError::Corrupted(check));
+        }
+
+        // user_version == 0 oznacza świeży plik bazy
+        let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(OpenError::UnsupportedVersion(version));
+        }
+
+        conn.pragma_update(None, "synchronous", "FULL")?;
+        let mut store = Store { conn };
+        if version > 0 {
+            store.upgrade(version)?;
+        }
+
+        Ok((store, version == 0))
+    }
+
+    // Schemat, import starych danych i numer wersji w jednej transakcji SQL - jeśli import
+    // się nie uda, baza zostaje świeża i przy następnym uruchomieniu próbujemy ponownie
+    pub fn initialize(&mut self, legacy: Option<&AppState>) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute_batch(SCHEMA)?;
+        if let Some(state) = legacy {
+            write_state(&tx, state)?;
+        }
+        tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        tx.commit()
+    }
+
+    fn upgrade(&mut self, from: u32) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        for (version, step) in UPGRADES {
+            if *version >= from {
+                step(&tx)?;
+            }
+        }
+        // Numer wersji razem z krokami - przerwane uruchomienie nie powtórzy ALTER TABLE
+        tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        tx.commit()
+    }
+
+    pub fn load_state(&self) -> rusqlite::Result<AppState> {
+        let mut state = AppState {
+            transactions: self.all_transactions()?,
+            categories: self.categories()?,
+            limits: self.all_limits()?,
+            rates: self.all_rates()?,
+            rules: self.rules()?,
+            recurring: self.recurring()?,
+            budget: self.budget()?,
+            ..AppState::default()
+        };
+        apply_settings(&mut state, self.settings()?);
+        Ok(state)
+    }
+
+    // Podmiana całej zawartości w jednej transakcji SQL
+    pub fn replace_state(&mut self, state: &AppState) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        write_state(&tx, state)?;
+        tx.commit()
+    }
+
+    // Zwraca false, gdy transakcja o tym id już jest w bazie - nic nie nadpisujemy
+    pub fn insert_transaction(&self, t: &Transaction) -> rusqlite::Result<bool> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM transactions WHERE id = ?1)",
+            params![t.id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Ok(false);
+        }
+        insert_transaction(&self.conn, t)?;
+        Ok(true)
+    }
+
+    // Import z pliku: wszystko albo nic, w jednej transakcji SQL. Transakcje o id albo
+    // identyfikatorze z banku, które już są w bazie, pomijamy; zwraca liczbę zapisanych.
+    pub fn insert_transactions(&mut self, transactions: &[Transaction]) -> rusqlite::Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+        for t in transactions {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM transactions WHERE id = ?1 OR external_id = ?2)",
+                params![t.id, t.external_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                insert_transaction(&tx, t)?;
+                inserted += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    // Identyfikatory z banku wszystkich zaimportowanych transakcji
+    pub fn external_ids(&self) -> rusqlite::Result<HashSet<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT external_id FROM transactions WHERE external_id IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    // Zwraca false, gdy transakcji o tym id nie ma w bazie
+    pub fn update_transaction(&self, t: &Transaction) -> rusqlite::Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE transactions SET title = ?2, amount_minor = ?3, currency = ?4, date = ?5, category = ?6, kind = ?7,
+             external_id = ?8, tags = ?9 WHERE id = ?1",
+            params![t.id, t.title, t.amount.minor, t.amount.currency, t.date.to_string(), t.category, t.kind, t.external_id, to_json(&t.tags)?],
+        )?;
+        Ok(changed > 0)
+    }
+
+    // Zmiana kategorii i tagów wielu transakcji naraz (ponowne zastosowanie reguł)
+    pub fn update_categories(&mut self, transactions: &[Transaction]) -> rusqlite::Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut changed = 0;
+        for t in transactions {
+            changed += tx.execute(
+                "UPDATE transactions SET category = ?2, tags = ?3 WHERE id = ?1",
+                params![t.id, t.category, to_json(&t.tags)?],
+            )?;
+        }
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    // Scalenie duplikatów w jednej transakcji SQL: duplikat (jeśli już zapisany) znika,
+    // a zapisana transakcja dostaje scalone dane. Zwraca liczbę scalonych par.
+    pub fn merge_duplicates(&mut self, merges: &[(Transaction, TransactionId)]) -> rusqlite::Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut merged = 0;
+        for (kept, duplicate) in merges {
+            // Najpierw usuwamy duplikat, bo może mieć ten sam identyfikator z banku
+            tx.execute("DELETE FROM transactions WHERE id = ?1", params![duplicate])?;
+            merged += tx.execute(
+                "UPDATE transactions SET category = ?2, external_id = ?3, tags = ?4 WHERE id = ?1",
+                params![kept.id, kept.category, kept.external_id, to_json(&kept.tags)?],
+            )?;
+        }
+        tx.commit()?;
+        Ok(merged)
+    }
+
+    pub fn delete_transaction(&self, id: TransactionId) -> rusqlite::Result<bool> {
+        let changed = self
+            .conn
+            .execute("DELETE FROM transactions WHERE id = ?1", params![id])?;
+        Ok(changed > 0)
+    }
+
+    // Transakcje z przedziału dat [from, to)
+    pub fn transactions_between(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> rusqlite::Result<Vec<Transaction>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM transactions WHERE date >= ?1 AND date < ?2 ORDER BY date, rowid",
+            TRANSACTION_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![from.to_string(), to.to_string()], row_to_transaction)?;
+        rows.collect()
+    }
+
+    pub fn set_month_limit(&mut self, month: &str, data: &MonthlyLimitData) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        write_month_limit(&tx, month, data)?;
+        tx.commit()
+    }
+
+    pub fn rules(&self) -> rusqlite::Result<Vec<CategoryRule>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, enabled, conditions, category, tags FROM category_rules ORDER BY position")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(CategoryRule {
+                id: row.get(0)?,
+                enabled: row.get(1)?,
+                conditions: from_json(row, 2)?,
+                category: row.get(3)?,
+                tags: from_json(row, 4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    // Reguły zapisujemy zawsze całą listą - kolejność to ich pierwszeństwo
+    pub fn save_rules(&mut self, rules: &[CategoryRule]) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM category_rules", [])?;
+        write_rules(&tx, rules)?;
+        tx.commit()
+    }
+
+    pub fn recurring(&self) -> rusqlite::Result<Vec<RecurringTemplate>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, amount_minor, currency, kind, category, tags, schedule, changes, materialized_until
+             FROM recurring_templates ORDER BY rowid",
+        )?;
+        let rows = stmt.query_map([], row_to_recurring)?;
+        rows.collect()
+    }
+
+    // Nowy szablon albo zmiana istniejącego
+    pub fn save_recurring(&self, template: &RecurringTemplate) -> rusqlite::Result<()> {
+        write_recurring(&self.conn, template)
+    }
+
+    // Utworzone już z szablonu transakcje zostają
+    pub fn delete_recurring(&self, id: &str) -> rusqlite::Result<bool> {
+        let changed = self.conn.execute("DELETE FROM recurring_templates WHERE id = ?1", params![id])?;
+        Ok(changed > 0)
+    }
+
+    // Wystąpienia, które nadeszły, razem z przesuniętymi szablonami - w jednej transakcji
+    // SQL, żeby po błędzie żadne nie zostało utworzone dwa razy ani pominięte
+    pub fn save_materialized(
+        &mut self,
+        templates: &[RecurringTemplate],
+        transactions: &[Transaction],
+    ) -> rusqlite::Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+        for t in transactions {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM transactions WHERE external_id = ?1)",
+                params![t.external_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                insert_transaction(&tx, t)?;
+                inserted += 1;
+            }
+        }
+        for template in templates {
+            write_recurring(&tx, template)?;
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    // Szablon limitów to wiersz limit_template z limitami kategorii; bez wiersza - brak szablonu
+    pub fn budget(&self) -> rusqlite::Result<Budget> {
+        let general: Option<Money> = self
+            .conn
+            .query_row("SELECT general_minor, currency FROM limit_template", [], |row| {
+                Ok(Money::new(row.get(0)?, row.get(1)?))
+            })
+            .optional()?;
+        let template = match general {
+            Some(general) => {
+                let mut stmt = self.conn.prepare("SELECT category, amount_minor, currency FROM category_limit_template")?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, Money::new(row.get(1)?, row.get(2)?))))?;
+                Some(MonthlyLimitData { general, categories: rows.collect::<rusqlite::Result<_>>()? })
+            }
+            None => None,
+        };
+        let mut stmt = self.conn.prepare("SELECT category FROM limit_rollover ORDER BY category")?;
+        let rollover = stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+        Ok(Budget { template, rollover })
+    }
+
+    pub fn save_budget(&mut self, budget: &Budget) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        write_budget(&tx, budget)?;
+        tx.commit()
+    }
+
+    pub fn categories(&self) -> rusqlite::Result<Vec<Category>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, kind, labels, color, icon, archived, parent FROM categories ORDER BY rowid")?;
+        let rows = stmt.query_map([], row_to_category)?;
+        rows.collect()
+    }
+
+    // Nowa kategoria albo zmiana istniejącej (nazwy, kolor, ikona, archiwizacja)
+    pub fn save_category(&self, category: &Category) -> rusqlite::Result<()> {
+        write_category(&self.conn, category)
+    }
+
+    // Przenosi transakcje, limity i podkategorie z kategorii `from` do `into` i usuwa `from`.
+    // Limity obu kategorii w tym samym miesiącu są sumowane. Zwraca false, gdy którejś brak.
+    pub fn merge_categories(&mut self, from: &str, into: &str) -> rusqlite::Result<bool> {
+        let tx = self.conn.transaction()?;
+        let found: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM categories WHERE id IN (?1, ?2)",
+            params![from, into],
+            |row| row.get(0),
+        )?;
+        if from == into || found != 2 {
+            return Ok(false);
+        }
+        tx.execute("UPDATE transactions SET category = ?2 WHERE category = ?1", params![from, into])?;
+        tx.execute(
+            "INSERT INTO category_limits (month, category, amount_minor, currency)
+                 SELECT month, ?2, amount_minor, currency FROM category_limits WHERE category = ?1
+             ON CONFLICT(month, category) DO UPDATE SET amount_minor = amount_minor + excluded.amount_minor",
+            params![from, into],
+        )?;
+        tx.execute("DELETE FROM category_limits WHERE category = ?1", params![from])?;
+        tx.execute(
+            "INSERT INTO category_limit_template (category, amount_minor, currency)
+                 SELECT ?2, amount_minor, currency FROM category_limit_template WHERE category = ?1
+             ON CONFLICT(category) DO UPDATE SET amount_minor = amount_minor + excluded.amount_minor",
+            params![from, into],
+        )?;
+        tx.execute("DELETE FROM category_limit_template WHERE category = ?1", params![from])?;
+        tx.execute(
+            "INSERT OR IGNORE INTO limit_rollover (category) SELECT ?2 FROM limit_rollover WHERE category = ?1",
+            params![from, into],
+        )?;
+        tx.execute("DELETE FROM limit_rollover WHERE category = ?1", params![from])?;
+        tx.execute("UPDATE categories SET parent = ?2 WHERE parent = ?1", params![from, into])?;
+        tx.execute("UPDATE category_rules SET category = ?2 WHERE category = ?1", params![from, into])?;
+        tx.execute("UPDATE recurring_templates SET category = ?2 WHERE category = ?1", params![from, into])?;
+        tx.execute("DELETE FROM categories WHERE id = ?1", params![from])?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    pub fn update_settings(&self, settings: &Settings) -> rusqlite::Result<()> {
+        write_settings(&self.conn, settings)
+    }
+
+    // Kategorie, reguły, szablony cykliczne i przenoszenie limitów zostają; szablon limitów nie
+    pub fn clear_data(&self) -> rusqlite::Result<()> {
+        self.conn.execute_batch(
+            "DELETE FROM transactions; DELETE FROM monthly_limits; DELETE FROM category_limits;
+             DELETE FROM limit_template; DELETE FROM category_limit_template;",
+        )
+    }
+
+    // Kursy na walutę raportu dla transakcji z przedziału [from, to) i limitów z tych miesięcy.
+    // Kwoty bez kursu w tabeli nie dostają wpisu - widok pominie je w sumach.
+    pub fn display_rates(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        currency: CurrencyCode,
+    ) -> rusqlite::Result<DisplayRates> {
+        let mut stmt = self.conn.prepare(
+            "SELECT currency, date FROM transactions WHERE date >= ?1 AND date < ?2 AND currency != ?3
+             UNION SELECT currency, month || '-01' FROM monthly_limits
+                 WHERE month || '-01' >= ?1 AND month || '-01' < ?2 AND currency != ?3
+             UNION SELECT currency, month || '-01' FROM category_limits
+                 WHERE month || '-01' >= ?1 AND month || '-01' < ?2 AND currency != ?3",
+        )?;
+        let needed: Vec<(CurrencyCode, String)> = stmt
+            .query_map(params![from.to_string(), to.to_string(), currency], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut display = DisplayRates::empty(currency);
+        if needed.is_empty() {
+            return Ok(display);
+        }
+        let table = RateTable::new(&self.all_rates()?);
+        for (from_currency, day) in needed {
+            let Ok(day) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") else {
+                continue;
+            };
+            if let Some(rate) = table.rate(from_currency, currency, day) {
+                display.rates.push((from_currency, day, rate));
+            }
+        }
+        Ok(display)
+    }
+
+    // Kursy obowiązujące w dniu `date`: dla każdej pary i źródła ostatni z tego dnia lub wcześniejszy
+    pub fn rates_on(&self, date: NaiveDate) -> rusqlite::Result<Vec<ExchangeRate>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT r.date, r.base, r.quote, r.rate, r.source FROM exchange_rates r
+             JOIN (SELECT base, quote, source, MAX(date) AS date FROM exchange_rates WHERE date <= ?1
+                   GROUP BY base, quote, source) latest USING (base, quote, source, date)
+             ORDER BY r.base, r.quote, r.source",
+        )?;
+        let rows = stmt.query_map(params![date.to_string()], row_to_rate)?;
+        rows.collect()
+    }
+
+    pub fn all_rates(&self) -> rusqlite::Result<Vec<ExchangeRate>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT date, base, quote, rate, source FROM exchange_rates ORDER BY date, base, quote, source")?;
+        let rows = stmt.query_map([], row_to_rate)?;
+        rows.collect()
+    }
+
+    // Import i ręczne korekty; kurs z tego samego dnia i źródła zostaje zastąpiony
+    pub fn save_rates(&mut self, rates: &[ExchangeRate]) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        write_rates(&tx, rates)?;
+        tx.commit()
+    }
+
+    pub fn delete_rate(&self, rate: &ExchangeRate) -> rusqlite::Result<bool> {
+        let changed = self.conn.execute(
+            "DELETE FROM exchange_rates WHERE date = ?1 AND base = ?2 AND quote = ?3 AND source = ?4",
+            params![rate.date.to_string(), rate.base, rate.quote, rate.source],
+        )?;
+        Ok(changed > 0)
+    }
+
+    pub fn all_transactions(&self) -> rusqlite::Result<Vec<Transaction>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM transactions ORDER BY date, rowid",
+            TRANSACTION_COLUMNS
+        ))?;
+        let rows = stmt.query_map([], row_to_transaction)?;
+        rows.collect()
+    }
+
+    pub fn all_limits(&self) -> rusqlite::Result<HashMap<String, MonthlyLimitData>> {
+        let mut limits = HashMap::new();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT month, general_minor, currency FROM monthly_limits")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, Money::new(row.get(1)?, row.get(2)?)))
+        })?;
+        for row in rows {
+            let (month, general) = row?;
+            limits.insert(
+                month,
+                MonthlyLimitData {
+                    general,
+                    categories: HashMap::new(),
+                },
+            );
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT month, category, amount_minor, currency FROM category_limits")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                Money::new(row.get(2)?, row.get(3)?),
+            ))
+        })?;
+        for row in rows {
+            let (month, category, amount) = row?;
+            limits
+                .entry(month)
+                .or_insert(MonthlyLimitData::empty(amount.currency))
+                .categories
+                .insert(category, amount);
+        }
+        Ok(limits)
+    }
+
+    fn settings(&self) -> rusqlite::Result<HashMap<String, String>> {
+        let mut stmt = self.conn.prepare("SELECT key, value FROM settings")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+}
+
+impl ToSql for CurrencyCode {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for CurrencyCode {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let code = value.as_str()?;
+        CurrencyCode::new(code).ok_or_else(|| FromSqlError::Other(format!("kod waluty: {}", code).into()))
+    }
+}
+
+impl ToSql for TransactionId {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+// Liczbowe id może jeszcze przyjść z bazy sprzed wersji 4 (odzyskiwanie z kwarantanny)
+impl FromSql for TransactionId {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Integer(old) => Ok(TransactionId::legacy(old as u64, 0)),
+            _ => {
+                let text = value.as_str()?;
+                TransactionId::parse(text)
+                    .ok_or_else(|| FromSqlError::Other(format!("id transakcji: {}", text).into()))
+            }
+        }
+    }
+}
+
+impl ToSql for TransactionKind {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for TransactionKind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        TransactionKind::parse(text)
+            .ok_or_else(|| FromSqlError::Other(format!("rodzaj transakcji: {}", text).into()))
+    }
+}
+
+impl ToSql for RateSource {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for RateSource {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let source = value.as_str()?;
+        RateSource::parse(source).ok_or_else(|| FromSqlError::Other(format!("źródło kursu: {}", source).into()))
+    }
+}
+
+const TRANSACTION_COLUMNS: &str = "id, title, amount_minor, currency, date, category, kind, external_id, tags";
+
+fn insert_transaction(conn: &Connection, t: &Transaction) -> rusqlite::Result<()> {
+    conn.execute(
+        &format!("INSERT INTO transactions ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)", TRANSACTION_COLUMNS),
+        params![t.id, t.title, t.amount.minor, t.amount.currency, t.date.to_string(), t.category, t.kind, t.external_id, to_json(&t.tags)?],
+    )?;
+    Ok(())
+}
+
+// Listy i struktury zapisywane w jednej kolumnie jako JSON
+fn to_json<T: serde::Serialize>(value: &T) -> rusqlite::Result<String> {
+    serde_json::to_string(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(row: &rusqlite::Row, index: usize) -> rusqlite::Result<T> {
+    let text: String = row.get(index)?;
+    serde_json::from_str(&text).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn write_rules(conn: &Connection, rules: &[CategoryRule]) -> rusqlite::Result<()> {
+    let mut insert = conn.prepare(
+        "INSERT INTO category_rules (id, position, enabled, conditions, category, tags) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for (position, rule) in rules.iter().enumerate() {
+        insert.execute(params![rule.id, position, rule.enabled, to_json(&rule.conditions)?, rule.category, to_json(&rule.tags)?])?;
+    }
+    Ok(())
+}
+
+// Cała zawartość bazy w miejsce dotychczasowej
+fn write_state(conn: &Connection, state: &AppState) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "DELETE FROM transactions; DELETE FROM monthly_limits; DELETE FROM category_limits;
+         DELETE FROM categories; DELETE FROM exchange_rates; DELETE FROM category_rules;
+         DELETE FROM recurring_templates;",
+    )?;
+    write_budget(conn, &state.budget)?;
+    write_rates(conn, &state.rates)?;
+    write_rules(conn, &state.rules)?;
+    for template in &state.recurring {
+        write_recurring(conn, template)?;
+    }
+    for category in &state.categories {
+        write_category(conn, category)?;
+    }
+    for t in &state.transactions {
+        insert_transaction(conn, t)?;
+    }
+    for (month, data) in &state.limits {
+        write_month_limit(conn, month, data)?;
+    }
+    write_settings(conn, &state.settings())
+}
+
+// Ustawienia budżetu zastępują poprzednie w całości
+fn write_budget(conn: &Connection, budget: &Budget) -> rusqlite::Result<()> {
+    conn.execute_batch("DELETE FROM limit_template; DELETE FROM category_limit_template; DELETE FROM limit_rollover;")?;
+    if let Some(template) = &budget.template {
+        conn.execute(
+            "INSERT INTO limit_template (id, general_minor, currency) VALUES (1, ?1, ?2)",
+            params![template.general.minor, template.general.currency],
+        )?;
+        let mut insert =
+            conn.prepare("INSERT INTO category_limit_template (category, amount_minor, currency) VALUES (?1, ?2, ?3)")?;
+        for (category, amount) in &template.categories {
+            insert.execute(params![category, amount.minor, amount.currency])?;
+        }
+    }
+    let mut insert = conn.prepare("INSERT OR IGNORE INTO limit_rollover (category) VALUES (?1)")?;
+    for category in &budget.rollover {
+        insert.execute(params![category])?;
+    }
+    Ok(())
+}
+
+fn write_recurring(conn: &Connection, t: &RecurringTemplate) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO recurring_templates (id, title, amount_minor, currency, kind, category, tags, schedule, changes, materialized_until)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT(id) DO UPDATE SET title = excluded.title, amount_minor = excluded.amount_minor,
+             currency = excluded.currency, kind = excluded.kind, category = excluded.category, tags = excluded.tags,
+             schedule = excluded.schedule, changes = excluded.changes, materialized_until = excluded.materialized_until",
+        params![
+            t.id,
+            t.title,
+            t.amount.minor,
+            t.amount.currency,
+            t.kind,
+            t.category,
+            to_json(&t.tags)?,
+            to_json(&t.schedule)?,
+            to_json(&t.changes)?,
+            t.materialized_until.map(|date| date.to_string())
+        ],
+    )?;
+    Ok(())
+}
+
+fn row_to_recurring(row: &rusqlite::Row) -> rusqlite::Result<RecurringTemplate> {
+    let materialized_until: Option<String> = row.get(9)?;
+    let materialized_until = materialized_until
+        .map(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(9, rusqlite::types::Type::Text, Box::new(e)))?;
+    Ok(RecurringTemplate {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        amount: Money::new(row.get(2)?, row.get(3)?),
+        kind: row.get(4)?,
+        category: row.get(5)?,
+        tags: from_json(row, 6)?,
+        schedule: from_json(row, 7)?,
+        changes: from_json(row, 8)?,
+        materialized_until,
+    })
+}
+
+fn write_category(conn: &Connection, category: &Category) -> rusqlite::Result<()> {
+    let labels = serde_json::to_string(&category.labels)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT INTO categories (id, kind, labels, color, icon, archived, parent) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET kind = excluded.kind, labels = excluded.labels,
+             color = excluded.color, icon = excluded.icon, archived = excluded.archived, parent = excluded.parent",
+        params![category.id, category.kind, labels, category.color, category.icon, category.archived, category.parent],
+    )?;
+    Ok(())
+}
+
+fn row_to_category(row: &rusqlite::Row) -> rusqlite::Result<Category> {
+    let labels: String = row.get(2)?;
+    let labels = serde_json::from_str(&labels).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(Category {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        labels,
+        color: row.get(3)?,
+        icon: row.get(4)?,
+        archived: row.get(5)?,
+        parent: row.get(6)?,
+    })
+}
+
+fn write_rates(conn: &Connection, rates: &[ExchangeRate]) -> rusqlite::Result<()> {
+    let mut upsert = conn.prepare(
+        "INSERT INTO exchange_rates (date, base, quote, rate, source) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(base, quote, source, date) DO UPDATE SET rate = excluded.rate",
+    )?;
+    for rate in rates {
+        upsert.execute(params![rate.date.to_string(), rate.base, rate.quote, rate.rate, rate.source])?;
+    }
+    Ok(())
+}
+
+fn row_to_rate(row: &rusqlite::Row) -> rusqlite::Result<ExchangeRate> {
+    let date: String = row.get(0)?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(ExchangeRate {
+        date,
+        base: row.get(1)?,
+        quote: row.get(2)?,
+        rate: row.get(3)?,
+        source: row.get(4)?,
+    })
+}
+
+// Limity miesiąca zastępują poprzednie w całości (także usunięte kategorie)
+fn write_month_limit(conn: &Connection, month: &str, data: &MonthlyLimitData) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO monthly_limits (month, general_minor, currency) VALUES (?1, ?2, ?3)
+         ON CONFLICT(month) DO UPDATE SET general_minor = excluded.general_minor, currency = excluded.currency",
+        params![month, data.general.minor, data.general.currency],
+    )?;
+    conn.execute("DELETE FROM category_limits WHERE month = ?1", params![month])?;
+    let mut insert = conn.prepare(
+        "INSERT INTO category_limits (month, category, amount_minor, currency) VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for (category, amount) in &data.categories {
+        insert.execute(params![month, category, amount.minor, amount.currency])?;
+    }
+    Ok(())
+}
+
+fn write_settings(conn: &Connection, settings: &Settings) -> rusqlite::Result<()> {
+    let mut upsert = conn.prepare(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+    )?;
+    upsert.execute(params!["theme", settings.theme])?;
+    upsert.execute(params!["language", settings.language])?;
+    upsert.execute(params!["currency", settings.currency])?;
+    Ok(())
+}
+
+fn apply_settings(state: &mut AppState, mut settings: HashMap<String, String>) {
+    if let Some(theme) = settings.remove("theme") {
+        state.theme = theme;
+    }
+    if let Some(language) = settings.remove("language") {
+        state.language = language;
+    }
+    if let Some(currency) = settings.remove("currency") {
+        state.currency = currency;
+    }
+}
+
+fn row_to_transaction(row: &rusqlite::Row) -> rusqlite::Result<Transaction> {
+    let date: String = row.get(4)?;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(Transaction {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        amount: Money::new(row.get(2)?, row.get(3)?),
+        kind: row.get(6)?,
+        date,
+        category: row.get(5)?,
+        external_id: row.get(7)?,
+        tags: from_json(row, 8)?,
+    })
+}
+
+// Odzyskiwanie z uszkodzonej bazy: czytamy wiersz po wierszu tyle, ile się da.
+// Zwraca odzyskany stan i liczbę pominiętych wierszy transakcji.
+pub fn salvage(path: &Path) -> rusqlite::Result<(AppState, usize)> {
+    // Bazę w starszej wersji najpierw aktualizujemy - na kopii, plik w kwarantannie zostaje
+    // nietknięty. Gdy aktualizacja się nie uda (uszkodzone strony), czytamy oryginał.
+    let copy = std::env::temp_dir().join(format!("expense_salvage_{}.db", std::process::id()));
+    let upgraded = fs::copy(path, &copy).is_ok() && upgrade_copy(&copy).is_ok();
+    let result = read_salvaged(if upgraded { &copy } else { path });
+    let _ = fs::remove_file(&copy);
+    result
+}
+
+fn upgrade_copy(path: &Path) -> rusqlite::Result<()> {
+    let conn = Connection::open(path)?;
+    let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let mut store = Store { conn };
+    if version > 0 && version < SCHEMA_VERSION {
+        store.upgrade(version)?;
+    }
+    Ok(())
+}
+
+fn read_salvaged(path: &Path) -> rusqlite::Result<(AppState, usize)> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut state = AppState::default();
+    let mut skipped = 0;
+
+    {
+        // Baza sprzed wersji 10 nie ma kolumny tags, a sprzed wersji 9 - także external_id
+        let without_tags = TRANSACTION_COLUMNS.replace("tags", "'[]'");
+        let stmt = conn
+            .prepare(&format!("SELECT {} FROM transactions", TRANSACTION_COLUMNS))
+            .or_else(|_| conn.prepare(&format!("SELECT {} FROM transactions", without_tags)))
+            .or_else(|_| {
+                let columns = without_tags.replace("external_id", "NULL");
+                conn.prepare(&format!("SELECT {} FROM transactions", columns))
+            });
+        if let Ok(mut stmt) = stmt {
+            if let Ok(mut rows) = stmt.query([]) {
+                // Błąd odczytu kolejnego wiersza zwykle oznacza uszkodzoną stronę - dalej nie idziemy
+                while let Ok(Some(row)) = rows.next() {
+                    match row_to_transaction(row) {
+                        Ok(tx) => state.transactions.push(tx),
+                        Err(_) => skipped += 1,
+                    }
+                }
+            }
+        }
+    }
+
+    let store = Store { conn };
+    if let Ok(limits) = store.all_limits() {
+        state.limits = limits;
+    }
+    if let Ok(categories) = store.categories() {
+        state.categories = categories;
+    }
+    if let Ok(rates) = store.all_rates() {
+        state.rates = rates;
+    }
+    if let Ok(rules) = store.rules() {
+        state.rules = rules;
+    }
+    if let Ok(recurring) = store.recurring() {
+        state.recurring = recurring;
+    }
+    if let Ok(budget) = store.budget() {
+        state.budget = budget;
+    }
+    state.ensure_categories();
+    if let Ok(settings) = store.settings() {
+        apply_settings(&mut state, settings);
+    }
+    Ok((state, skipped))
+}
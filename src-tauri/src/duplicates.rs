@@ -0,0 +1,100 @@
+use chrono::{Duration, NaiveDate};
+use std::collections::HashSet;
+
+use crate::models::{ImportRow, Transaction, FALLBACK_CATEGORY};
+
+// Ile dni mogą się różnić daty duplikatów (bank księguje płatność kartą z opóźnieniem)
+pub const DATE_WINDOW_DAYS: i64 = 3;
+// Minimalne podobieństwo tytułów (0..1), od którego uznajemy je za ten sam zakup
+const MIN_TITLE_SIMILARITY: f64 = 0.5;
+
+// Czy `b` wygląda na tę samą operację co `a`: ta sama kwota i rodzaj, daty blisko siebie
+// i podobny tytuł. Dwie operacje z różnymi identyfikatorami z banku nigdy nie są duplikatami.
+pub fn is_duplicate(a: &Transaction, b: &Transaction) -> bool {
+    if a.id == b.id || a.amount != b.amount || a.kind != b.kind {
+        return false;
+    }
+    if let (Some(x), Some(y)) = (&a.external_id, &b.external_id) {
+        if x != y {
+            return false;
+        }
+    }
+    (a.date - b.date).num_days().abs() <= DATE_WINDOW_DAYS && similarity(&a.title, &b.title) >= MIN_TITLE_SIMILARITY
+}
+
+// Kandydaci na duplikat `t` spośród `existing`, od najbardziej podobnego tytułu
+pub fn candidates<'a>(t: &Transaction, existing: &'a [Transaction]) -> Vec<&'a Transaction> {
+    let mut found: Vec<&Transaction> = existing.iter().filter(|e| is_duplicate(t, e)).collect();
+    found.sort_by(|a, b| similarity(&t.title, &b.title).total_cmp(&similarity(&t.title, &a.title)));
+    found
+}
+
+// Zaznacza w podglądzie importu wiersze, które mogą powtarzać zapisane już transakcje
+pub fn mark(rows: &mut [ImportRow], existing: &[Transaction]) {
+    for row in rows {
+        row.duplicate_of = row
+            .transaction
+            .as_ref()
+            .and_then(|t| candidates(t, existing).first().map(|d| (*d).clone()));
+    }
+}
+
+// Zakres dat [od, do), w którym trzeba szukać duplikatów transakcji z tych dat
+pub fn search_range(dates: impl IntoIterator<Item = NaiveDate>) -> Option<(NaiveDate, NaiveDate)> {
+    let dates: Vec<NaiveDate> = dates.into_iter().collect();
+    let (min, max) = (dates.iter().min()?, dates.iter().max()?);
+    let window = Duration::days(DATE_WINDOW_DAYS);
+    Some((*min - window, *max + window + Duration::days(1)))
+}
+
+// Transakcja po scaleniu duplikatu z zapisaną: zostają jej dane, a z duplikatu bierzemy
+// identyfikator z banku (żeby kolejny import tej operacji ją rozpoznał), brakujące tagi
+// i kategorię, jeśli zapisana nie ma żadnej konkretnej
+pub fn merge(kept: &Transaction, duplicate: &Transaction) -> Transaction {
+    let mut merged = kept.clone();
+    if merged.external_id.is_none() {
+        merged.external_id = duplicate.external_id.clone();
+    }
+    if merged.category == FALLBACK_CATEGORY {
+        merged.category = duplicate.category.clone();
+    }
+    for tag in &duplicate.tags {
+        if !merged.tags.contains(tag) {
+            merged.tags.push(tag.clone());
+        }
+    }
+    merged
+}
+
+// Podobieństwo tytułów od 0 do 1. Tytuł zawarty w drugim ("Biedronka" i "BIEDRONKA 1234
+// WARSZAWA") liczy się jako zgodny; poza tym współczynnik Dice'a par liter.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let (shorter, longer) = if a.len() <= b.len() { (&a, &b) } else { (&b, &a) };
+    if shorter.iter().all(|word| longer.contains(word)) {
+        return 1.0;
+    }
+    let (x, y) = (bigrams(&a), bigrams(&b));
+    if x.is_empty() || y.is_empty() {
+        return 0.0;
+    }
+    let common = x.iter().filter(|pair| y.contains(*pair)).count();
+    2.0 * common as f64 / (x.len() + y.len()) as f64
+}
+
+// Słowa małymi literami, bez znaków przestankowych
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn bigrams(words: &[String]) -> HashSet<(char, char)> {
+    let letters: Vec<char> = words.concat().chars().collect();
+    letters.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
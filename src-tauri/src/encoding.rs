@@ -0,0 +1,46 @@
+use crate::models::TextEncoding;
+
+// Znaki Windows-1250 dla bajtów 0x80-0xFF (poniżej jest zwykłe ASCII).
+// Bajty bez przypisanego znaku zamieniamy na U+FFFD.
+const WINDOWS_1250: [char; 128] = [
+    '\u{20ac}', '\u{fffd}', '\u{201a}', '\u{fffd}', '\u{201e}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{fffd}', '\u{2030}', '\u{160}', '\u{2039}', '\u{15a}', '\u{164}', '\u{17d}', '\u{179}',
+    '\u{fffd}', '\u{2018}', '\u{2019}', '\u{201c}', '\u{201d}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{fffd}', '\u{2122}', '\u{161}', '\u{203a}', '\u{15b}', '\u{165}', '\u{17e}', '\u{17a}',
+    '\u{a0}', '\u{2c7}', '\u{2d8}', '\u{141}', '\u{a4}', '\u{104}', '\u{a6}', '\u{a7}',
+    '\u{a8}', '\u{a9}', '\u{15e}', '\u{ab}', '\u{ac}', '\u{ad}', '\u{ae}', '\u{17b}',
+    '\u{b0}', '\u{b1}', '\u{2db}', '\u{142}', '\u{b4}', '\u{b5}', '\u{b6}', '\u{b7}',
+    '\u{b8}', '\u{105}', '\u{15f}', '\u{bb}', '\u{13d}', '\u{2dd}', '\u{13e}', '\u{17c}',
+    '\u{154}', '\u{c1}', '\u{c2}', '\u{102}', '\u{c4}', '\u{139}', '\u{106}', '\u{c7}',
+    '\u{10c}', '\u{c9}', '\u{118}', '\u{cb}', '\u{11a}', '\u{cd}', '\u{ce}', '\u{10e}',
+    '\u{110}', '\u{143}', '\u{147}', '\u{d3}', '\u{d4}', '\u{150}', '\u{d6}', '\u{d7}',
+    '\u{158}', '\u{16e}', '\u{da}', '\u{170}', '\u{dc}', '\u{dd}', '\u{162}', '\u{df}',
+    '\u{155}', '\u{e1}', '\u{e2}', '\u{103}', '\u{e4}', '\u{13a}', '\u{107}', '\u{e7}',
+    '\u{10d}', '\u{e9}', '\u{119}', '\u{eb}', '\u{11b}', '\u{ed}', '\u{ee}', '\u{10f}',
+    '\u{111}', '\u{144}', '\u{148}', '\u{f3}', '\u{f4}', '\u{151}', '\u{f6}', '\u{f7}',
+    '\u{159}', '\u{16f}', '\u{fa}', '\u{171}', '\u{fc}', '\u{fd}', '\u{163}', '\u{2d9}',
+];
+
+// Kodowanie zgadujemy po treści: poprawny UTF-8 (z BOM albo bez) to UTF-8,
+// wszystko inne traktujemy jako Windows-1250
+pub fn detect(content: &[u8]) -> TextEncoding {
+    if std::str::from_utf8(content).is_ok() {
+        TextEncoding::Utf8
+    } else {
+        TextEncoding::Windows1250
+    }
+}
+
+pub fn decode(content: &[u8], encoding: TextEncoding) -> String {
+    let text = match encoding {
+        TextEncoding::Utf8 => String::from_utf8_lossy(content).into_owned(),
+        TextEncoding::Windows1250 => content
+            .iter()
+            .map(|&b| if b < 0x80 { b as char } else { WINDOWS_1250[(b - 0x80) as usize] })
+            .collect(),
+    };
+    match text.strip_prefix('\u{feff}') {
+        Some(rest) => rest.to_string(),
+        None => text,
+    }
+}
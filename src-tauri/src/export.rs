@@ -0,0 +1,41 @@
+use crate::models::{Category, Money, Transaction, TransactionKind};
+
+// Kolumny eksportu CSV. Kwota ze znakiem (wydatki ujemne) i id kategorii pozwalają
+// wczytać plik z powrotem kreatorem importu CSV.
+const CSV_HEADER: [&str; 7] = ["date", "title", "amount", "currency", "kind", "category", "category_name"];
+
+// Transakcje jako CSV (RFC 4180, przecinek, UTF-8); nazwy kategorii w języku `lang`
+pub fn transactions_csv(transactions: &[Transaction], categories: &[Category], lang: &str) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push_str("\r\n");
+    for t in transactions {
+        let amount = if t.kind == TransactionKind::Expense { Money::new(-t.amount.minor, t.amount.currency) } else { t.amount };
+        let category_name = categories
+            .iter()
+            .find(|c| c.id == t.category)
+            .map(|c| c.label(lang).to_string())
+            .unwrap_or_else(|| t.category.clone());
+        let fields = [
+            t.date.to_string(),
+            t.title.clone(),
+            amount.to_decimal_string(),
+            t.amount.currency.as_str().to_string(),
+            t.kind.as_str().to_string(),
+            t.category.clone(),
+            category_name,
+        ];
+        let line: Vec<String> = fields.iter().map(|field| quote(field)).collect();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+// Pole w cudzysłowie, gdy zawiera przecinek, cudzysłów albo znak nowej linii
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -1,55 +1,729 @@
+use chrono::{Datelike, Local, NaiveDate};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_opener::OpenerExt;
 
 #[path = "../../src/models.rs"]
 pub mod models;
+pub mod budget;
+pub mod camt;
+pub mod classifier;
+pub mod csv_import;
+pub mod db;
+pub mod duplicates;
+pub mod encoding;
+pub mod export;
+pub mod migrations;
+pub mod mt940;
+pub mod ofx;
+pub mod qif;
+pub mod rates;
+pub mod recurring;
+pub mod rules;
+pub mod recovery;
+pub mod statement;
+pub mod storage;
+pub mod subscriptions;
+mod xml;
 
-use models::AppState;
+use classifier::Classifier;
+use db::Store;
+use rules::RuleSet;
+use models::{
+    AppState, Budget, Category, CategoryRule, CategorySuggestion, CsvFile, CsvOptions, CurrencyCode, DisplayRates, DuplicateMerge, ExchangeRate, ImportReport,
+    ImportRow, LoadError, Money, MonthlyLimitData, Occurrence, OccurrenceChange, RateSource, RecurringTemplate, Settings, Subscription, Transaction, TransactionFilter,
+    TransactionId,
+};
 
-fn get_data_path(app: &AppHandle) -> PathBuf {
+// Główne dane trzymamy w SQLite. expense_data.json to dawny format: importowany raz
+// do nowej bazy, a potem zapisywany przy każdym starcie jako migawka z rotacją kopii.
+const DB_FILE: &str = "expense_data.db";
+const SNAPSHOT_FILE: &str = "expense_data.json";
+
+// Otwarta baza; None dopóki load_data (albo ekran odzyskiwania) jej nie otworzy
+#[derive(Default)]
+struct DataStore(Mutex<Option<Store>>);
+
+// Klasyfikator podpowiedzi kategorii; None, dopóki pierwsza podpowiedź nie nauczy go na
+// historii - po starcie i po zmianach wielu transakcji naraz (import, scalanie kategorii)
+#[derive(Default)]
+struct Learner(Mutex<Option<Classifier>>);
+
+fn get_data_dir(app: &AppHandle) -> PathBuf {
     let app_dir = app.path().app_data_dir().unwrap();
     if !app_dir.exists() {
         let _ = fs::create_dir_all(&app_dir);
     }
-    app_dir.join("expense_data.json")
+    app_dir
+}
+
+fn with_store<T>(
+    data: &State<DataStore>,
+    f: impl FnOnce(&mut Store) -> rusqlite::Result<T>,
+) -> Result<T, String> {
+    let mut guard = data.0.lock().map_err(|e| e.to_string())?;
+    let store = guard.as_mut().ok_or("Baza danych nie jest otwarta")?;
+    f(store).map_err(|e| e.to_string())
+}
+
+// Pojedynczą zmianę nauczony już klasyfikator uwzględnia od razu
+fn update_learner(learner: &State<Learner>, f: impl FnOnce(&mut Classifier)) {
+    if let Some(classifier) = learner.0.lock().unwrap().as_mut() {
+        f(classifier);
+    }
+}
+
+fn reset_learner(learner: &State<Learner>) {
+    *learner.0.lock().unwrap() = None;
 }
 
+// Start aplikacji: otwiera bazę i zwraca ustawienia. Transakcje i limity frontend
+// pobiera osobno (query_transactions, load_limits) - tylko to, co wyświetla.
 #[tauri::command]
-fn save_data(app: AppHandle, state: AppState) -> Result<(), String> {
-    println!("DEBUG: Próba zapisu danych..."); 
+fn load_data(app: AppHandle, data: State<DataStore>, learner: State<Learner>) -> Result<Settings, LoadError> {
+    println!("DEBUG: Próba wczytania danych...");
+    let dir = get_data_dir(&app);
 
-    let path = get_data_path(&app);
-    let json = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
-    
-    fs::write(path, json).map_err(|e| e.to_string())?;
-    
-    println!("DEBUG: Zapisano pomyślnie!");
+    let mut store = recovery::open_store(&dir.join(DB_FILE), &dir.join(SNAPSHOT_FILE))?;
+    materialize_recurring(&mut store).map_err(recovery::db_error)?;
+    apply_limit_template(&mut store).map_err(recovery::db_error)?;
+    let state = store.load_state().map_err(recovery::db_error)?;
+    persist_snapshot(&dir, &state)?;
+    *data.0.lock().unwrap() = Some(store);
+    reset_learner(&learner);
+
+    println!("DEBUG: Wczytano dane!");
+    Ok(state.settings())
+}
+
+// Zwraca zapisane wcześniej transakcje, które mogą być duplikatem dodanej
+#[tauri::command]
+fn add_transaction(
+    data: State<DataStore>,
+    learner: State<Learner>,
+    transaction: Transaction,
+) -> Result<Vec<Transaction>, String> {
+    let candidates = with_store(&data, |store| {
+        if !store.insert_transaction(&transaction)? {
+            return Ok(None);
+        }
+        let nearby = nearby_transactions(store, [transaction.date])?;
+        Ok(Some(duplicates::candidates(&transaction, &nearby).into_iter().cloned().collect()))
+    })?
+    .ok_or_else(|| format!("Transakcja o id {} już istnieje", transaction.id))?;
+    update_learner(&learner, |classifier| classifier.learn(&transaction));
+    Ok(candidates)
+}
+
+// Użytkownik uznał dodaną transakcję za duplikat zapisanej: zostaje tylko `kept`
+#[tauri::command]
+fn merge_transactions(data: State<DataStore>, learner: State<Learner>, merge: DuplicateMerge) -> Result<Transaction, String> {
+    let merged = duplicates::merge(&merge.kept, &merge.duplicate);
+    if with_store(&data, |store| store.merge_duplicates(&[(merged.clone(), merge.duplicate.id)]))? == 0 {
+        return Err(format!("Nie znaleziono transakcji {}", merge.kept.id));
+    }
+    update_learner(&learner, |classifier| {
+        classifier.forget(merge.duplicate.id);
+        classifier.learn(&merged);
+    });
+    Ok(merged)
+}
+
+// Zapisane transakcje z okolicy podanych dat - do szukania duplikatów
+fn nearby_transactions(store: &Store, dates: impl IntoIterator<Item = NaiveDate>) -> rusqlite::Result<Vec<Transaction>> {
+    match duplicates::search_range(dates) {
+        Some((from, to)) => store.transactions_between(from, to),
+        None => Ok(vec![]),
+    }
+}
+
+// Ostatni krok podglądu importu: transakcje bez kategorii z pliku dostają ją z reguł,
+// a wiersze, które mogą powtarzać zapisane transakcje, są zaznaczone
+fn finish_preview(data: &State<DataStore>, mut rows: Vec<ImportRow>) -> Result<Vec<ImportRow>, String> {
+    let dates: Vec<NaiveDate> = rows.iter().filter_map(|row| row.transaction.as_ref()).map(|t| t.date).collect();
+    let (rule_list, categories, nearby) = with_store(data, |store| {
+        Ok((store.rules()?, store.categories()?, nearby_transactions(store, dates)?))
+    })?;
+    let rules = RuleSet::new(&rule_list, &categories);
+    for t in rows.iter_mut().filter_map(|row| row.transaction.as_mut()) {
+        if rules::is_uncategorized(t) {
+            rules.apply(t);
+        }
+    }
+    duplicates::mark(&mut rows, &nearby);
+    Ok(rows)
+}
+
+#[tauri::command]
+fn update_transaction(data: State<DataStore>, learner: State<Learner>, transaction: Transaction) -> Result<(), String> {
+    if with_store(&data, |store| store.update_transaction(&transaction))? {
+        update_learner(&learner, |classifier| classifier.learn(&transaction));
+        Ok(())
+    } else {
+        Err(format!("Nie znaleziono transakcji {}", transaction.id))
+    }
+}
+
+#[tauri::command]
+fn delete_transaction(data: State<DataStore>, learner: State<Learner>, id: TransactionId) -> Result<(), String> {
+    if with_store(&data, |store| store.delete_transaction(id))? {
+        update_learner(&learner, |classifier| classifier.forget(id));
+        Ok(())
+    } else {
+        Err(format!("Nie znaleziono transakcji {}", id))
+    }
+}
+
+// month w formacie "RRRR-MM"
+#[tauri::command]
+fn query_transactions(data: State<DataStore>, month: String) -> Result<Vec<Transaction>, String> {
+    let (from, to) = month_range(&month)?;
+    with_store(&data, |store| store.transactions_between(from, to))
+}
+
+#[tauri::command]
+fn query_year(data: State<DataStore>, year: i32) -> Result<Vec<Transaction>, String> {
+    let from = NaiveDate::from_ymd_opt(year, 1, 1).ok_or("Niepoprawny rok")?;
+    let to = NaiveDate::from_ymd_opt(year + 1, 1, 1).ok_or("Niepoprawny rok")?;
+    with_store(&data, |store| store.transactions_between(from, to))
+}
+
+#[tauri::command]
+fn load_limits(data: State<DataStore>) -> Result<HashMap<String, MonthlyLimitData>, String> {
+    with_store(&data, |store| store.all_limits())
+}
+
+#[tauri::command]
+fn set_month_limit(
+    data: State<DataStore>,
+    month: String,
+    limits: MonthlyLimitData,
+) -> Result<(), String> {
+    month_range(&month)?;
+    with_store(&data, |store| store.set_month_limit(&month, &limits))
+}
+
+// Nowy miesiąc bez limitów dostaje szablon. Zapisany zostaje w miesiącu na stałe,
+// więc późniejsza zmiana szablonu nie zmienia miesięcy, które już się zaczęły.
+fn apply_limit_template(store: &mut Store) -> rusqlite::Result<()> {
+    let month = Local::now().format("%Y-%m").to_string();
+    if let Some(template) = store.budget()?.template {
+        if !store.all_limits()?.contains_key(&month) {
+            store.set_month_limit(&month, &template)?;
+        }
+    }
     Ok(())
 }
 
 #[tauri::command]
-fn load_data(app: AppHandle) -> Result<AppState, String> {
-    println!("DEBUG: Próba wczytania danych...");
-    let path = get_data_path(&app);
+fn load_budget(data: State<DataStore>) -> Result<Budget, String> {
+    with_store(&data, |store| store.budget())
+}
+
+#[tauri::command]
+fn save_budget(data: State<DataStore>, budget: Budget) -> Result<(), String> {
+    with_store(&data, |store| store.save_budget(&budget))
+}
+
+// Kwoty przeniesione do miesiąca z poprzednich, w walucie wyświetlania
+#[tauri::command]
+fn carried_limits(data: State<DataStore>, month: String, currency: CurrencyCode) -> Result<HashMap<String, Money>, String> {
+    let (from, _) = month_range(&month)?;
+    let current = Local::now().format("%Y-%m").to_string();
+    with_store(&data, |store| {
+        let (limits, budget) = (store.all_limits()?, store.budget()?);
+        let months = budget::MonthLimits { limits: &limits, budget: &budget, current: &current };
+        let Some(start) = months.rollover_start(&month) else {
+            return Ok(HashMap::new());
+        };
+        let transactions = store.transactions_between(start, from)?;
+        let rates = store.display_rates(start, from, currency)?;
+        Ok(months.carried(&month, &transactions, &store.categories()?, currency, &rates))
+    })
+}
 
-    if !path.exists() {
-        println!("DEBUG: Brak pliku, zwracam domyślne.");
-        return Ok(AppState::default());
+// Przeniesienia do każdego miesiąca roku (podsumowanie roczne)
+#[tauri::command]
+fn year_carried_limits(data: State<DataStore>, year: i32, currency: CurrencyCode) -> Result<Vec<HashMap<String, Money>>, String> {
+    let keys: Vec<String> = (1..=12).map(|month| format!("{}-{:02}", year, month)).collect();
+    let (end, _) = month_range(&keys[11])?;
+    let current = Local::now().format("%Y-%m").to_string();
+    with_store(&data, |store| {
+        let (limits, budget) = (store.all_limits()?, store.budget()?);
+        let months = budget::MonthLimits { limits: &limits, budget: &budget, current: &current };
+        let Some(start) = keys.iter().filter_map(|key| months.rollover_start(key)).min() else {
+            return Ok(vec![HashMap::new(); 12]);
+        };
+        let transactions = store.transactions_between(start, end)?;
+        let rates = store.display_rates(start, end, currency)?;
+        let categories = store.categories()?;
+        Ok(keys.iter().map(|key| months.carried(key, &transactions, &categories, currency, &rates)).collect())
+    })
+}
+
+#[tauri::command]
+fn load_categories(data: State<DataStore>) -> Result<Vec<Category>, String> {
+    with_store(&data, |store| store.categories())
+}
+
+#[tauri::command]
+fn save_category(data: State<DataStore>, category: Category) -> Result<(), String> {
+    if category.id.is_empty() || category.labels.values().all(|label| label.trim().is_empty()) {
+        return Err("Kategoria musi mieć nazwę".to_string());
+    }
+    let categories = with_store(&data, |store| store.categories())?;
+    if let Some(parent) = &category.parent {
+        if !Category::valid_parent(&categories, &category.id, category.kind, parent) {
+            return Err(format!("Kategoria {} nie może być nadrzędną dla {}", parent, category.id));
+        }
+    }
+    // Zmiana rodzaju rozerwałaby drzewo - podkategorie muszą mieć ten sam rodzaj co rodzic
+    let has_other_kind_children = categories
+        .iter()
+        .any(|c| c.parent.as_deref() == Some(category.id.as_str()) && c.kind != category.kind);
+    if has_other_kind_children {
+        return Err("Podkategorie muszą mieć ten sam rodzaj co kategoria nadrzędna".to_string());
     }
+    with_store(&data, |store| store.save_category(&category))
+}
 
-    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-    let state = serde_json::from_str(&content).unwrap_or_default();
-    
-    println!("DEBUG: Wczytano dane!");
-    Ok(state)
+// Scalenie z własną podkategorią zrobiłoby z niej rodzica samej siebie - odrzucamy,
+// tak samo jak scalanie kategorii różnych rodzajów
+#[tauri::command]
+fn merge_categories(data: State<DataStore>, learner: State<Learner>, from: String, into: String) -> Result<(), String> {
+    let categories = with_store(&data, |store| store.categories())?;
+    let kind_of = |id: &str| categories.iter().find(|c| c.id == id).map(|c| c.kind);
+    if kind_of(&from) != kind_of(&into) {
+        return Err("Można scalać tylko kategorie tego samego rodzaju".to_string());
+    }
+    if from != into && Category::ancestors(&categories, &into).contains(&from.as_str()) {
+        return Err(format!("Nie można scalić kategorii {} z jej podkategorią", from));
+    }
+    if with_store(&data, |store| store.merge_categories(&from, &into))? {
+        reset_learner(&learner);
+        Ok(())
+    } else {
+        Err(format!("Nie można scalić kategorii {} z {}", from, into))
+    }
+}
+
+#[tauri::command]
+fn update_settings(data: State<DataStore>, settings: Settings) -> Result<(), String> {
+    with_store(&data, |store| store.update_settings(&settings))
+}
+
+// Kursy na walutę raportu dla transakcji i limitów z danego roku
+#[tauri::command]
+fn display_rates(data: State<DataStore>, year: i32, currency: CurrencyCode) -> Result<DisplayRates, String> {
+    let from = NaiveDate::from_ymd_opt(year, 1, 1).ok_or("Niepoprawny rok")?;
+    let to = NaiveDate::from_ymd_opt(year + 1, 1, 1).ok_or("Niepoprawny rok")?;
+    with_store(&data, |store| store.display_rates(from, to, currency))
+}
+
+// Import pliku z kursami EBC/NBP wybranego w oknie dialogowym
+#[tauri::command]
+fn import_rates(data: State<DataStore>, path: String) -> Result<ImportReport, String> {
+    let import = rates::parse(&read_file(&path)?)?;
+    with_store(&data, |store| store.save_rates(&import.rates))?;
+    Ok(import.report())
+}
+
+#[tauri::command]
+fn query_rates(data: State<DataStore>, date: NaiveDate) -> Result<Vec<ExchangeRate>, String> {
+    with_store(&data, |store| store.rates_on(date))
+}
+
+// Ręczna korekta kursu; ma pierwszeństwo przed kursem z importu z tego samego dnia
+#[tauri::command]
+fn set_rate(data: State<DataStore>, rate: ExchangeRate) -> Result<(), String> {
+    if rate.base == rate.quote || !rate.rate.is_finite() || rate.rate <= 0.0 {
+        return Err(format!("Niepoprawny kurs {}/{}: {}", rate.base.as_str(), rate.quote.as_str(), rate.rate));
+    }
+    let rate = ExchangeRate {
+        source: RateSource::Manual,
+        ..rate
+    };
+    with_store(&data, |store| store.save_rates(&[rate]))
+}
+
+#[tauri::command]
+fn delete_rate(data: State<DataStore>, rate: ExchangeRate) -> Result<(), String> {
+    if with_store(&data, |store| store.delete_rate(&rate))? {
+        Ok(())
+    } else {
+        Err(format!("Nie znaleziono kursu {}/{} z dnia {}", rate.base.as_str(), rate.quote.as_str(), rate.date))
+    }
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("Nie można odczytać pliku {}: {}", path, e))
+}
+
+// Kreator importu CSV, krok 1: wykryte ustawienia pliku i próbka wierszy
+#[tauri::command]
+fn inspect_csv(path: String, currency: CurrencyCode) -> Result<CsvFile, String> {
+    csv_import::inspect(&read_file(&path)?, currency)
+}
+
+// Krok 1 po zmianie separatora, kodowania albo nagłówka
+#[tauri::command]
+fn reread_csv(path: String, options: CsvOptions) -> Result<CsvFile, String> {
+    Ok(csv_import::reread(&read_file(&path)?, &options))
+}
+
+// Krok 2: wszystkie wiersze zamienione na transakcje albo opisane błędem
+#[tauri::command]
+fn preview_csv(data: State<DataStore>, path: String, options: CsvOptions) -> Result<Vec<ImportRow>, String> {
+    let content = read_file(&path)?;
+    let categories = with_store(&data, |store| store.categories())?;
+    finish_preview(&data, csv_import::preview(&content, &options, &categories)?)
+}
+
+// Wyciąg OFX/QFX albo QIF: od razu podgląd, bo kolumn nie trzeba przypisywać.
+// `currency` to waluta operacji, gdy plik jej nie podaje.
+#[tauri::command]
+fn preview_statement(data: State<DataStore>, path: String, currency: CurrencyCode) -> Result<Vec<ImportRow>, String> {
+    let entries = statement::parse(&read_file(&path)?, currency)?;
+    let (categories, known) = with_store(&data, |store| Ok((store.categories()?, store.external_ids()?)))?;
+    finish_preview(&data, statement::to_rows(entries, &categories, &known))
+}
+
+// Krok 3: zapis poprawnych wierszy z podglądu w jednej partii. Wiersze uznane za duplikaty
+// (`merges`) nie są zapisywane, tylko scalane z transakcjami, które już są w bazie.
+#[tauri::command]
+fn commit_import(
+    data: State<DataStore>,
+    learner: State<Learner>,
+    transactions: Vec<Transaction>,
+    merges: Vec<DuplicateMerge>,
+) -> Result<ImportReport, String> {
+    let merges: Vec<(Transaction, TransactionId)> = merges
+        .iter()
+        .map(|m| (duplicates::merge(&m.kept, &m.duplicate), m.duplicate.id))
+        .collect();
+    let (imported, merged) = with_store(&data, |store| {
+        Ok((store.insert_transactions(&transactions)?, store.merge_duplicates(&merges)?))
+    })?;
+    reset_learner(&learner);
+    Ok(ImportReport {
+        imported,
+        skipped: transactions.len() - imported,
+        merged,
+    })
+}
+
+#[tauri::command]
+fn load_rules(data: State<DataStore>) -> Result<Vec<CategoryRule>, String> {
+    with_store(&data, |store| store.rules())
+}
+
+// Cała lista reguł w kolejności pierwszeństwa
+#[tauri::command]
+fn save_rules(data: State<DataStore>, rules: Vec<CategoryRule>) -> Result<(), String> {
+    let categories = with_store(&data, |store| store.categories())?;
+    for rule in &rules {
+        rules::validate(rule, &categories)?;
+    }
+    with_store(&data, |store| store.save_rules(&rules))
+}
+
+// Reguła, która nadałaby kategorię wpisywanej transakcji (podpowiedź w formularzu)
+#[tauri::command]
+fn match_rule(data: State<DataStore>, transaction: Transaction) -> Result<Option<CategoryRule>, String> {
+    let (rule_list, categories) = with_store(&data, |store| Ok((store.rules()?, store.categories()?)))?;
+    Ok(RuleSet::new(&rule_list, &categories).find(&transaction).cloned())
+}
+
+// Podpowiedź kategorii dla wpisywanej transakcji z klasyfikatora uczonego na historii
+#[tauri::command]
+fn suggest_category(
+    data: State<DataStore>,
+    learner: State<Learner>,
+    transaction: Transaction,
+) -> Result<Option<CategorySuggestion>, String> {
+    let categories = with_store(&data, |store| store.categories())?;
+    let mut guard = learner.0.lock().map_err(|e| e.to_string())?;
+    if guard.is_none() {
+        let history = with_store(&data, |store| store.all_transactions())?;
+        let classifier = Classifier::train(&history);
+        *guard = Some(classifier);
+    }
+    Ok(guard.as_ref().and_then(|classifier| classifier.suggest(&transaction, &categories)))
+}
+
+// "Sprawdź na historii": zapisane transakcje, do których pasuje reguła (jeszcze niezapisana)
+#[tauri::command]
+fn test_rule(data: State<DataStore>, rule: CategoryRule) -> Result<Vec<Transaction>, String> {
+    let (transactions, categories) = with_store(&data, |store| Ok((store.all_transactions()?, store.categories()?)))?;
+    rules::validate(&rule, &categories)?;
+    let rule = CategoryRule { enabled: true, ..rule };
+    let single = [rule];
+    let rules = RuleSet::new(&single, &categories);
+    Ok(transactions.into_iter().rev().filter(|t| rules.find(t).is_some()).collect())
+}
+
+// Ponowne zastosowanie reguł do zapisanych transakcji; zwraca liczbę zmienionych
+#[tauri::command(rename_all = "snake_case")]
+fn reapply_rules(data: State<DataStore>, learner: State<Learner>, only_uncategorized: bool) -> Result<usize, String> {
+    let changed = with_store(&data, |store| {
+        let rule_list = store.rules()?;
+        let categories = store.categories()?;
+        let rules = RuleSet::new(&rule_list, &categories);
+        let changed: Vec<Transaction> = store
+            .all_transactions()?
+            .into_iter()
+            .filter(|t| !only_uncategorized || rules::is_uncategorized(t))
+            .filter_map(|mut t| rules.apply(&mut t).then_some(t))
+            .collect();
+        store.update_categories(&changed)
+    })?;
+    reset_learner(&learner);
+    Ok(changed)
+}
+
+// Wystąpienia szablonów cyklicznych, które już nadeszły, stają się zwykłymi transakcjami
+fn materialize_recurring(store: &mut Store) -> rusqlite::Result<usize> {
+    let mut templates = store.recurring()?;
+    let created = recurring::materialize(&mut templates, Local::now().date_naive());
+    store.save_materialized(&templates, &created)
+}
+
+#[tauri::command]
+fn load_recurring(data: State<DataStore>) -> Result<Vec<RecurringTemplate>, String> {
+    with_store(&data, |store| store.recurring())
+}
+
+// Nowy albo zmieniony szablon. Wystąpienia, które już nadeszły, od razu stają się
+// transakcjami - zwraca ich liczbę.
+#[tauri::command]
+fn save_recurring(data: State<DataStore>, learner: State<Learner>, template: RecurringTemplate) -> Result<usize, String> {
+    let categories = with_store(&data, |store| store.categories())?;
+    recurring::validate(&template, &categories)?;
+    let created = with_store(&data, |store| {
+        store.save_recurring(&template)?;
+        materialize_recurring(store)
+    })?;
+    if created > 0 {
+        reset_learner(&learner);
+    }
+    Ok(created)
+}
+
+#[tauri::command]
+fn delete_recurring(data: State<DataStore>, id: String) -> Result<(), String> {
+    if with_store(&data, |store| store.delete_recurring(&id))? {
+        Ok(())
+    } else {
+        Err(format!("Nie znaleziono szablonu {}", id))
+    }
+}
+
+// Nadchodzące wystąpienia wszystkich szablonów do `until` włącznie
+#[tauri::command]
+fn upcoming_recurring(data: State<DataStore>, until: NaiveDate) -> Result<Vec<Occurrence>, String> {
+    let templates = with_store(&data, |store| store.recurring())?;
+    Ok(recurring::upcoming(&templates, until))
+}
+
+// Propozycje subskrypcji z historii wydatków; przyjęcie to zapis szablonu przez save_recurring
+#[tauri::command]
+fn detect_subscriptions(data: State<DataStore>) -> Result<Vec<Subscription>, String> {
+    let (transactions, templates) = with_store(&data, |store| Ok((store.all_transactions()?, store.recurring()?)))?;
+    let found = subscriptions::detect(&transactions, &templates, Local::now().date_naive());
+    Ok(found)
+}
+
+// Pominięcie albo zmiana jednego nadchodzącego wystąpienia; pusta zmiana przywraca wzorzec
+#[tauri::command]
+fn change_occurrence(data: State<DataStore>, template: String, change: OccurrenceChange) -> Result<(), String> {
+    let mut found = with_store(&data, |store| store.recurring())?
+        .into_iter()
+        .find(|t| t.id == template)
+        .ok_or_else(|| format!("Nie znaleziono szablonu {}", template))?;
+    if !recurring::is_pending(&found, change.date) {
+        return Err(format!("{} nie jest nadchodzącym wystąpieniem", change.date));
+    }
+    if change.amount.is_some_and(|amount| !amount.is_positive()) {
+        return Err("Kwota musi być większa od zera".to_string());
+    }
+    found.changes.retain(|c| c.date != change.date);
+    if !change.is_empty() {
+        found.changes.push(change);
+        found.changes.sort_by_key(|c| c.date);
+    }
+    with_store(&data, |store| store.save_recurring(&found))
+}
+
+#[tauri::command]
+fn clear_data(data: State<DataStore>, learner: State<Learner>) -> Result<(), String> {
+    reset_learner(&learner);
+    with_store(&data, |store| store.clear_data())
+}
+
+// "RRRR-MM" -> [pierwszy dzień miesiąca, pierwszy dzień następnego)
+fn month_range(month: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let from = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .map_err(|_| format!("Niepoprawny miesiąc: {}", month))?;
+    let to = if from.month() == 12 {
+        NaiveDate::from_ymd_opt(from.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(from.year(), from.month() + 1, 1)
+    };
+    Ok((from, to.ok_or("Niepoprawny miesiąc")?))
+}
+
+// Ekran odzyskiwania: każda z opcji zapisuje wybrany stan do nowej bazy
+// i zwraca ustawienia, tak jak load_data
+
+#[tauri::command]
+fn restore_backup(
+    app: AppHandle,
+    data: State<DataStore>,
+    learner: State<Learner>,
+    generation: usize,
+) -> Result<Settings, LoadError> {
+    let dir = get_data_dir(&app);
+    let state = recovery::read_backup(&dir.join(SNAPSHOT_FILE), generation)?;
+    install(&dir, &data, &learner, &state)?;
+    Ok(state.settings())
+}
+
+#[tauri::command]
+fn salvage_data(app: AppHandle, data: State<DataStore>, learner: State<Learner>) -> Result<Settings, LoadError> {
+    let dir = get_data_dir(&app);
+    let state = recovery::salvage_latest(&dir.join(DB_FILE))?;
+    install(&dir, &data, &learner, &state)?;
+    Ok(state.settings())
+}
+
+#[tauri::command]
+fn start_fresh(app: AppHandle, data: State<DataStore>, learner: State<Learner>) -> Result<Settings, LoadError> {
+    let dir = get_data_dir(&app);
+    let state = AppState::default();
+    install(&dir, &data, &learner, &state)?;
+    Ok(state.settings())
+}
+
+fn install(dir: &Path, data: &State<DataStore>, learner: &State<Learner>, state: &AppState) -> Result<(), LoadError> {
+    let db_path = dir.join(DB_FILE);
+    // Plik bazy musi być zamknięty, zanim zostanie odłożony na bok
+    *data.0.lock().unwrap() = None;
+    recovery::set_aside(&db_path)?;
+    let mut store = recovery::open_store(&db_path, &dir.join(SNAPSHOT_FILE))?;
+    store.replace_state(state).map_err(recovery::db_error)?;
+    materialize_recurring(&mut store).map_err(recovery::db_error)?;
+    apply_limit_template(&mut store).map_err(recovery::db_error)?;
+    // Migawka z bazy - razem z transakcjami utworzonymi z szablonów i limitami z szablonu
+    let saved = store.load_state().map_err(recovery::db_error)?;
+    persist_snapshot(dir, &saved)?;
+    *data.0.lock().unwrap() = Some(store);
+    reset_learner(learner);
+    Ok(())
+}
+
+fn persist_snapshot(dir: &Path, state: &AppState) -> Result<(), LoadError> {
+    let json = serde_json::to_string_pretty(state).map_err(|e| LoadError::Io {
+        message: e.to_string(),
+    })?;
+    storage::write_atomic(&dir.join(SNAPSHOT_FILE), json.as_bytes()).map_err(recovery::io_error)
+}
+
+// Eksport całego stanu do JSON-a (ten sam format co migawka, opisany w docs/eksport.md)
+#[tauri::command]
+fn export_json(app: AppHandle, data: State<DataStore>) -> Result<String, String> {
+    let state = with_store(&data, |store| store.load_state())?;
+    let json = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
+    write_export(&app, "json", json.as_bytes())
+}
+
+// Eksport transakcji z wybranego zakresu dat i kategorii do CSV
+#[tauri::command]
+fn export_csv(
+    app: AppHandle,
+    data: State<DataStore>,
+    filter: TransactionFilter,
+    language: String,
+) -> Result<String, String> {
+    let to = filter.to.succ_opt().ok_or("Niepoprawna data")?;
+    let (transactions, categories) =
+        with_store(&data, |store| Ok((store.transactions_between(filter.from, to)?, store.categories()?)))?;
+    let selected: Vec<Transaction> = transactions
+        .into_iter()
+        .filter(|t| filter.matches(t, &categories))
+        .collect();
+    let csv = export::transactions_csv(&selected, &categories, &language);
+    write_export(&app, "csv", csv.as_bytes())
+}
+
+// Zapis eksportu w katalogu exports/ i pokazanie pliku w menedżerze plików
+fn write_export(app: &AppHandle, extension: &str, content: &[u8]) -> Result<String, String> {
+    let exports = get_data_dir(app).join("exports");
+    fs::create_dir_all(&exports).map_err(|e| e.to_string())?;
+    let path = exports.join(format!("cashflow-{}.{}", Local::now().format("%Y%m%d-%H%M%S"), extension));
+    storage::write_atomic(&path, content).map_err(|e| e.to_string())?;
+
+    // Plik już jest zapisany - brak menedżera plików nie jest błędem eksportu
+    let _ = app.opener().reveal_item_in_dir(&path);
+    Ok(path.display().to_string())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![save_data, load_data])
+        .plugin(tauri_plugin_dialog::init())
+        .manage(DataStore::default())
+        .manage(Learner::default())
+        .invoke_handler(tauri::generate_handler![
+            load_data,
+            add_transaction,
+            merge_transactions,
+            load_rules,
+            save_rules,
+            match_rule,
+            suggest_category,
+            test_rule,
+            reapply_rules,
+            load_recurring,
+            save_recurring,
+            delete_recurring,
+            upcoming_recurring,
+            change_occurrence,
+            detect_subscriptions,
+            update_transaction,
+            delete_transaction,
+            query_transactions,
+            query_year,
+            load_limits,
+            set_month_limit,
+            load_budget,
+            save_budget,
+            carried_limits,
+            year_carried_limits,
+            load_categories,
+            save_category,
+            merge_categories,
+            update_settings,
+            display_rates,
+            import_rates,
+            query_rates,
+            set_rate,
+            delete_rate,
+            inspect_csv,
+            reread_csv,
+            preview_csv,
+            preview_statement,
+            commit_import,
+            clear_data,
+            restore_backup,
+            salvage_data,
+            start_fresh,
+            export_json,
+            export_csv
+        ])
         .run(tauri::generate_context!())
         .expect("Błąd uruchamiania aplikacji");
 }
@@ -0,0 +1,260 @@
+use chrono::NaiveDate;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+use crate::models::{
+    Category, CurrencyCode, Money, TransactionId, DEFAULT_CATEGORIES, FALLBACK_CATEGORY,
+    SCHEMA_VERSION,
+};
+
+// Dokumenty sprzed wprowadzenia pola "version" traktujemy jako wersję 1
+const UNVERSIONED: u32 = 1;
+
+#[derive(Debug, PartialEq)]
+pub enum MigrationError {
+    UnsupportedVersion(u32),
+    Malformed { from: u32, reason: String },
+}
+
+type Step = fn(&mut Value) -> Result<(), String>;
+
+// STEPS[i] podnosi dokument z wersji i + 1 do i + 2
+const STEPS: &[Step] = &[v1_typed_dates, v2_minor_units, v3_uuid_ids, v4_kinds, v5_category_ids, v6_category_parents, v7_exchange_rates, v8_external_ids, v9_rules, v10_recurring, v11_budget];
+
+pub fn document_version(doc: &Value) -> u32 {
+    doc.get("version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(UNVERSIONED)
+}
+
+// Podnosi dokument krok po kroku do SCHEMA_VERSION
+pub fn migrate(mut doc: Value) -> Result<Value, MigrationError> {
+    let mut version = document_version(&doc);
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(MigrationError::UnsupportedVersion(version));
+    }
+
+    while version < SCHEMA_VERSION {
+        let step = STEPS[(version - 1) as usize];
+        step(&mut doc).map_err(|reason| MigrationError::Malformed {
+            from: version,
+            reason,
+        })?;
+        version += 1;
+        doc["version"] = Value::from(version);
+    }
+    Ok(doc)
+}
+
+fn transactions_mut(doc: &mut Value) -> Result<&mut Vec<Value>, String> {
+    doc.get_mut("transactions")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| "brak tablicy \"transactions\"".to_string())
+}
+
+// v1 -> v2: daty jako tekst z formularza -> NaiveDate (zapisywana jako RRRR-MM-DD).
+// Puste lub nieczytelne daty i tak nie pojawiały się w żadnym miesiącu, więc transakcja
+// zostaje zachowana z datą 1970-01-01 zamiast blokować odczyt całego pliku.
+fn v1_typed_dates(doc: &mut Value) -> Result<(), String> {
+    for tx in transactions_mut(doc)? {
+        let tx = tx.as_object_mut().ok_or("transakcja nie jest obiektem")?;
+        let raw = tx.get("date").and_then(Value::as_str).unwrap_or_default();
+        let date = parse_legacy_date(raw).unwrap_or_default();
+        tx.insert("date".to_string(), Value::from(date.format("%Y-%m-%d").to_string()));
+    }
+    Ok(())
+}
+
+fn parse_legacy_date(raw: &str) -> Option<NaiveDate> {
+    let raw = raw.trim();
+    // Znacznik czasu "2024-05-01T12:00:00" - liczy się tylko część z datą
+    let date_part = raw.split('T').next().unwrap_or(raw);
+    ["%Y-%m-%d", "%d.%m.%Y", "%m/%d/%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date_part, format).ok())
+}
+
+// v2 -> v3: kwoty f64 -> Money (grosze + kod waluty). Wszystkie kwoty były w walucie
+// z ustawień dokumentu, bo zmiana waluty przeliczała je na miejscu.
+fn v2_minor_units(doc: &mut Value) -> Result<(), String> {
+    let currency = doc
+        .get("currency")
+        .and_then(Value::as_str)
+        .and_then(CurrencyCode::new)
+        .unwrap_or(CurrencyCode::PLN);
+    let to_money = |value: Option<&mut Value>| -> Result<(), String> {
+        let value = value.ok_or("brak kwoty")?;
+        let major = value
+            .as_f64()
+            .ok_or_else(|| format!("kwota nie jest liczbą: {}", value))?;
+        *value = serde_json::to_value(Money::from_major(major, currency)).map_err(|e| e.to_string())?;
+        Ok(())
+    };
+
+    for tx in transactions_mut(doc)? {
+        to_money(tx.get_mut("amount"))?;
+    }
+    if let Some(limits) = doc.get_mut("limits").and_then(Value::as_object_mut) {
+        for data in limits.values_mut() {
+            to_money(data.get_mut("general"))?;
+            if let Some(categories) = data.get_mut("categories").and_then(Value::as_object_mut) {
+                for amount in categories.values_mut() {
+                    to_money(Some(amount))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// v3 -> v4: losowe id u32 -> 128-bitowe TransactionId. Stare id zostaje w dolnych bitach,
+// a powtórzenia (kolizje losowania) dostają kolejne numery wystąpienia, więc żadna
+// transakcja nie ginie, a wynik migracji jest zawsze taki sam.
+fn v3_uuid_ids(doc: &mut Value) -> Result<(), String> {
+    let mut occurrences: HashMap<u64, u64> = HashMap::new();
+    for tx in transactions_mut(doc)? {
+        let tx = tx.as_object_mut().ok_or("transakcja nie jest obiektem")?;
+        let old = tx
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| format!("id nie jest liczbą: {:?}", tx.get("id")))?;
+        let occurrence = occurrences.entry(old).or_insert(0);
+        let id = TransactionId::legacy(old, *occurrence);
+        *occurrence += 1;
+        tx.insert("id".to_string(), Value::from(id.to_string()));
+    }
+    Ok(())
+}
+
+// v4 -> v5: rodzaj transakcji. Wcześniej dało się zapisać tylko wydatki.
+fn v4_kinds(doc: &mut Value) -> Result<(), String> {
+    for tx in transactions_mut(doc)? {
+        let tx = tx.as_object_mut().ok_or("transakcja nie jest obiektem")?;
+        tx.insert("kind".to_string(), Value::from("expense"));
+    }
+    Ok(())
+}
+
+// Id kategorii dla dawnej nazwy z pola `category`. Nazwy kategorii domyślnych (pl albo en)
+// dostają ich id, puste - "uncategorized", pozostałe - Category::legacy_id.
+// Używane też przez aktualizację bazy SQLite do tej samej wersji.
+pub fn legacy_category_id(name: &str) -> String {
+    let name = name.trim();
+    if name.is_empty() {
+        return FALLBACK_CATEGORY.to_string();
+    }
+    DEFAULT_CATEGORIES
+        .iter()
+        .find(|(_, _, pl, en, _, _)| name == *pl || name == *en)
+        .map(|(id, ..)| id.to_string())
+        .unwrap_or_else(|| Category::legacy_id(name))
+}
+
+// v5 -> v6: kategorie jako rekordy z id i nazwami w każdym języku. Transakcje i klucze
+// limitów dostają id zamiast nazwy; nieznane nazwy stają się nowymi kategoriami.
+fn v5_category_ids(doc: &mut Value) -> Result<(), String> {
+    let mut categories: Vec<Value> = DEFAULT_CATEGORIES
+        .iter()
+        .map(|(id, kind, pl, en, color, icon)| category_v6(id, kind.as_str(), pl, en, color, icon))
+        .collect();
+    let mut known: Vec<String> = DEFAULT_CATEGORIES.iter().map(|(id, ..)| id.to_string()).collect();
+    let mut add_legacy = |name: &str, kind: &str| -> String {
+        let id = legacy_category_id(name);
+        if !known.contains(&id) {
+            let name = name.trim();
+            categories.push(category_v6(&id, kind, name, name, "#94a3b8", "\u{1F3F7}"));
+            known.push(id.clone());
+        }
+        id
+    };
+
+    for tx in transactions_mut(doc)? {
+        let tx = tx.as_object_mut().ok_or("transakcja nie jest obiektem")?;
+        let name = tx.get("category").and_then(Value::as_str).unwrap_or_default().to_string();
+        let kind = tx.get("kind").and_then(Value::as_str).unwrap_or("expense").to_string();
+        let id = add_legacy(&name, &kind);
+        tx.insert("category".to_string(), Value::from(id));
+    }
+    if let Some(limits) = doc.get_mut("limits").and_then(Value::as_object_mut) {
+        for data in limits.values_mut() {
+            if let Some(by_name) = data.get_mut("categories").and_then(Value::as_object_mut) {
+                let by_id: Map<String, Value> = std::mem::take(by_name)
+                    .into_iter()
+                    .map(|(name, amount)| (add_legacy(&name, "expense"), amount))
+                    .collect();
+                *by_name = by_id;
+            }
+        }
+    }
+
+    doc.as_object_mut()
+        .ok_or("dokument nie jest obiektem")?
+        .insert("categories".to_string(), Value::from(categories));
+    Ok(())
+}
+
+// Kategoria w formacie z wersji 6 - zapisana wprost, żeby późniejsze zmiany struktury
+// Category nie zmieniały wyniku tego kroku
+fn category_v6(id: &str, kind: &str, pl: &str, en: &str, color: &str, icon: &str) -> Value {
+    json!({
+        "id": id,
+        "kind": kind,
+        "labels": { "pl": pl, "en": en },
+        "color": color,
+        "icon": icon,
+        "archived": false,
+    })
+}
+
+// v6 -> v7: podkategorie. Istniejące kategorie zostają kategoriami głównymi.
+fn v6_category_parents(doc: &mut Value) -> Result<(), String> {
+    let categories = doc
+        .get_mut("categories")
+        .and_then(Value::as_array_mut)
+        .ok_or("brak tablicy \"categories\"")?;
+    for category in categories {
+        let category = category.as_object_mut().ok_or("kategoria nie jest obiektem")?;
+        category.insert("parent".to_string(), Value::Null);
+    }
+    Ok(())
+}
+
+// v7 -> v8: tabela kursów walut. Wcześniej kursy były stałymi w kodzie, więc zaczynamy od pustej.
+fn v7_exchange_rates(doc: &mut Value) -> Result<(), String> {
+    doc.as_object_mut()
+        .ok_or("dokument nie jest obiektem")?
+        .insert("rates".to_string(), Value::from(Vec::<Value>::new()));
+    Ok(())
+}
+
+// v8 -> v9: transakcje mogą mieć identyfikator operacji z banku (external_id). Pole jest
+// opcjonalne, a dotychczasowe transakcje go nie mają - treść dokumentu się nie zmienia.
+fn v8_external_ids(_doc: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+// v9 -> v10: reguły automatycznej kategoryzacji (na początku żadnych) i tagi transakcji
+// (pole opcjonalne, więc transakcje zostają bez zmian)
+fn v9_rules(doc: &mut Value) -> Result<(), String> {
+    doc.as_object_mut()
+        .ok_or("dokument nie jest obiektem")?
+        .insert("rules".to_string(), Value::from(Vec::<Value>::new()));
+    Ok(())
+}
+
+// v10 -> v11: szablony transakcji cyklicznych (na początku żadnych)
+fn v10_recurring(doc: &mut Value) -> Result<(), String> {
+    doc.as_object_mut()
+        .ok_or("dokument nie jest obiektem")?
+        .insert("recurring".to_string(), Value::from(Vec::<Value>::new()));
+    Ok(())
+}
+
+// v11 -> v12: ustawienia budżetu - bez szablonu limitów i bez przenoszenia
+fn v11_budget(doc: &mut Value) -> Result<(), String> {
+    doc.as_object_mut()
+        .ok_or("dokument nie jest obiektem")?
+        .insert("budget".to_string(), json!({ "template": null, "rollover": [] }));
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+
+use crate::csv_import::parse_amount;
+use crate::models::{CurrencyCode, Money};
+use crate::statement::{self, Entry};
+
+// Wyciąg SWIFT MT940: pola ":20:" (numer wyciągu), ":25:" (rachunek), ":60F:" (saldo
+// otwarcia z walutą), ":61:" (operacja) i ":86:" (jej opis)
+pub fn is_mt940(text: &str) -> bool {
+    text.contains(":20:") && text.contains(":25:") && text.contains(":61:")
+}
+
+// Pole ":TAG:" z numerem wiersza, w którym się zaczyna, i kolejnymi wierszami treści
+struct Field<'a> {
+    tag: &'a str,
+    line: usize,
+    lines: Vec<&'a str>,
+}
+
+fn fields(text: &str) -> Vec<Field<'_>> {
+    let mut fields: Vec<Field> = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        let tag = line
+            .strip_prefix(':')
+            .and_then(|rest| rest.split_once(':'))
+            .filter(|(tag, _)| tag.len() <= 3 && tag.starts_with(|c: char| c.is_ascii_digit()));
+        match tag {
+            Some((tag, value)) => fields.push(Field { tag, line: i + 1, lines: vec![value] }),
+            // Koniec wiadomości ("-" albo "-}") i nagłówki bloków SWIFT ("{1:...}")
+            None if line.starts_with('-') || line.starts_with('{') => {}
+            None => {
+                if let Some(field) = fields.last_mut() {
+                    field.lines.push(line);
+                }
+            }
+        }
+    }
+    fields
+}
+
+// Operacje ":61:" ze wszystkich wyciągów w pliku, z opisem z następującego po nich ":86:".
+// Identyfikator łączy rachunek z referencją banku (po "//"); bez niej tworzymy go z treści.
+pub fn parse(text: &str, currency: CurrencyCode) -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut occurrences = HashMap::new();
+    let mut account = String::new();
+    let mut statement_currency = currency;
+    let mut pending: Option<(&Field, Option<&Field>)> = None;
+    let fields = fields(text);
+
+    for field in &fields {
+        match field.tag {
+            "86" => {
+                if let Some((_, details)) = pending.as_mut() {
+                    *details = Some(field);
+                }
+                continue;
+            }
+            "25" => account = field.lines[0].trim().trim_start_matches('/').to_string(),
+            // "C240101PLN1000,00" - znak salda, data i waluta rachunku
+            "60F" | "60M" => {
+                statement_currency = field.lines[0].get(7..10).and_then(CurrencyCode::new).unwrap_or(currency);
+            }
+            _ => {}
+        }
+        if let Some((line, details)) = pending.take() {
+            entries.push(entry(line, details, &account, statement_currency, &mut occurrences));
+        }
+        if field.tag == "61" {
+            pending = Some((field, None));
+        }
+    }
+    if let Some((line, details)) = pending {
+        entries.push(entry(line, details, &account, statement_currency, &mut occurrences));
+    }
+    entries
+}
+
+// Wiersz ":61:" rozłożony na części
+struct StatementLine<'a> {
+    date: Option<NaiveDate>,
+    amount: Option<Money>,
+    customer_reference: &'a str,
+    bank_reference: &'a str,
+}
+
+fn entry(
+    line: &Field,
+    details: Option<&Field>,
+    account: &str,
+    currency: CurrencyCode,
+    occurrences: &mut HashMap<String, usize>,
+) -> Entry {
+    let parsed = statement_line(line.lines[0], currency);
+    let (name, description) = details.map(|d| description(&d.lines)).unwrap_or_default();
+    let title = statement::title(&name, &description);
+
+    let reference = [parsed.bank_reference, parsed.customer_reference]
+        .into_iter()
+        .find(|r| !r.is_empty() && *r != "NONREF");
+    let external_id = match reference {
+        Some(reference) => Some(format!("mt940:{}:{}", account, reference)),
+        None => parsed
+            .date
+            .zip(parsed.amount)
+            .map(|(date, amount)| statement::content_id(&format!("mt940:{}", account), date, amount, &title, occurrences)),
+    };
+    Entry {
+        line: line.line,
+        cells: [Some(line), details].into_iter().flatten().map(|f| f.lines.join(" ")).collect(),
+        date: parsed.date,
+        amount: parsed.amount,
+        title,
+        category: String::new(),
+        external_id,
+        unsupported: None,
+    }
+}
+
+// "2401050104D42,50NTRFNONREF//BANKREF123": data waluty (RRMMDD), opcjonalna data
+// księgowania (MMDD), znak (D, C albo storno RD, RC), opcjonalny trzeci znak kodu waluty,
+// kwota z przecinkiem, typ operacji (4 znaki), referencja klienta i po "//" referencja banku
+fn statement_line(text: &str, currency: CurrencyCode) -> StatementLine<'_> {
+    let mut line = StatementLine { date: None, amount: None, customer_reference: "", bank_reference: "" };
+    let value_date = text.get(..6).and_then(|d| NaiveDate::parse_from_str(d, "%y%m%d").ok());
+    let mut rest = text.get(6..).unwrap_or_default();
+
+    // Data księgowania bez roku: bierzemy rok z daty waluty, pilnując przełomu roku
+    let booking = rest.get(..4).filter(|d| d.chars().all(|c| c.is_ascii_digit()));
+    line.date = match (value_date, booking) {
+        (Some(value), Some(booking)) => {
+            rest = &rest[4..];
+            let (month, day) = (booking[..2].parse().unwrap_or(0), booking[2..].parse().unwrap_or(0));
+            let year = match (value.month(), month) {
+                (1, 12) => value.year() - 1,
+                (12, 1) => value.year() + 1,
+                _ => value.year(),
+            };
+            NaiveDate::from_ymd_opt(year, month, day)
+        }
+        (value, None) => value,
+        (None, Some(_)) => None,
+    };
+
+    let (debit, mark) = match rest.get(..2) {
+        Some("RD") => (false, 2),
+        Some("RC") => (true, 2),
+        _ if rest.starts_with('D') => (true, 1),
+        _ if rest.starts_with('C') => (false, 1),
+        _ => return line,
+    };
+    rest = &rest[mark..];
+    if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        rest = &rest[1..];
+    }
+
+    let length = rest.find(|c: char| !(c.is_ascii_digit() || c == ',')).unwrap_or(rest.len());
+    line.amount = parse_amount(&rest[..length], currency)
+        .map(|a| Money::new(if debit { -a.minor.abs() } else { a.minor.abs() }, currency));
+    let references = rest[length..].get(4..).unwrap_or_default();
+    let (customer, bank) = references.split_once("//").unwrap_or((references, ""));
+    line.customer_reference = customer.trim();
+    line.bank_reference = bank.trim();
+    line
+}
+
+// Opis z ":86:" jako (kontrahent, tytuł). Polskie banki (standard ZBP) dzielą go na
+// podpola "~20".."~25" (tytuł), "~32"-"~33" albo "~27"-"~28" (nazwa kontrahenta) itd.;
+// separatorem jest znak po trzycyfrowym kodzie operacji ("~", "^", "?", "<").
+// Podpola są cięte co 27 znaków, często w środku słowa, więc sklejamy je bez spacji.
+fn description(lines: &[&str]) -> (String, String) {
+    let joined: String = lines.concat();
+    let separator = joined
+        .chars()
+        .nth(3)
+        .filter(|c| joined.get(..3).is_some_and(|code| code.chars().all(|c| c.is_ascii_digit())) && !c.is_alphanumeric());
+    let Some(separator) = separator else {
+        return (String::new(), lines.join(" ").trim().to_string());
+    };
+
+    let mut subfields: HashMap<u32, String> = HashMap::new();
+    for part in joined.split(separator).skip(1) {
+        if let Some(code) = part.get(..2).and_then(|c| c.parse().ok()) {
+            subfields.entry(code).or_default().push_str(&part[2..]);
+        }
+    }
+    let join = |codes: &[u32]| codes.iter().filter_map(|c| subfields.get(c)).cloned().collect::<String>().trim().to_string();
+    let name = Some(join(&[32, 33])).filter(|n| !n.is_empty()).unwrap_or_else(|| join(&[27, 28]));
+    let mut text = join(&[20, 21, 22, 23, 24, 25, 26, 60, 61, 62, 63]);
+    if text.is_empty() {
+        text = join(&[0]);
+    }
+    (name, text)
+}
@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+
+use crate::csv_import::parse_amount;
+use crate::models::CurrencyCode;
+use crate::statement::{self, Entry};
+use crate::xml::{open_lines, tokens, unescape, Token};
+
+// OFX 1.x (SGML z nagłówkiem "OFXHEADER:100") i 2.x (XML); QFX to OFX z dodatkami Quickena
+pub fn is_ofx(text: &str) -> bool {
+    text.contains("OFXHEADER") || text.contains("<OFX>")
+}
+
+// Operacje z sekcji <STMTTRN> wszystkich wyciągów w pliku (rachunki i karty kredytowe).
+// FITID jest unikalny tylko w obrębie rachunku, więc identyfikator łączy go z ACCTID.
+pub fn parse(text: &str, currency: CurrencyCode) -> Vec<Entry> {
+    let lines = open_lines(text, "STMTTRN");
+
+    let mut entries = vec![];
+    let mut statement_currency = currency;
+    let mut account = String::new();
+    let mut record: Option<Vec<(&str, String)>> = None;
+    let mut field = None;
+    for token in tokens(text) {
+        match token {
+            Token::Open("STMTTRN", _) => record = Some(vec![]),
+            Token::Open(name, _) => field = Some(name),
+            Token::Text(value) => match (field, record.as_mut()) {
+                (Some(name), Some(fields)) => fields.push((name, unescape(value))),
+                (Some("CURDEF"), None) => statement_currency = CurrencyCode::new(value.trim()).unwrap_or(currency),
+                (Some("ACCTID"), None) => account = value.trim().to_string(),
+                _ => {}
+            },
+            Token::Close("STMTTRN") => {
+                if let Some(fields) = record.take() {
+                    let line = lines.get(entries.len()).copied().unwrap_or(0);
+                    entries.push(entry(line, &fields, &account, statement_currency));
+                }
+                field = None;
+            }
+            Token::Close(_) => field = None,
+        }
+    }
+    entries
+}
+
+fn entry(line: usize, fields: &[(&str, String)], account: &str, currency: CurrencyCode) -> Entry {
+    let get = |name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.trim())
+            .unwrap_or_default()
+    };
+    let (posted, amount, name, memo, fitid) = (get("DTPOSTED"), get("TRNAMT"), get("NAME"), get("MEMO"), get("FITID"));
+    Entry {
+        line,
+        cells: [posted, amount, name, memo, fitid].map(String::from).to_vec(),
+        date: parse_date(posted),
+        amount: parse_amount(amount, currency),
+        title: statement::title(name, memo),
+        category: String::new(),
+        external_id: (!fitid.is_empty()).then(|| format!("ofx:{}:{}", account, fitid)),
+        unsupported: None,
+    }
+}
+
+// "20240131", "20240131120000", "20240131120000.000[-5:EST]" - liczy się tylko dzień
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..8)?, "%Y%m%d").ok()
+}
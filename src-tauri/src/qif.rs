@@ -0,0 +1,135 @@
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+use crate::csv_import::parse_amount;
+use crate::models::CurrencyCode;
+use crate::statement::{self, Entry};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Transactions,
+    Investments,
+    // Lista kont, kategorii, klas, szablonów - nic do importu
+    Other,
+}
+
+// Rekordy QIF (pola "D", "T", "P"... zakończone "^") z sekcji !Type:Bank, Cash, CCard,
+// Oth A i Oth L. Operacje inwestycyjne (!Type:Invst) trafiają do podglądu jako pominięte.
+pub fn parse(text: &str, currency: CurrencyCode) -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut section = Section::Other;
+    let mut fields: Vec<(char, &str)> = vec![];
+    let mut start = 0;
+    // QIF nie ma identyfikatorów operacji - budujemy je z treści i numeru powtórzenia
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+
+    let mut finish = |fields: &mut Vec<(char, &str)>, section: Section, start: usize| {
+        if !fields.is_empty() && section != Section::Other {
+            entries.push(entry(start, fields, section, currency, &mut occurrences));
+        }
+        fields.clear();
+    };
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('!') {
+            finish(&mut fields, section, start);
+            section = match header.trim() {
+                "Type:Bank" | "Type:Cash" | "Type:CCard" | "Type:Oth A" | "Type:Oth L" => Section::Transactions,
+                "Type:Invst" => Section::Investments,
+                _ => Section::Other,
+            };
+            continue;
+        }
+        if line == "^" {
+            finish(&mut fields, section, start);
+            continue;
+        }
+        if fields.is_empty() {
+            start = i + 1;
+        }
+        let mut chars = line.chars();
+        if let Some(code) = chars.next() {
+            fields.push((code, chars.as_str()));
+        }
+    }
+    // Ostatni rekord bez "^" na końcu pliku
+    finish(&mut fields, section, start);
+    entries
+}
+
+fn entry(
+    line: usize,
+    fields: &[(char, &str)],
+    section: Section,
+    currency: CurrencyCode,
+    occurrences: &mut HashMap<String, usize>,
+) -> Entry {
+    // Przy podziale na kategorie (pola S, E, $) liczy się kwota całej operacji z pola T
+    let get = |code: char| {
+        fields
+            .iter()
+            .find(|(field, _)| *field == code)
+            .map(|(_, value)| value.trim())
+            .unwrap_or_default()
+    };
+    let amount_text = if get('T').is_empty() { get('U') } else { get('T') };
+    let (date, amount) = (parse_date(get('D')), parse_amount(amount_text, currency));
+    let payee = get('P');
+
+    let external_id = date
+        .zip(amount)
+        .map(|(date, amount)| statement::content_id("qif", date, amount, payee, occurrences));
+    Entry {
+        line,
+        cells: fields.iter().map(|(code, value)| format!("{}{}", code, value)).collect(),
+        date,
+        amount,
+        title: statement::title(payee, get('M')),
+        category: category(get('L')),
+        external_id,
+        unsupported: (section == Section::Investments)
+            .then(|| "Operacje inwestycyjne nie są obsługiwane".to_string()),
+    }
+}
+
+// "Jedzenie:Restauracje/Wakacje" -> "Restauracje" (podkategoria bez klasy);
+// "[Oszczędności]" to przelew na inne konto, a nie kategoria
+fn category(text: &str) -> String {
+    if text.starts_with('[') {
+        return String::new();
+    }
+    let without_class = text.split('/').next().unwrap_or_default();
+    without_class.rsplit(':').next().unwrap_or_default().trim().to_string()
+}
+
+// Daty w QIF zależą od programu: "1/31/2024" i "1/31'24" (miesiąc pierwszy, apostrof
+// oznacza lata 2000+), "31.01.2024" (dzień pierwszy) albo "2024-01-31"
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if let Ok(date) = NaiveDate::parse_from_str(&text, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let apostrophe = text.contains('\'');
+    let text = text.replace('\'', "/");
+    let (separator, month_first) = if text.contains('/') {
+        ('/', true)
+    } else if text.contains('.') {
+        ('.', false)
+    } else {
+        ('-', true)
+    };
+    let parts: Vec<&str> = text.split(separator).collect();
+    let [first, second, year] = parts[..] else {
+        return None;
+    };
+    let (first, second, mut year): (u32, u32, i32) = (first.parse().ok()?, second.parse().ok()?, year.parse().ok()?);
+    if year < 100 {
+        year += if apostrophe || year < 50 { 2000 } else { 1900 };
+    }
+    let (month, day) = if month_first { (first, second) } else { (second, first) };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
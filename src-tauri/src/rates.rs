@@ -0,0 +1,213 @@
+use chrono::NaiveDate;
+
+use crate::models::{CurrencyCode, ExchangeRate, ImportReport, RateSource};
+use crate::xml::{attribute, tokens, Token};
+
+// Kursy odczytane z pliku i liczba wpisów, których nie dało się odczytać
+#[derive(Debug, Default, PartialEq)]
+pub struct RateImport {
+    pub rates: Vec<ExchangeRate>,
+    pub skipped: usize,
+}
+
+impl RateImport {
+    pub fn report(&self) -> ImportReport {
+        ImportReport {
+            imported: self.rates.len(),
+            skipped: self.skipped,
+            ..Default::default()
+        }
+    }
+}
+
+// Odczyt pliku z kursami pobranego ze strony EBC lub NBP. Format rozpoznajemy po treści:
+// - XML EBC (eurofxref-daily.xml, eurofxref-hist.xml): kursy walut względem EUR
+// - CSV EBC (eurofxref-hist.csv): kolumna Date i po jednej kolumnie na walutę
+// - XML NBP: tabele A/B z API (ExchangeRatesTable) i z archiwum (tabela_kursow)
+// - CSV NBP (archiwum_tab_a_RRRR.csv): średnie kursy PLN, nagłówki typu "1USD", "100JPY"
+pub fn parse(content: &[u8]) -> Result<RateImport, String> {
+    // Pliki NBP bywają w Windows-1250. Kody walut, daty i liczby są w ASCII,
+    // więc zgubione polskie znaki (w nazwach walut) niczego nie psują.
+    let text = String::from_utf8_lossy(content);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+
+    if text.starts_with('<') {
+        if text.contains("<Cube") {
+            Ok(parse_ecb_xml(text))
+        } else if text.contains("ExchangeRatesTable") || text.contains("tabela_kursow") {
+            Ok(parse_nbp_xml(text))
+        } else {
+            Err("Nieznany format pliku XML z kursami".to_string())
+        }
+    } else {
+        let header = text.lines().next().unwrap_or_default().trim().to_lowercase();
+        if header.starts_with("date") {
+            Ok(parse_ecb_csv(text))
+        } else if header.starts_with("data") {
+            Ok(parse_nbp_csv(text))
+        } else {
+            Err("Nieznany format pliku z kursami".to_string())
+        }
+    }
+}
+
+fn parse_ecb_xml(xml: &str) -> RateImport {
+    let mut import = RateImport::default();
+    let mut day = None;
+    for token in tokens(xml) {
+        let Token::Open("Cube", attrs) = token else {
+            continue;
+        };
+        // <Cube time="..."> otwiera dzień, a <Cube currency="..." rate="..."/> to kurs w tym dniu
+        if let Some(time) = attribute(attrs, "time") {
+            day = parse_date(time);
+            if day.is_none() {
+                import.skipped += 1;
+            }
+        }
+        if let (Some(code), Some(rate)) = (attribute(attrs, "currency"), attribute(attrs, "rate")) {
+            match (day, CurrencyCode::new(code), parse_number(rate)) {
+                (Some(date), Some(quote), Some(rate)) => import.rates.push(ExchangeRate {
+                    date,
+                    base: CurrencyCode::EUR,
+                    quote,
+                    rate,
+                    source: RateSource::Ecb,
+                }),
+                _ => import.skipped += 1,
+            }
+        }
+    }
+    import
+}
+
+fn parse_nbp_xml(xml: &str) -> RateImport {
+    let mut import = RateImport::default();
+    let mut day = None;
+    let mut field = None;
+    let (mut code, mut mid, mut units) = (None, None, Some(1.0));
+    for token in tokens(xml) {
+        match token {
+            Token::Open(name, _) => {
+                if matches!(name, "Rate" | "pozycja") {
+                    (code, mid, units) = (None, None, Some(1.0));
+                }
+                field = Some(name);
+            }
+            Token::Text(text) => match field {
+                Some("EffectiveDate" | "data_publikacji") => day = parse_date(text),
+                Some("Code" | "kod_waluty") => code = CurrencyCode::new(text.trim()),
+                Some("Mid" | "kurs_sredni") => mid = parse_number(text),
+                Some("przelicznik") => units = parse_number(text),
+                _ => {}
+            },
+            Token::Close(name) => {
+                field = None;
+                if matches!(name, "Rate" | "pozycja") {
+                    // Tabela NBP podaje cenę `units` jednostek waluty w złotych
+                    match (day, code, mid, units) {
+                        (Some(date), Some(base), Some(mid), Some(units)) => import.rates.push(ExchangeRate {
+                            date,
+                            base,
+                            quote: CurrencyCode::PLN,
+                            rate: mid / units,
+                            source: RateSource::Nbp,
+                        }),
+                        _ => import.skipped += 1,
+                    }
+                }
+            }
+        }
+    }
+    import
+}
+
+fn parse_ecb_csv(csv: &str) -> RateImport {
+    let mut import = RateImport::default();
+    let mut lines = csv.lines();
+    let header: Vec<Option<CurrencyCode>> = lines
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .map(|column| CurrencyCode::new(column.trim()))
+        .collect();
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        let mut fields = line.split(',');
+        let Some(date) = fields.next().and_then(parse_date) else {
+            import.skipped += 1;
+            continue;
+        };
+        for (quote, value) in header.iter().skip(1).zip(fields) {
+            let value = value.trim();
+            // N/A: waluta nie była jeszcze (albo już) notowana w tym dniu
+            if value.is_empty() || value == "N/A" {
+                continue;
+            }
+            match (quote, parse_number(value)) {
+                (Some(quote), Some(rate)) => import.rates.push(ExchangeRate {
+                    date,
+                    base: CurrencyCode::EUR,
+                    quote: *quote,
+                    rate,
+                    source: RateSource::Ecb,
+                }),
+                _ => import.skipped += 1,
+            }
+        }
+    }
+    import
+}
+
+fn parse_nbp_csv(csv: &str) -> RateImport {
+    let mut import = RateImport::default();
+    let mut lines = csv.lines();
+    // "1USD" -> (USD, 1), "100JPY" -> (JPY, 100); kolumny z numerem tabeli odpadają
+    let header: Vec<Option<(CurrencyCode, f64)>> = lines
+        .next()
+        .unwrap_or_default()
+        .split(';')
+        .map(|column| {
+            let column = column.trim();
+            let split = column.find(|c: char| !c.is_ascii_digit())?;
+            let units: f64 = column[..split].parse().ok()?;
+            Some((CurrencyCode::new(&column[split..])?, units))
+        })
+        .collect();
+    for line in lines {
+        let mut fields = line.split(';');
+        // Archiwum ma pod nagłówkiem wiersze z nazwami walut, a na końcu opis - bez daty
+        let Some(date) = fields.next().and_then(parse_date) else {
+            continue;
+        };
+        for (column, value) in header.iter().skip(1).zip(fields) {
+            let Some((base, units)) = column else {
+                continue;
+            };
+            match parse_number(value) {
+                Some(mid) => import.rates.push(ExchangeRate {
+                    date,
+                    base: *base,
+                    quote: CurrencyCode::PLN,
+                    rate: mid / units,
+                    source: RateSource::Nbp,
+                }),
+                None if value.trim().is_empty() => {}
+                None => import.skipped += 1,
+            }
+        }
+    }
+    import
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    ["%Y-%m-%d", "%Y%m%d", "%d %B %Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+}
+
+// Kurs z przecinkiem albo kropką; zero i liczby ujemne nie są kursem
+fn parse_number(text: &str) -> Option<f64> {
+    let value: f64 = text.trim().replace(',', ".").parse().ok()?;
+    (value.is_finite() && value > 0.0).then_some(value)
+}
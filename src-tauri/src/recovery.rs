@@ -0,0 +1,353 @@
+use chrono::{DateTime, Local};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::db::{self, OpenError, Store};
+use crate::migrations::{self, MigrationError};
+use crate::models::{
+    AppState, BackupInfo, Category, LoadError, MonthlyLimitData, Transaction, SCHEMA_VERSION,
+};
+use crate::storage::{self, BACKUP_GENERATIONS};
+
+const QUARANTINE_MARKER: &str = ".corrupt-";
+
+pub fn io_error(e: std::io::Error) -> LoadError {
+    LoadError::Io {
+        message: e.to_string(),
+    }
+}
+
+pub fn db_error(e: rusqlite::Error) -> LoadError {
+    LoadError::Io {
+        message: e.to_string(),
+    }
+}
+
+// Otwarcie bazy SQLite. Przy pierwszym uruchomieniu po aktualizacji przenosi do niej
+// dane ze starego pliku JSON (z migracjami); uszkodzona baza trafia do kwarantanny.
+pub fn open_store(db_path: &Path, json_path: &Path) -> Result<Store, LoadError> {
+    let (mut store, fresh) = match Store::open(db_path) {
+        Ok(opened) => opened,
+        Err(OpenError::UnsupportedVersion(found)) => {
+            return Err(LoadError::UnsupportedVersion {
+                found,
+                supported: SCHEMA_VERSION,
+            })
+        }
+        Err(OpenError::Corrupted(reason)) => {
+            let quarantined = quarantine(db_path).map_err(io_error)?;
+            return Err(LoadError::Corrupted {
+                quarantined: quarantined.display().to_string(),
+                reason,
+                backups: list_backups(json_path),
+            });
+        }
+        Err(OpenError::Sqlite(e)) => return Err(db_error(e)),
+    };
+
+    if fresh {
+        let legacy = if json_path.exists() { Some(load_or_quarantine(json_path)?) } else { None };
+        store.initialize(legacy.as_ref()).map_err(db_error)?;
+    }
+    Ok(store)
+}
+
+enum ParseError {
+    Unreadable(String),
+    Unsupported(u32),
+}
+
+// JSON -> migracje do bieżącej wersji -> AppState
+fn parse_document(content: &str) -> Result<AppState, ParseError> {
+    let doc: Value =
+        serde_json::from_str(content).map_err(|e| ParseError::Unreadable(e.to_string()))?;
+    let doc = migrations::migrate(doc).map_err(|e| match e {
+        MigrationError::UnsupportedVersion(found) => ParseError::Unsupported(found),
+        MigrationError::Malformed { from, reason } => {
+            ParseError::Unreadable(format!("migracja z wersji {}: {}", from, reason))
+        }
+    })?;
+    let mut state: AppState =
+        serde_json::from_value(doc).map_err(|e| ParseError::Unreadable(e.to_string()))?;
+    state.resolve_duplicate_ids();
+    state.ensure_categories();
+    Ok(state)
+}
+
+// Wczytanie pliku danych. Uszkodzony plik nie jest nadpisywany - przenosimy go na bok
+// i zwracamy błąd, żeby użytkownik sam zdecydował co dalej. Plik z nowszej wersji
+// aplikacji zostaje na miejscu.
+pub fn load_or_quarantine(path: &Path) -> Result<AppState, LoadError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(AppState::default()),
+        Err(e) => return Err(io_error(e)),
+    };
+
+    match parse_document(&content) {
+        Ok(state) => Ok(state),
+        Err(ParseError::Unsupported(found)) => Err(LoadError::UnsupportedVersion {
+            found,
+            supported: SCHEMA_VERSION,
+        }),
+        Err(ParseError::Unreadable(reason)) => {
+            let quarantined = quarantine(path).map_err(io_error)?;
+            Err(LoadError::Corrupted {
+                quarantined: quarantined.display().to_string(),
+                reason,
+                backups: list_backups(path),
+            })
+        }
+    }
+}
+
+// Przed nadpisaniem danych z ekranu odzyskiwania odkładamy bieżący plik (np. z nowszej wersji)
+pub fn set_aside(path: &Path) -> Result<(), LoadError> {
+    if path.exists() {
+        quarantine(path).map_err(io_error)?;
+    }
+    Ok(())
+}
+
+fn quarantine(path: &Path) -> std::io::Result<PathBuf> {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    // Licznik na końcu odróżnia kilka plików odłożonych w tej samej sekundzie
+    let target = (1..)
+        .map(|n| {
+            path.with_file_name(format!(
+                "{}{}{}-{}.{}",
+                stem, QUARANTINE_MARKER, stamp, n, extension
+            ))
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_default();
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+fn latest_quarantined(path: &Path) -> Option<PathBuf> {
+    let dir = path.parent()?;
+    let prefix = format!(
+        "{}{}",
+        path.file_stem().unwrap_or_default().to_string_lossy(),
+        QUARANTINE_MARKER
+    );
+    // Znacznik czasu w nazwie sortuje się leksykograficznie
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy().starts_with(&prefix))
+                .unwrap_or(false)
+        })
+        .max()
+}
+
+// Generacja 0 to sama migawka JSON zapisywana przy starcie, 1..N to jej poprzednie wersje
+fn generation_path(path: &Path, generation: usize) -> PathBuf {
+    if generation == 0 {
+        path.to_path_buf()
+    } else {
+        storage::backup_path(path, generation)
+    }
+}
+
+// Tylko kopie, które da się poprawnie odczytać - od najnowszej
+pub fn list_backups(path: &Path) -> Vec<BackupInfo> {
+    (0..=BACKUP_GENERATIONS)
+        .filter_map(|generation| {
+            let state = read_backup(path, generation).ok()?;
+            let saved_at = fs::metadata(generation_path(path, generation))
+                .and_then(|m| m.modified())
+                .map(|t| DateTime::<Local>::from(t).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            Some(BackupInfo {
+                generation,
+                saved_at,
+                transactions: state.transactions.len(),
+            })
+        })
+        .collect()
+}
+
+pub fn read_backup(path: &Path, generation: usize) -> Result<AppState, LoadError> {
+    let unreadable = LoadError::BackupUnreadable { generation };
+    let content =
+        fs::read_to_string(generation_path(path, generation)).map_err(|_| unreadable.clone())?;
+    parse_document(&content).map_err(|_| unreadable)
+}
+
+// Odzyskuje dane z najnowszego pliku w kwarantannie - bazy lub starego JSON-a
+// (oba mają ten sam rdzeń nazwy, więc wystarczy jedno wyszukiwanie)
+pub fn salvage_latest(path: &Path) -> Result<AppState, LoadError> {
+    let source = latest_quarantined(path).ok_or(LoadError::NothingToSalvage)?;
+
+    let (state, _) = if source.extension().is_some_and(|e| e == "db") {
+        db::salvage(&source).map_err(db_error)?
+    } else {
+        let content = fs::read_to_string(&source).map_err(io_error)?;
+        salvage(&content)
+    };
+    Ok(state)
+}
+
+// Pobłażliwy odczyt: bierzemy każdą transakcję i każdy miesiąc limitów, które da się
+// osobno zmigrować i sparsować. Zwraca odzyskany stan i liczbę pominiętych transakcji.
+pub fn salvage(content: &str) -> (AppState, usize) {
+    let mut state = AppState::default();
+    let mut skipped = 0;
+
+    let (version, transactions) = match serde_json::from_str::<Value>(content) {
+        Ok(root) => {
+            let version = migrations::document_version(&root);
+            if let Some(limits) = root.get("limits").and_then(Value::as_object) {
+                for (month, data) in limits {
+                    let single = json!({ "version": version, "transactions": [], "limits": { month: data } });
+                    if let Some(data) = migrate_fragment(single)
+                        .and_then(|doc| doc["limits"].get(month).cloned())
+                        .and_then(|data| serde_json::from_value::<MonthlyLimitData>(data).ok())
+                    {
+                        state.limits.insert(month.clone(), data);
+                    }
+                }
+            }
+            // Lista kategorii istnieje od wersji 6; starsze pliki dostaną ją z migracji nazw
+            if let Some(list) = root.get("categories").and_then(Value::as_array).filter(|_| version >= 6) {
+                let categories: Vec<Category> = list
+                    .iter()
+                    .filter_map(|category| {
+                        let single = json!({ "version": version, "transactions": [], "limits": {}, "categories": [category] });
+                        migrate_fragment(single)
+                            .and_then(|mut doc| doc["categories"].get_mut(0).map(Value::take))
+                            .and_then(|category| serde_json::from_value(category).ok())
+                    })
+                    .collect();
+                if !categories.is_empty() {
+                    state.categories = categories;
+                }
+            }
+            // Tabela kursów istnieje od wersji 8
+            if let Some(list) = root.get("rates").and_then(Value::as_array).filter(|_| version >= 8) {
+                state.rates = list
+                    .iter()
+                    .filter_map(|rate| {
+                        let single = json!({ "version": version, "transactions": [], "limits": {}, "categories": [], "rates": [rate] });
+                        migrate_fragment(single)
+                            .and_then(|mut doc| doc["rates"].get_mut(0).map(Value::take))
+                            .and_then(|rate| serde_json::from_value(rate).ok())
+                    })
+                    .collect();
+            }
+            if let Some(theme) = root.get("theme").and_then(Value::as_str) {
+                state.theme = theme.to_string();
+            }
+            if let Some(language) = root.get("language").and_then(Value::as_str) {
+                state.language = language.to_string();
+            }
+            if let Some(currency) = root.get("currency").and_then(Value::as_str) {
+                state.currency = currency.to_string();
+            }
+            let transactions = root
+                .get("transactions")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            (version, transactions)
+        }
+        // Najczęstszy przypadek: plik ucięty w połowie zapisu
+        Err(_) => (
+            scan_version(content),
+            scan_array_objects(content, "\"transactions\""),
+        ),
+    };
+
+    for value in transactions {
+        let single = json!({ "version": version, "transactions": [value], "limits": {} });
+        match migrate_fragment(single)
+            .and_then(|mut doc| doc["transactions"].get_mut(0).map(Value::take))
+            .and_then(|tx| serde_json::from_value::<Transaction>(tx).ok())
+        {
+            Some(tx) => state.transactions.push(tx),
+            None => skipped += 1,
+        }
+    }
+    // Każda transakcja była migrowana osobno, więc kolizje starych id trzeba rozwiązać tutaj,
+    // a kategorie odtworzyć z ich id (lista kategorii z pliku mogła nie przetrwać)
+    state.resolve_duplicate_ids();
+    state.ensure_categories();
+    (state, skipped)
+}
+
+fn migrate_fragment(doc: Value) -> Option<Value> {
+    migrations::migrate(doc).ok()
+}
+
+fn scan_version(content: &str) -> u32 {
+    content
+        .find("\"version\"")
+        .and_then(|pos| content[pos..].split_once(':'))
+        .and_then(|(_, rest)| {
+            let digits: String = rest
+                .trim_start()
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect();
+            digits.parse().ok()
+        })
+        .unwrap_or(1)
+}
+
+// Wyciąga kompletne obiekty {...} z tablicy występującej po kluczu `key`
+fn scan_array_objects(content: &str, key: &str) -> Vec<Value> {
+    let Some(key_pos) = content.find(key) else {
+        return vec![];
+    };
+    let Some(array_start) = content[key_pos..].find('[').map(|i| key_pos + i + 1) else {
+        return vec![];
+    };
+
+    let mut objects = vec![];
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut object_start = 0;
+
+    for (i, c) in content[array_start..].char_indices() {
+        let i = array_start + i;
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => {
+                if depth == 0 {
+                    object_start = i;
+                }
+                depth += 1;
+            }
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    if let Ok(value) = serde_json::from_str(&content[object_start..=i]) {
+                        objects.push(value);
+                    }
+                }
+            }
+            ']' if depth == 0 => break,
+            _ => {}
+        }
+    }
+    objects
+}
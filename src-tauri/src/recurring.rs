@@ -0,0 +1,158 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+use crate::models::{
+    Category, Frequency, Occurrence, RecurrenceEnd, RecurringTemplate, Schedule, Transaction, TransactionId,
+};
+
+// Prefiks identyfikatora transakcji utworzonej z szablonu: "recurring:{szablon}:{data}".
+// Unikalny external_id chroni przed utworzeniem tego samego wystąpienia dwa razy.
+const EXTERNAL_PREFIX: &str = "recurring";
+
+// Kolejne daty wystąpień, od pierwszego; bez końca, jeśli harmonogram go nie ma
+pub fn dates(schedule: &Schedule) -> impl Iterator<Item = NaiveDate> + '_ {
+    let limit = match schedule.end {
+        RecurrenceEnd::Count { count } => count as usize,
+        _ => usize::MAX,
+    };
+    (0u32..)
+        .map_while(move |k| nth_period(schedule, k.checked_mul(schedule.interval.max(1))?))
+        .flatten()
+        .filter(move |date| *date >= schedule.start)
+        .take_while(move |date| match schedule.end {
+            RecurrenceEnd::Until { date: until } => *date <= until,
+            _ => true,
+        })
+        .take(limit)
+}
+
+// Data wystąpienia `periods` okresów po starcie. Some(None): w tym okresie nie ma takiego dnia
+// (piąty wtorek); None: data poza zakresem kalendarza - dalej już nic nie ma.
+fn nth_period(schedule: &Schedule, periods: u32) -> Option<Option<NaiveDate>> {
+    let start = schedule.start;
+    let months = match schedule.frequency {
+        Frequency::Daily => return start.checked_add_signed(Duration::days(periods as i64)).map(Some),
+        Frequency::Weekly => return start.checked_add_signed(Duration::weeks(periods as i64)).map(Some),
+        Frequency::Monthly => periods,
+        Frequency::Yearly => periods.checked_mul(12)?,
+    };
+    let month = start.with_day(1)?.checked_add_months(Months::new(months))?;
+    Some(match schedule.nth_weekday {
+        Some(nth) => nth_weekday_of(month, nth.nth, nth.weekday),
+        None => Some(clamped_day(month, start.day())),
+    })
+}
+
+// Dzień miesiąca, a gdy miesiąc jest krótszy - jego ostatni dzień
+fn clamped_day(month: NaiveDate, day: u32) -> NaiveDate {
+    (1..=day).rev().find_map(|d| month.with_day(d)).unwrap_or(month)
+}
+
+fn nth_weekday_of(month: NaiveDate, nth: i8, weekday: chrono::Weekday) -> Option<NaiveDate> {
+    if nth < 0 {
+        let last = clamped_day(month, 31);
+        let back = (last.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+        return Some(last - Duration::days(back as i64));
+    }
+    NaiveDate::from_weekday_of_month_opt(month.year(), month.month(), weekday, nth as u8)
+}
+
+// Transakcja z wystąpienia w danym dniu, ze zmianami tego wystąpienia (poza pominięciem)
+pub fn planned(template: &RecurringTemplate, date: NaiveDate) -> Transaction {
+    let change = template.changes.iter().find(|c| c.date == date);
+    Transaction {
+        id: TransactionId::generate(),
+        title: change.and_then(|c| c.title.clone()).unwrap_or_else(|| template.title.clone()),
+        amount: change.and_then(|c| c.amount).unwrap_or(template.amount),
+        kind: template.kind,
+        date,
+        category: change.and_then(|c| c.category.clone()).unwrap_or_else(|| template.category.clone()),
+        external_id: Some(format!("{}:{}:{}", EXTERNAL_PREFIX, template.id, date)),
+        tags: template.tags.clone(),
+    }
+}
+
+// Czy transakcja powstała z szablonu transakcji cyklicznej
+pub fn is_materialized(t: &Transaction) -> bool {
+    t.external_id.as_deref().is_some_and(|id| id.starts_with(&format!("{}:", EXTERNAL_PREFIX)))
+}
+
+pub fn is_skipped(template: &RecurringTemplate, date: NaiveDate) -> bool {
+    template.changes.iter().any(|c| c.date == date && c.skip)
+}
+
+// Wystąpienia, które nadeszły (do `today` włącznie), a nie są jeszcze transakcjami.
+// Przesuwa `materialized_until` szablonów; zwraca transakcje do zapisania.
+pub fn materialize(templates: &mut [RecurringTemplate], today: NaiveDate) -> Vec<Transaction> {
+    let mut created = vec![];
+    for template in templates {
+        let due: Vec<NaiveDate> = pending(template).take_while(|date| *date <= today).collect();
+        created.extend(due.iter().filter(|date| !is_skipped(template, **date)).map(|date| planned(template, *date)));
+        if let Some(last) = due.last() {
+            template.materialized_until = Some(*last);
+            // Zmiany wystąpień, które już minęły, nie są potrzebne
+            template.changes.retain(|c| c.date > *last);
+        }
+    }
+    created
+}
+
+// Wystąpienia, które jeszcze nie stały się transakcjami
+fn pending(template: &RecurringTemplate) -> impl Iterator<Item = NaiveDate> + '_ {
+    let after = template.materialized_until;
+    dates(&template.schedule).filter(move |date| after.is_none_or(|after| *date > after))
+}
+
+// Czy zmiana dotyczy wystąpienia, które jeszcze nie stało się transakcją
+pub fn is_pending(template: &RecurringTemplate, date: NaiveDate) -> bool {
+    pending(template).take_while(|d| *d <= date).any(|d| d == date)
+}
+
+// Nadchodzące wystąpienia wszystkich szablonów do `until` włącznie, od najbliższego;
+// pominięte też, żeby można było je przywrócić
+pub fn upcoming(templates: &[RecurringTemplate], until: NaiveDate) -> Vec<Occurrence> {
+    let mut list: Vec<Occurrence> = templates
+        .iter()
+        .flat_map(|template| {
+            pending(template).take_while(move |date| *date <= until).map(move |date| Occurrence {
+                template: template.id.clone(),
+                date,
+                transaction: planned(template, date),
+                skipped: is_skipped(template, date),
+            })
+        })
+        .collect();
+    list.sort_by_key(|o| o.date);
+    list
+}
+
+// Sprawdzenie szablonu przed zapisem; błąd po polsku do pokazania w formularzu
+pub fn validate(template: &RecurringTemplate, categories: &[Category]) -> Result<(), String> {
+    if template.title.trim().is_empty() {
+        return Err("Szablon musi mieć tytuł".to_string());
+    }
+    if !template.amount.is_positive() {
+        return Err("Kwota musi być większa od zera".to_string());
+    }
+    if !categories.iter().any(|c| c.id == template.category && c.kind == template.kind) {
+        return Err(format!("Nie znaleziono kategorii {}", template.category));
+    }
+    let schedule = &template.schedule;
+    if schedule.interval == 0 {
+        return Err("Odstęp między wystąpieniami musi być większy od zera".to_string());
+    }
+    if let Some(nth) = schedule.nth_weekday {
+        if !matches!(schedule.frequency, Frequency::Monthly | Frequency::Yearly) {
+            return Err("Dzień tygodnia miesiąca wymaga powtarzania co miesiąc lub co rok".to_string());
+        }
+        if !(1..=5).contains(&nth.nth) && nth.nth != -1 {
+            return Err(format!("Niepoprawny numer dnia tygodnia: {}", nth.nth));
+        }
+    }
+    match schedule.end {
+        RecurrenceEnd::Until { date } if date < schedule.start => {
+            Err("Data końca jest wcześniejsza niż data startu".to_string())
+        }
+        RecurrenceEnd::Count { count: 0 } => Err("Liczba wystąpień musi być większa od zera".to_string()),
+        _ => Ok(()),
+    }
+}
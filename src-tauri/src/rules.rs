@@ -0,0 +1,108 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::models::{Category, CategoryRule, Transaction, TransactionKind, FALLBACK_CATEGORY};
+use crate::statement::INCOME_FALLBACK;
+
+// Reguła gotowa do sprawdzania: ze skompilowanym wyrażeniem i rodzajem swojej kategorii
+struct Compiled<'a> {
+    rule: &'a CategoryRule,
+    regex: Option<Regex>,
+    kind: TransactionKind,
+}
+
+// Włączone reguły w kolejności pierwszeństwa. Reguły z błędnym wyrażeniem albo
+// nieistniejącą kategorią są pomijane (zapis przez `validate` do tego nie dopuszcza).
+pub struct RuleSet<'a> {
+    rules: Vec<Compiled<'a>>,
+}
+
+impl<'a> RuleSet<'a> {
+    pub fn new(rules: &'a [CategoryRule], categories: &[Category]) -> RuleSet<'a> {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled && !rule.conditions.is_empty())
+            .filter_map(|rule| {
+                let kind = categories.iter().find(|c| c.id == rule.category)?.kind;
+                let regex = compile(&rule.conditions.title_regex).ok()?;
+                Some(Compiled { rule, regex, kind })
+            })
+            .collect();
+        RuleSet { rules }
+    }
+
+    // Pierwsza reguła, która pasuje do transakcji
+    pub fn find(&self, t: &Transaction) -> Option<&'a CategoryRule> {
+        self.rules.iter().find(|c| c.matches(t)).map(|c| c.rule)
+    }
+
+    // Nadaje transakcji kategorię i tagi pierwszej pasującej reguły; zwraca, czy coś zmieniła
+    pub fn apply(&self, t: &mut Transaction) -> bool {
+        let Some(rule) = self.find(t) else {
+            return false;
+        };
+        let mut changed = t.category != rule.category;
+        t.category = rule.category.clone();
+        for tag in &rule.tags {
+            if !t.tags.contains(tag) {
+                t.tags.push(tag.clone());
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+impl Compiled<'_> {
+    fn matches(&self, t: &Transaction) -> bool {
+        let conditions = &self.rule.conditions;
+        let title = t.title.to_lowercase();
+        let contains = conditions.title_contains.trim().to_lowercase();
+        let wanted_payee = conditions.payee.trim().to_lowercase();
+
+        t.kind == self.kind
+            && (contains.is_empty() || title.contains(&contains))
+            && self.regex.as_ref().is_none_or(|regex| regex.is_match(&t.title))
+            && (wanted_payee.is_empty() || payee(&title).trim() == wanted_payee)
+            && conditions.min_amount.is_none_or(|min| min.currency == t.amount.currency && t.amount.minor >= min.minor)
+            && conditions.max_amount.is_none_or(|max| max.currency == t.amount.currency && t.amount.minor <= max.minor)
+    }
+}
+
+// Kontrahent z tytułu operacji z wyciągu ("Orange Polska – Abonament") albo cały tytuł
+pub fn payee(title: &str) -> &str {
+    title.split(" – ").next().unwrap_or(title)
+}
+
+// Transakcja bez konkretnej kategorii - taką reguły mogą przypisać przy imporcie
+pub fn is_uncategorized(t: &Transaction) -> bool {
+    t.category == FALLBACK_CATEGORY || t.category == INCOME_FALLBACK
+}
+
+// Sprawdzenie reguły przed zapisem; błąd po polsku do pokazania w formularzu
+pub fn validate(rule: &CategoryRule, categories: &[Category]) -> Result<(), String> {
+    let conditions = &rule.conditions;
+    if conditions.is_empty() {
+        return Err("Reguła musi mieć co najmniej jeden warunek".to_string());
+    }
+    if !categories.iter().any(|c| c.id == rule.category) {
+        return Err(format!("Nie znaleziono kategorii {}", rule.category));
+    }
+    compile(&conditions.title_regex).map_err(|e| format!("Niepoprawne wyrażenie regularne: {}", e))?;
+    if let (Some(min), Some(max)) = (conditions.min_amount, conditions.max_amount) {
+        if min.currency != max.currency {
+            return Err("Granice kwoty muszą być w tej samej walucie".to_string());
+        }
+        if min.minor > max.minor {
+            return Err("Kwota minimalna jest większa od maksymalnej".to_string());
+        }
+    }
+    Ok(())
+}
+
+// Puste wyrażenie to brak warunku; wielkość liter nie ma znaczenia, jak w pozostałych warunkach
+fn compile(pattern: &str) -> Result<Option<Regex>, regex::Error> {
+    if pattern.trim().is_empty() {
+        return Ok(None);
+    }
+    RegexBuilder::new(pattern).case_insensitive(true).build().map(Some)
+}
@@ -0,0 +1,152 @@
+use chrono::NaiveDate;
+use std::collections::{HashMap, HashSet};
+
+use crate::encoding;
+use crate::models::{
+    Category, CurrencyCode, ImportRow, Money, Transaction, TransactionId, TransactionKind,
+    FALLBACK_CATEGORY,
+};
+use crate::{camt, mt940, ofx, qif};
+
+// Kategoria dla wpływów bez rozpoznanej kategorii (wydatki trafiają do FALLBACK_CATEGORY)
+pub const INCOME_FALLBACK: &str = "other_income";
+
+// Operacja z wyciągu bankowego przed zamianą na transakcję
+#[derive(Debug, Default, PartialEq)]
+pub struct Entry {
+    // Numer wiersza w pliku, w którym zaczyna się operacja (od 1)
+    pub line: usize,
+    // Surowe pola do pokazania w podglądzie
+    pub cells: Vec<String>,
+    pub date: Option<NaiveDate>,
+    // Kwota ze znakiem: ujemna obciąża rachunek
+    pub amount: Option<Money>,
+    pub title: String,
+    pub category: String,
+    pub external_id: Option<String>,
+    // Operacja, której nie importujemy (np. inwestycyjna) - z powodem
+    pub unsupported: Option<String>,
+}
+
+// Wyciąg OFX/QFX, QIF, MT940 albo camt.053; format rozpoznajemy po treści. `currency`
+// to waluta operacji, gdy plik jej nie podaje (QIF nigdy jej nie ma).
+pub fn parse(content: &[u8], currency: CurrencyCode) -> Result<Vec<Entry>, String> {
+    let text = encoding::decode(content, encoding::detect(content));
+    if ofx::is_ofx(&text) {
+        Ok(ofx::parse(&text, currency))
+    } else if camt::is_camt(&text) {
+        Ok(camt::parse(&text, currency))
+    } else if mt940::is_mt940(&text) {
+        Ok(mt940::parse(&text, currency))
+    } else if text.trim_start().starts_with('!') {
+        Ok(qif::parse(&text, currency))
+    } else {
+        Err("Nieznany format wyciągu".to_string())
+    }
+}
+
+// Wiersze podglądu. Operacje, których identyfikator z banku jest już w bazie (`known`)
+// albo powtarza się w pliku, są pomijane jako błędne.
+pub fn to_rows(entries: Vec<Entry>, categories: &[Category], known: &HashSet<String>) -> Vec<ImportRow> {
+    let mut seen = HashSet::new();
+    entries
+        .into_iter()
+        .map(|entry| {
+            let duplicate = entry.external_id.as_ref().and_then(|id| {
+                if known.contains(id) {
+                    Some("Operacja została już zaimportowana".to_string())
+                } else if !seen.insert(id.clone()) {
+                    Some("Operacja powtarza się w pliku".to_string())
+                } else {
+                    None
+                }
+            });
+            let result = match (entry.unsupported.or(duplicate), entry.amount, entry.date) {
+                (Some(reason), _, _) => Err(reason),
+                (None, None, _) => Err("Niepoprawna kwota".to_string()),
+                (None, _, None) => Err("Niepoprawna data".to_string()),
+                (None, Some(amount), Some(date)) => transaction(&entry.title, amount, date, &entry.category, categories)
+                    .map(|t| Transaction { external_id: entry.external_id, ..t }),
+            };
+            let (transaction, error) = match result {
+                Ok(t) => (Some(t), None),
+                Err(e) => (None, Some(e)),
+            };
+            ImportRow {
+                line: entry.line,
+                cells: entry.cells,
+                transaction,
+                error,
+                duplicate_of: None,
+            }
+        })
+        .collect()
+}
+
+// Transakcja z operacji na wyciągu. Rodzaj wynika z rozpoznanej kategorii, a bez niej
+// ze znaku kwoty; zapisana kwota jest zawsze dodatnia.
+pub fn transaction(
+    title: &str,
+    amount: Money,
+    date: NaiveDate,
+    category: &str,
+    categories: &[Category],
+) -> Result<Transaction, String> {
+    if title.is_empty() {
+        return Err("Brak tytułu".to_string());
+    }
+    if amount.minor == 0 {
+        return Err("Kwota nie może być zerowa".to_string());
+    }
+    let (kind, category) = match find_category(categories, category) {
+        Some(c) => (c.kind, c.id.clone()),
+        None if amount.minor < 0 => (TransactionKind::Expense, FALLBACK_CATEGORY.to_string()),
+        None => (TransactionKind::Income, INCOME_FALLBACK.to_string()),
+    };
+    Ok(Transaction {
+        id: TransactionId::generate(),
+        title: title.to_string(),
+        amount: amount.abs(),
+        kind,
+        date,
+        category,
+        external_id: None,
+        tags: vec![],
+    })
+}
+
+// Identyfikator dla operacji, której bank nie nadał numeru: z daty, kwoty i opisu oraz
+// numeru powtórzenia, żeby dwie identyczne operacje z jednego pliku się nie zlały
+pub fn content_id(
+    prefix: &str,
+    date: NaiveDate,
+    amount: Money,
+    text: &str,
+    occurrences: &mut HashMap<String, usize>,
+) -> String {
+    let key = format!("{}:{}:{}:{}", prefix, date, amount.minor, text);
+    let occurrence = occurrences.entry(key.clone()).or_insert(0);
+    *occurrence += 1;
+    format!("{}:{}", key, occurrence)
+}
+
+// Tytuł z nazwy kontrahenta i opisu operacji; opis dopisujemy, jeśli coś wnosi
+pub fn title(payee: &str, memo: &str) -> String {
+    let (payee, memo) = (payee.trim(), memo.trim());
+    if payee.is_empty() || memo.is_empty() || payee == memo {
+        if payee.is_empty() { memo } else { payee }.to_string()
+    } else {
+        format!("{} – {}", payee, memo)
+    }
+}
+
+// Kategoria z pliku po id albo nazwie w dowolnym języku, bez względu na wielkość liter
+fn find_category<'a>(categories: &'a [Category], name: &str) -> Option<&'a Category> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    categories
+        .iter()
+        .find(|c| c.id == name || c.labels.values().any(|label| label.to_lowercase() == name))
+}
@@ -0,0 +1,77 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Ile poprzednich wersji pliku danych trzymamy obok niego (expense_data.json.1 ... .N)
+pub const BACKUP_GENERATIONS: usize = 5;
+
+pub fn backup_path(path: &Path, generation: usize) -> PathBuf {
+    append_to_file_name(path, &format!(".{}", generation))
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    append_to_file_name(path, ".tmp")
+}
+
+fn append_to_file_name(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+// Zapis: plik tymczasowy -> fsync -> rotacja kopii -> rename na właściwą nazwę.
+// Przerwanie w dowolnym momencie zostawia albo stary, albo nowy plik - nigdy ucięty.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Ok(current) = fs::read(path) {
+        if current == contents {
+            return Ok(());
+        }
+    }
+
+    let tmp = temp_path(path);
+    {
+        let mut file = File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+
+    if path.exists() {
+        rotate_backups(path)?;
+    }
+
+    fs::rename(&tmp, path)?;
+    sync_parent_dir(path);
+    Ok(())
+}
+
+fn rotate_backups(path: &Path) -> io::Result<()> {
+    let oldest = backup_path(path, BACKUP_GENERATIONS);
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+    for generation in (1..BACKUP_GENERATIONS).rev() {
+        let from = backup_path(path, generation);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, generation + 1))?;
+        }
+    }
+
+    // Kopia przez plik tymczasowy, żeby .1 też nigdy nie był ucięty
+    let tmp = temp_path(&backup_path(path, 1));
+    fs::copy(path, &tmp)?;
+    File::open(&tmp)?.sync_all()?;
+    fs::rename(&tmp, backup_path(path, 1))?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) {
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) {}
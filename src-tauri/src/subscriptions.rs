@@ -0,0 +1,135 @@
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+use crate::models::{
+    CurrencyCode, Frequency, Money, PriceChange, RecurrenceEnd, RecurringTemplate, Schedule, Subscription, Transaction,
+    TransactionKind,
+};
+use crate::{recurring, rules};
+
+// Rozpoznawane odstępy między płatnościami: częstotliwość, co ile, typowa liczba dni
+// i dopuszczalne odchylenie (miesiące mają 28-31 dni, bank księguje z opóźnieniem)
+const PERIODS: [(Frequency, u32, i64, i64); 7] = [
+    (Frequency::Weekly, 1, 7, 1),
+    (Frequency::Weekly, 2, 14, 2),
+    (Frequency::Monthly, 1, 30, 5),
+    (Frequency::Monthly, 2, 61, 6),
+    (Frequency::Monthly, 3, 91, 7),
+    (Frequency::Monthly, 6, 182, 8),
+    (Frequency::Yearly, 1, 365, 8),
+];
+// O ile (względnie) mogą się różnić kolejne płatności - więcej to już nie ta sama usługa
+const AMOUNT_TOLERANCE: f64 = 0.25;
+// Najmniej płatności, żeby mówić o regularności; roczne rzadko mają dłuższą historię
+const MIN_PAYMENTS: usize = 3;
+const MIN_YEARLY_PAYMENTS: usize = 2;
+
+// Subskrypcje w historii wydatków, od najdroższej w skali roku. Pomija transakcje
+// utworzone z szablonów, kontrahentów, którzy mają już szablon, i subskrypcje, które
+// nie były opłacone od ponad dwóch okresów (przed `today`).
+pub fn detect(transactions: &[Transaction], templates: &[RecurringTemplate], today: NaiveDate) -> Vec<Subscription> {
+    let mut groups: HashMap<(String, CurrencyCode), Vec<&Transaction>> = HashMap::new();
+    for t in transactions {
+        if t.kind != TransactionKind::Expense || recurring::is_materialized(t) {
+            continue;
+        }
+        let key = payee_key(&t.title);
+        if !key.is_empty() {
+            groups.entry((key, t.amount.currency)).or_default().push(t);
+        }
+    }
+
+    let mut found: Vec<Subscription> = groups
+        .into_iter()
+        .filter(|((key, currency), _)| {
+            !templates.iter().any(|template| {
+                template.kind == TransactionKind::Expense
+                    && template.amount.currency == *currency
+                    && payee_key(&template.title) == *key
+            })
+        })
+        .filter_map(|(_, mut payments)| {
+            payments.sort_by_key(|t| t.date);
+            subscription(&payments, today)
+        })
+        .collect();
+    found.sort_by(|a, b| b.yearly_cost.minor.cmp(&a.yearly_cost.minor).then_with(|| a.payee.cmp(&b.payee)));
+    found
+}
+
+// Kontrahent bez wielkości liter i samych numerów ("NETFLIX.COM 1234" i "Netflix.com")
+fn payee_key(title: &str) -> String {
+    rules::payee(title)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !word.chars().all(|c| c.is_ascii_digit()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Subskrypcja z płatności jednego kontrahenta (posortowanych po dacie), jeśli są regularne
+fn subscription(payments: &[&Transaction], today: NaiveDate) -> Option<Subscription> {
+    let gaps: Vec<i64> = payments.windows(2).map(|pair| (pair[1].date - pair[0].date).num_days()).collect();
+    let mut sorted = gaps.clone();
+    sorted.sort_unstable();
+    let median = *sorted.get(sorted.len() / 2)?;
+    let (frequency, interval, days, tolerance) = PERIODS
+        .into_iter()
+        .find(|(_, _, days, tolerance)| (median - days).abs() <= *tolerance)?;
+
+    let minimum = if frequency == Frequency::Yearly { MIN_YEARLY_PAYMENTS } else { MIN_PAYMENTS };
+    if payments.len() < minimum || gaps.iter().any(|gap| (gap - days).abs() > tolerance) {
+        return None;
+    }
+    if payments.windows(2).any(|pair| !similar(pair[0].amount, pair[1].amount)) {
+        return None;
+    }
+    let (first, last) = (payments.first()?, payments.last()?);
+    if (today - last.date).num_days() > 2 * days + tolerance {
+        return None;
+    }
+
+    let price_increases = payments
+        .windows(2)
+        .filter(|pair| pair[1].amount.minor > pair[0].amount.minor)
+        .map(|pair| PriceChange { date: pair[1].date, from: pair[0].amount, to: pair[1].amount })
+        .collect();
+    let yearly = last.amount.minor as f64 * payments_per_year(frequency) / interval as f64;
+
+    // Szablon od następnej płatności - te z historii już są transakcjami
+    let mut schedule = Schedule { start: last.date, frequency, interval, nth_weekday: None, end: RecurrenceEnd::Never };
+    let next = recurring::dates(&schedule).nth(1)?;
+    schedule.start = next;
+    // Nazwa z ostatniej płatności, bez numerów transakcji ("NETFLIX.COM 1004" -> "NETFLIX.COM")
+    let payee = rules::payee(&last.title)
+        .split_whitespace()
+        .filter(|word| !word.chars().all(|c| c.is_ascii_digit()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut template = RecurringTemplate::new(&payee, last.amount, TransactionKind::Expense, &last.category, schedule);
+    template.tags = last.tags.clone();
+
+    Some(Subscription {
+        payee,
+        payments: payments.len(),
+        first_date: first.date,
+        last_date: last.date,
+        yearly_cost: Money::new(yearly.round() as i64, last.amount.currency),
+        price_increases,
+        template,
+    })
+}
+
+fn similar(a: Money, b: Money) -> bool {
+    let (low, high) = (a.minor.min(b.minor) as f64, a.minor.max(b.minor) as f64);
+    low > 0.0 && high <= low * (1.0 + AMOUNT_TOLERANCE)
+}
+
+fn payments_per_year(frequency: Frequency) -> f64 {
+    match frequency {
+        Frequency::Daily => 365.0,
+        Frequency::Weekly => 52.0,
+        Frequency::Monthly => 12.0,
+        Frequency::Yearly => 1.0,
+    }
+}
@@ -0,0 +1,84 @@
+// Minimalny odczyt XML na potrzeby tabel kursów i wyciągów (także OFX 1.x w SGML, gdzie
+// pola nie mają znaczników zamykających): znaczniki (bez prefiksu przestrzeni nazw)
+// z surowymi atrybutami oraz tekst między nimi. Deklaracje i komentarze są pomijane.
+#[derive(Debug, PartialEq)]
+pub enum Token<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+pub fn tokens(xml: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        let text = rest[..start].trim();
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+        let Some(length) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + length];
+        rest = &rest[start + length + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(local_name(name.trim())));
+            continue;
+        }
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/').trim();
+        let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        tokens.push(Token::Open(local_name(name), attrs));
+        if self_closing {
+            tokens.push(Token::Close(local_name(name)));
+        }
+    }
+    tokens
+}
+
+// Numery wierszy (od 1), w których otwierają się znaczniki `name` - do wskazania
+// operacji w podglądzie importu
+pub fn open_lines(xml: &str, name: &str) -> Vec<usize> {
+    xml.match_indices('<')
+        .filter(|(offset, _)| {
+            let tag = &xml[offset + 1..];
+            let end = tag.find(|c: char| c == '>' || c == '/' || c.is_whitespace()).unwrap_or(tag.len());
+            end > 0 && local_name(&tag[..end]) == name
+        })
+        .map(|(offset, _)| xml[..offset].matches('\n').count() + 1)
+        .collect()
+}
+
+// "gesmes:Envelope" -> "Envelope"
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+// Wartość atrybutu w cudzysłowie lub apostrofach
+pub fn attribute<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attrs;
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim();
+        let value = rest[eq + 1..].trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let end = value[1..].find(quote)?;
+        if key == name {
+            return Some(&value[1..end + 1]);
+        }
+        rest = &value[end + 2..];
+    }
+    None
+}
+
+// Tekst z encjami XML (&amp; &lt; &gt; &quot; &apos;) zamienionymi na znaki
+pub fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,92 @@
+use chrono::NaiveDate;
+use expense_tracker_rust_lib::budget::{self, MonthLimits};
+use expense_tracker_rust_lib::models::{
+    Budget, Category, CurrencyCode, Money, MonthlyLimitData, RateTable, Transaction, TransactionId, TransactionKind,
+};
+use std::collections::HashMap;
+
+fn pln(minor: i64) -> Money {
+    Money::new(minor, CurrencyCode::PLN)
+}
+
+fn expense(category: &str, minor: i64, date: &str) -> Transaction {
+    Transaction {
+        id: TransactionId::generate(),
+        title: "x".to_string(),
+        amount: pln(minor),
+        kind: TransactionKind::Expense,
+        date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+        category: category.to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+fn limits(categories: &[(&str, i64)]) -> MonthlyLimitData {
+    let mut data = MonthlyLimitData::empty(CurrencyCode::PLN);
+    for (category, minor) in categories {
+        data.categories.insert(category.to_string(), pln(*minor));
+    }
+    data
+}
+
+#[test]
+fn template_fills_only_months_from_the_current_one() {
+    let stored = HashMap::from([("2024-03".to_string(), limits(&[("food", 50000)]))]);
+    let budget = Budget { template: Some(limits(&[("food", 80000)])), rollover: vec![] };
+
+    assert_eq!(budget.limits_for(&stored, "2024-03", "2024-04"), Some(limits(&[("food", 50000)])));
+    assert_eq!(budget.limits_for(&stored, "2024-02", "2024-04"), None);
+    assert_eq!(budget.limits_for(&stored, "2024-04", "2024-04"), budget.template);
+    assert_eq!(budget.limits_for(&stored, "2024-07", "2024-04"), budget.template);
+    assert_eq!(Budget::default().limits_for(&stored, "2024-04", "2024-04"), None);
+    assert_eq!(budget::previous_month("2024-01").as_deref(), Some("2023-12"));
+}
+
+#[test]
+fn unspent_and_overspent_limits_carry_into_next_month() {
+    let categories = [
+        Category { id: "food".to_string(), ..Category::new(TransactionKind::Expense, "Jedzenie", "Food", "#ef4444", "x") },
+        Category {
+            id: "groceries".to_string(),
+            parent: Some("food".to_string()),
+            ..Category::new(TransactionKind::Expense, "Zakupy", "Groceries", "#ef4444", "x")
+        },
+        Category { id: "fun".to_string(), ..Category::new(TransactionKind::Expense, "Rozrywka", "Fun", "#ef4444", "x") },
+    ];
+    let stored = HashMap::from([
+        ("2024-01".to_string(), limits(&[("food", 50000), ("fun", 20000), ("bills", 10000)])),
+        ("2024-02".to_string(), limits(&[("food", 50000), ("fun", 20000), ("bills", 10000)])),
+        ("2024-03".to_string(), limits(&[("food", 50000)])),
+    ]);
+    let budget = Budget { template: None, rollover: vec!["food".to_string(), "fun".to_string()] };
+    let months = MonthLimits { limits: &stored, budget: &budget, current: "2024-03" };
+    let transactions = [
+        expense("groceries", 30000, "2024-01-10"),
+        expense("fun", 25000, "2024-01-20"),
+        expense("bills", 2000, "2024-01-20"),
+        expense("food", 60000, "2024-02-05"),
+        expense("fun", 10000, "2024-02-14"),
+        expense("groceries", 55000, "2024-03-02"),
+    ];
+    let carried = |month: &str| months.carried(month, &transactions, &categories, CurrencyCode::PLN, &RateTable::default());
+
+    // Styczeń: jedzenie +200, rozrywka -50; luty: jedzenie 200 + 500 - 600, rozrywka -50 + 200 - 100
+    assert_eq!(months.rollover_start("2024-03"), NaiveDate::from_ymd_opt(2024, 1, 1));
+    assert_eq!(carried("2024-03"), HashMap::from([("food".to_string(), pln(10000)), ("fun".to_string(), pln(5000))]));
+    // Marzec nie ma limitu rozrywki, więc do kwietnia przechodzi tylko jedzenie
+    assert_eq!(carried("2024-04"), HashMap::from([("food".to_string(), pln(5000))]));
+    assert!(carried("2024-01").is_empty());
+
+    let usages = stored["2024-03"].breakdown(&transactions[5..], &categories, CurrencyCode::PLN, &carried("2024-03"));
+    let rows: Vec<(&str, i64, i64, Option<u32>, bool)> = usages
+        .iter()
+        .map(|u| (u.category.as_str(), u.limit.minor, u.carried.minor, u.percent(), u.is_over()))
+        .collect();
+    assert_eq!(rows, [("food", 50000, 10000, Some(91), false), ("groceries", 0, 0, None, false), ("fun", 0, 5000, Some(0), false)]);
+
+    // Przekroczenie większe niż cały limit: budżet wyczerpany, zanim miesiąc się zaczął
+    let overspent = HashMap::from([("food".to_string(), pln(-60000))]);
+    let usage = &limits(&[("food", 50000)]).breakdown(&[], &categories, CurrencyCode::PLN, &overspent)[0];
+    assert_eq!((usage.available().minor, usage.percent(), usage.is_over()), (-10000, Some(100), true));
+}
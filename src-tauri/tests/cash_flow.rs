@@ -0,0 +1,44 @@
+use expense_tracker_rust_lib::models::{
+    CashFlow, CurrencyCode, Money, Transaction, TransactionId, TransactionKind,
+};
+
+fn tx(kind: TransactionKind, minor: i64) -> Transaction {
+    Transaction {
+        id: TransactionId::generate(),
+        title: "x".to_string(),
+        amount: Money::new(minor, CurrencyCode::PLN),
+        kind,
+        date: Default::default(),
+        category: "Ogólne".to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+#[test]
+fn splits_income_and_expenses() {
+    let txs = [
+        tx(TransactionKind::Income, 500000),
+        tx(TransactionKind::Expense, 185000),
+        tx(TransactionKind::Expense, 4250),
+    ];
+    let flow = CashFlow::of(&txs, CurrencyCode::PLN);
+    assert_eq!(flow.income, Money::new(500000, CurrencyCode::PLN));
+    assert_eq!(flow.expenses, Money::new(189250, CurrencyCode::PLN));
+    assert_eq!(flow.net(), Money::new(310750, CurrencyCode::PLN));
+}
+
+#[test]
+fn transfers_do_not_change_the_balance() {
+    let txs = [tx(TransactionKind::Transfer, 100000), tx(TransactionKind::Expense, 2000)];
+    let flow = CashFlow::of(&txs, CurrencyCode::PLN);
+    assert_eq!(flow.income, Money::zero(CurrencyCode::PLN));
+    assert_eq!(flow.net(), Money::new(-2000, CurrencyCode::PLN));
+}
+
+#[test]
+fn kind_is_stored_as_snake_case_text() {
+    assert_eq!(serde_json::to_string(&TransactionKind::Income).unwrap(), "\"income\"");
+    assert_eq!(TransactionKind::parse("transfer"), Some(TransactionKind::Transfer));
+    assert_eq!(TransactionKind::parse("Expense"), None);
+}
@@ -0,0 +1,98 @@
+use expense_tracker_rust_lib::migrations::legacy_category_id;
+use expense_tracker_rust_lib::models::{
+    AppState, Category, CurrencyCode, Money, MonthlyLimitData, Transaction, TransactionId,
+    TransactionKind, FALLBACK_CATEGORY,
+};
+
+#[test]
+fn legacy_names_map_to_default_ids_in_either_language() {
+    assert_eq!(legacy_category_id("Jedzenie"), "food");
+    assert_eq!(legacy_category_id("Food"), "food");
+    assert_eq!(legacy_category_id("Ogólne"), "general");
+    assert_eq!(legacy_category_id("  "), FALLBACK_CATEGORY);
+}
+
+#[test]
+fn unknown_names_keep_a_stable_id_that_remembers_the_name() {
+    let id = legacy_category_id("Zwierzęta");
+    assert_eq!(id, legacy_category_id("Zwierzęta"));
+    assert_ne!(id, legacy_category_id("Zwierzeta"));
+    assert_eq!(Category::placeholder(&id).label("en"), "Zwierzęta");
+}
+
+#[test]
+fn labels_fall_back_to_polish_then_id() {
+    let mut category = Category::new(TransactionKind::Income, "Premia", "", "#22c55e", "x");
+    assert_eq!(category.label("en"), "Premia");
+    category.labels.clear();
+    assert_eq!(category.label("pl"), category.id);
+}
+
+#[test]
+fn missing_categories_are_recreated_from_references() {
+    let mut limits = MonthlyLimitData::empty(CurrencyCode::PLN);
+    limits.categories.insert("legacy-4b6f74".to_string(), Money::new(100, CurrencyCode::PLN));
+    let mut state = AppState {
+        transactions: vec![Transaction {
+            id: TransactionId::generate(),
+            title: "Bilet".to_string(),
+            amount: Money::new(500, CurrencyCode::PLN),
+            kind: TransactionKind::Expense,
+            date: Default::default(),
+            category: "food".to_string(),
+            external_id: None,
+            tags: vec![],
+        }],
+        ..AppState::default()
+    };
+    state.limits.insert("2024-03".to_string(), limits);
+
+    assert_eq!(state.ensure_categories(), 1);
+    assert_eq!(state.categories.last().unwrap().label("pl"), "Kot");
+    assert_eq!(state.ensure_categories(), 0);
+}
+
+fn category(id: &str, parent: Option<&str>) -> Category {
+    Category {
+        id: id.to_string(),
+        parent: parent.map(str::to_string),
+        ..Category::new(TransactionKind::Expense, id, id, "#ef4444", "x")
+    }
+}
+
+#[test]
+fn tree_lists_children_under_their_parent() {
+    let categories = [
+        category("sushi", Some("restaurants")),
+        category("food", None),
+        category("restaurants", Some("food")),
+        category("bills", None),
+        category("orphan", Some("deleted")),
+    ];
+    let tree: Vec<(&str, usize)> = Category::tree(&categories)
+        .into_iter()
+        .map(|(c, depth)| (c.id.as_str(), depth))
+        .collect();
+    assert_eq!(
+        tree,
+        [("food", 0), ("restaurants", 1), ("sushi", 2), ("bills", 0), ("orphan", 0)]
+    );
+}
+
+#[test]
+fn parents_cannot_create_cycles_or_mix_kinds() {
+    let mut categories = vec![
+        category("food", None),
+        category("restaurants", Some("food")),
+        category("sushi", Some("restaurants")),
+    ];
+    assert!(Category::valid_parent(&categories, "sushi", TransactionKind::Expense, "food"));
+    assert!(!Category::valid_parent(&categories, "food", TransactionKind::Expense, "sushi"));
+    assert!(!Category::valid_parent(&categories, "food", TransactionKind::Expense, "food"));
+    assert!(!Category::valid_parent(&categories, "salary", TransactionKind::Income, "food"));
+
+    // Cykl z ręcznie poprawionego pliku nie zawiesza wyszukiwania przodków
+    categories[0].parent = Some("sushi".to_string());
+    assert_eq!(Category::ancestors(&categories, "sushi"), ["sushi", "restaurants", "food"]);
+    assert_eq!(Category::tree(&categories).len(), 3);
+}
@@ -0,0 +1,81 @@
+use chrono::NaiveDate;
+use expense_tracker_rust_lib::classifier::Classifier;
+use expense_tracker_rust_lib::models::{
+    Category, CurrencyCode, Money, Transaction, TransactionId, TransactionKind, AUTO_APPLY_CONFIDENCE, FALLBACK_CATEGORY,
+};
+
+fn transaction(title: &str, minor: i64, category: &str) -> Transaction {
+    Transaction {
+        id: TransactionId::generate(),
+        title: title.to_string(),
+        amount: Money::new(minor, CurrencyCode::PLN),
+        kind: TransactionKind::Expense,
+        date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        category: category.to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+fn history() -> Vec<Transaction> {
+    vec![
+        transaction("Biedronka 1234", 8550, "food"),
+        transaction("BIEDRONKA Warszawa", 12000, "food"),
+        transaction("Lidl zakupy", 9900, "food"),
+        transaction("Orlen stacja 42", 25000, "transport"),
+        transaction("Orlen paliwo", 22000, "transport"),
+        transaction("Bilet ZTM", 450, "transport"),
+        transaction("Kino Helios", 3800, "entertainment"),
+        transaction("Biedronka", 4000, FALLBACK_CATEGORY),
+    ]
+}
+
+#[test]
+fn suggests_the_category_of_similar_titles_with_confidence() {
+    let categories = Category::defaults();
+    let classifier = Classifier::train(&history());
+    assert_eq!(classifier.len(), 7);
+
+    let suggestion = classifier.suggest(&transaction("biedronka 99", 9000, ""), &categories).unwrap();
+    assert_eq!(suggestion.category, "food");
+    assert!(suggestion.confidence >= AUTO_APPLY_CONFIDENCE, "{}", suggestion.confidence);
+
+    // Słowo spotykane w dwóch kategoriach daje niepewną podpowiedź
+    let mixed = classifier.suggest(&transaction("Orlen Biedronka", 100000, ""), &categories).unwrap();
+    assert!(mixed.confidence < AUTO_APPLY_CONFIDENCE, "{}", mixed.confidence);
+
+    // Nieznany tytuł, inny rodzaj albo zarchiwizowana kategoria - bez podpowiedzi
+    assert_eq!(classifier.suggest(&transaction("Apteka", 9000, ""), &categories), None);
+    let income = Transaction { kind: TransactionKind::Income, ..transaction("Biedronka", 9000, "") };
+    assert_eq!(classifier.suggest(&income, &categories), None);
+    let archived: Vec<Category> = categories
+        .iter()
+        .cloned()
+        .map(|c| Category { archived: c.id == "food", ..c })
+        .collect();
+    assert_ne!(classifier.suggest(&transaction("Biedronka", 9000, ""), &archived).map(|s| s.category).as_deref(), Some("food"));
+}
+
+#[test]
+fn edits_and_deletions_update_the_model_without_retraining() {
+    let categories = Category::defaults();
+    let mut list = history();
+    let mut classifier = Classifier::train(&list);
+    let cinema = transaction("Kino Helios", 4200, "");
+    assert_eq!(classifier.suggest(&cinema, &categories).unwrap().category, "entertainment");
+
+    // Przeniesienie jedynego seansu do innej kategorii zmienia podpowiedź
+    list[6].category = "daily".to_string();
+    classifier.learn(&list[6]);
+    assert_eq!(classifier.len(), 7);
+    assert_eq!(classifier.suggest(&cinema, &categories).unwrap().category, "daily");
+    let (updated, retrained) = (classifier.suggest(&cinema, &categories).unwrap(), Classifier::train(&list).suggest(&cinema, &categories).unwrap());
+    assert!((updated.confidence - retrained.confidence).abs() < 1e-9);
+
+    classifier.forget(list[6].id);
+    assert_eq!(classifier.len(), 6);
+    assert_eq!(classifier.suggest(&cinema, &categories), None);
+
+    classifier.learn(&transaction("Multikino", 3000, "entertainment"));
+    assert_eq!(classifier.len(), 7);
+}
@@ -0,0 +1,10 @@
+use std::fs;
+use std::path::PathBuf;
+
+// Pusty katalog tymczasowy na pliki jednego testu
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("expense_tracker_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+use expense_tracker_rust_lib::csv_import;
+use expense_tracker_rust_lib::encoding;
+use expense_tracker_rust_lib::models::{Category, CurrencyCode, Money, TextEncoding, TransactionKind};
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn detects_windows_1250_bank_export() {
+    // Wyciąg z kilkoma wierszami nagłówka rachunku przed tabelą operacji
+    let mut content = b"Rachunek;PL61109010140000071219812874\n\n".to_vec();
+    content.extend_from_slice(b"Data operacji;Opis operacji;Kwota;Kategoria\n");
+    content.extend_from_slice(b"2024-01-03;\"Biedronka; zakupy\";-45,90;Jedzenie\n");
+    content.extend_from_slice(b"2024-01-05;Wyp\xb3ata;5 000,00;\n");
+
+    assert_eq!(encoding::detect(&content), TextEncoding::Windows1250);
+    let file = csv_import::inspect(&content, CurrencyCode::PLN).unwrap();
+    assert_eq!(file.options.delimiter, ';');
+    assert_eq!(file.options.skip_rows, 1);
+    assert!(file.options.has_header);
+    assert_eq!(file.headers[1], "Opis operacji");
+    assert_eq!(file.sample[1][1], "Wypłata");
+
+    let mapping = &file.options.mapping;
+    assert_eq!((mapping.date, mapping.title, mapping.amount, mapping.category), (Some(0), Some(1), Some(2), Some(3)));
+
+    let rows = csv_import::preview(&content, &file.options, &Category::defaults()).unwrap();
+    assert_eq!(rows.len(), 2);
+    let food = rows[0].transaction.as_ref().unwrap();
+    assert_eq!(food.title, "Biedronka; zakupy");
+    assert_eq!(food.amount, Money::new(4590, CurrencyCode::PLN));
+    assert_eq!((food.kind, food.category.as_str()), (TransactionKind::Expense, "food"));
+    assert_eq!(rows[0].line, 4);
+    let salary = rows[1].transaction.as_ref().unwrap();
+    assert_eq!((salary.kind, salary.category.as_str()), (TransactionKind::Income, "other_income"));
+    assert_eq!(salary.date, day("2024-01-05"));
+}
+
+#[test]
+fn guesses_columns_without_header_and_reports_bad_rows() {
+    let content = "31.01.2024,Coffee shop,\"-1,234.50\"\n01.02.2024,Refund,abc\n02.02.2024,,-3.00\n";
+    let file = csv_import::inspect(content.as_bytes(), CurrencyCode::EUR).unwrap();
+    assert_eq!(file.options.encoding, TextEncoding::Utf8);
+    assert_eq!(file.options.delimiter, ',');
+    assert!(!file.options.has_header);
+    assert_eq!(file.options.mapping.title, Some(1));
+    // Kolumna kwot ma błędną wartość, więc przypisujemy ją ręcznie
+    let mut options = file.options.clone();
+    options.mapping.amount = Some(2);
+
+    let rows = csv_import::preview(content.as_bytes(), &options, &[]).unwrap();
+    let coffee = rows[0].transaction.as_ref().unwrap();
+    assert_eq!(coffee.amount, Money::new(123450, CurrencyCode::EUR));
+    assert_eq!(coffee.date, day("2024-01-31"));
+    assert!(rows[1].error.as_deref().unwrap().contains("abc"));
+    assert!(rows[2].error.is_some());
+}
+
+#[test]
+fn parses_bank_amount_notations() {
+    let pln = CurrencyCode::PLN;
+    assert_eq!(csv_import::parse_amount("−7,00", pln), Some(Money::new(-700, pln)));
+    assert_eq!(csv_import::parse_amount("+12.50 PLN", pln), Some(Money::new(1250, pln)));
+    assert_eq!(csv_import::parse_amount("1.234,56", pln), Some(Money::new(123456, pln)));
+    assert_eq!(csv_import::parse_amount("1,234,567.8", pln), Some(Money::new(123456780, pln)));
+    assert_eq!(csv_import::parse_amount("Kwota", pln), None);
+    assert_eq!(csv_import::parse_date("2024-01-31 12:00:00"), Some(day("2024-01-31")));
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use common::temp_dir;
+use expense_tracker_rust_lib::db::{self, Store};
+use expense_tracker_rust_lib::models::{AppState, LoadError, TransactionId, TransactionKind, SCHEMA_VERSION};
+use expense_tracker_rust_lib::recovery;
+use rusqlite::Connection;
+use std::fs;
+use std::path::Path;
+
+const FIXTURES: &[(u32, &str)] = &[
+    (1, include_str!("fixtures/v1.json")),
+    (2, include_str!("fixtures/v2.json")),
+    (3, include_str!("fixtures/v3.json")),
+    (4, include_str!("fixtures/v4.json")),
+    (5, include_str!("fixtures/v5.json")),
+    (6, include_str!("fixtures/v6.json")),
+    (7, include_str!("fixtures/v7.json")),
+    (8, include_str!("fixtures/v8.json")),
+    (9, include_str!("fixtures/v9.json")),
+    (10, include_str!("fixtures/v10.json")),
+    (11, include_str!("fixtures/v11.json")),
+    (12, include_str!("fixtures/v12.json")),
+];
+
+fn user_version(path: &Path) -> u32 {
+    Connection::open(path).unwrap().query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap()
+}
+
+// Baza w wersji 2: kwoty REAL, id liczbowe, kategorie po nazwie
+fn create_v2_database(path: &Path) {
+    Connection::open(path)
+        .unwrap()
+        .execute_batch(
+            "CREATE TABLE transactions (id INTEGER PRIMARY KEY, title TEXT NOT NULL, amount REAL NOT NULL, date TEXT NOT NULL, category TEXT NOT NULL);
+            CREATE INDEX idx_transactions_date ON transactions(date);
+            CREATE INDEX idx_transactions_category ON transactions(category, date);
+            CREATE TABLE monthly_limits (month TEXT PRIMARY KEY, general REAL NOT NULL);
+            CREATE TABLE category_limits (month TEXT NOT NULL, category TEXT NOT NULL, amount REAL NOT NULL, PRIMARY KEY (month, category));
+            CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+            INSERT INTO settings VALUES ('currency', 'EUR');
+            INSERT INTO transactions VALUES (1, 'Biedronka', 84.37, '2024-03-02', 'Jedzenie');
+            INSERT INTO monthly_limits VALUES ('2024-03', 3000.0);
+            INSERT INTO category_limits VALUES ('2024-03', 'Jedzenie', 800.5);
+            PRAGMA user_version = 2;",
+        )
+        .unwrap();
+}
+
+#[test]
+fn fresh_store_starts_empty_at_the_current_version() {
+    let dir = temp_dir("db_fresh");
+    let db_path = dir.join("expense_data.db");
+
+    let store = recovery::open_store(&db_path, &dir.join("expense_data.json")).unwrap();
+    let state = store.load_state().unwrap();
+    assert!(state.transactions.is_empty());
+    assert!(state.limits.is_empty());
+    drop(store);
+    assert_eq!(user_version(&db_path), SCHEMA_VERSION);
+}
+
+#[test]
+fn every_json_fixture_is_imported_once() {
+    let golden: AppState = serde_json::from_str(include_str!("fixtures/current.json")).unwrap();
+    for (version, content) in FIXTURES {
+        let dir = temp_dir(&format!("db_import_v{}", version));
+        let (db_path, json_path) = (dir.join("expense_data.db"), dir.join("expense_data.json"));
+        fs::write(&json_path, content).unwrap();
+
+        let mut store = recovery::open_store(&db_path, &json_path).unwrap();
+        let mut state = store.load_state().unwrap();
+        state.transactions.sort_by_key(|t| t.id);
+        let mut expected = golden.transactions.clone();
+        expected.sort_by_key(|t| t.id);
+        assert_eq!(state.transactions, expected, "fixture v{}", version);
+        assert_eq!(state.limits, golden.limits, "fixture v{}", version);
+        assert_eq!(state.settings(), golden.settings(), "fixture v{}", version);
+
+        // Import jest jednorazowy: ponowne otwarcie nie nadpisuje bazy plikiem JSON
+        store.replace_state(&AppState::default()).unwrap();
+        drop(store);
+        let store = recovery::open_store(&db_path, &json_path).unwrap();
+        assert!(store.load_state().unwrap().transactions.is_empty(), "fixture v{}", version);
+    }
+}
+
+#[test]
+fn failed_import_leaves_the_database_fresh() {
+    let dir = temp_dir("db_failed_import");
+    let (db_path, json_path) = (dir.join("expense_data.db"), dir.join("expense_data.json"));
+    fs::write(&json_path, &FIXTURES[0].1[..200]).unwrap();
+
+    assert!(matches!(recovery::open_store(&db_path, &json_path), Err(LoadError::Corrupted { .. })));
+    assert_eq!(user_version(&db_path), 0);
+
+    // Przy następnym uruchomieniu baza nadal jest świeża i import próbuje ponownie
+    fs::write(&json_path, FIXTURES[0].1).unwrap();
+    let store = recovery::open_store(&db_path, &json_path).unwrap();
+    assert!(!store.load_state().unwrap().transactions.is_empty());
+    drop(store);
+    assert_eq!(user_version(&db_path), SCHEMA_VERSION);
+}
+
+#[test]
+fn old_databases_are_upgraded_in_place() {
+    let dir = temp_dir("db_upgrade");
+    let path = dir.join("expense_data.db");
+    create_v2_database(&path);
+
+    let (store, fresh) = Store::open(&path).unwrap();
+    assert!(!fresh);
+    let state = store.load_state().unwrap();
+    let t = &state.transactions[0];
+    assert_eq!((t.id, t.kind, t.category.as_str()), (TransactionId::legacy(1, 0), TransactionKind::Expense, "food"));
+    assert_eq!((t.amount.minor, t.amount.currency.as_str()), (8437, "EUR"));
+    assert_eq!(state.limits["2024-03"].general.minor, 300000);
+    assert_eq!(state.limits["2024-03"].categories["food"].minor, 80050);
+    drop(store);
+    assert_eq!(user_version(&path), SCHEMA_VERSION);
+}
+
+#[test]
+fn salvage_does_not_modify_the_quarantined_database() {
+    let dir = temp_dir("db_salvage");
+    let path = dir.join("expense_data.db");
+    create_v2_database(&path);
+    let before = fs::read(&path).unwrap();
+
+    // Starsza baza jest aktualizowana na kopii, zanim z niej czytamy
+    let (state, skipped) = db::salvage(&path).unwrap();
+    assert_eq!((state.transactions[0].amount.minor, skipped), (8437, 0));
+    assert_eq!(state.limits["2024-03"].categories["food"].minor, 80050);
+    assert_eq!(fs::read(&path).unwrap(), before);
+
+    // Uszkodzony plik: odzyskujemy, ile się da, a oryginał zostaje bez zmian
+    let mut damaged = before.clone();
+    let middle = damaged.len() / 2;
+    damaged[middle..middle + 64].fill(0xAB);
+    fs::write(&path, &damaged).unwrap();
+    let _ = db::salvage(&path);
+    assert_eq!(fs::read(&path).unwrap(), damaged);
+}
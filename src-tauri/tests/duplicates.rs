@@ -0,0 +1,74 @@
+use chrono::NaiveDate;
+use expense_tracker_rust_lib::duplicates;
+use expense_tracker_rust_lib::models::{
+    CurrencyCode, ImportRow, Money, Transaction, TransactionId, TransactionKind, FALLBACK_CATEGORY,
+};
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn expense(title: &str, minor: i64, date: &str) -> Transaction {
+    Transaction {
+        id: TransactionId::generate(),
+        title: title.to_string(),
+        amount: Money::new(minor, CurrencyCode::PLN),
+        kind: TransactionKind::Expense,
+        date: day(date),
+        category: "food".to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+#[test]
+fn duplicates_need_same_amount_close_dates_and_similar_titles() {
+    let receipt = expense("Biedronka", 4599, "2024-03-01");
+
+    assert!(duplicates::is_duplicate(&receipt, &expense("BIEDRONKA 1234 WARSZAWA", 4599, "2024-03-03")));
+    assert!(duplicates::is_duplicate(&receipt, &expense("Biedronk", 4599, "2024-03-01")));
+    assert!(!duplicates::is_duplicate(&receipt, &receipt));
+    assert!(!duplicates::is_duplicate(&receipt, &expense("Biedronka", 4598, "2024-03-01")));
+    assert!(!duplicates::is_duplicate(&receipt, &expense("Biedronka", 4599, "2024-03-05")));
+    assert!(!duplicates::is_duplicate(&receipt, &expense("Lidl", 4599, "2024-03-01")));
+
+    let income = Transaction { kind: TransactionKind::Income, ..expense("Biedronka", 4599, "2024-03-01") };
+    assert!(!duplicates::is_duplicate(&receipt, &income));
+
+    // Dwie różne operacje z banku o tej samej kwocie to nie duplikat
+    let first = Transaction { external_id: Some("ofx:1:A".into()), ..receipt.clone() };
+    let second = Transaction { external_id: Some("ofx:1:B".into()), ..expense("Biedronka", 4599, "2024-03-01") };
+    assert!(!duplicates::is_duplicate(&first, &second));
+}
+
+#[test]
+fn preview_rows_point_at_best_match_and_merge_keeps_saved_data() {
+    let saved = vec![
+        expense("Stacja paliw", 20000, "2024-03-10"),
+        Transaction { category: FALLBACK_CATEGORY.to_string(), ..expense("Orlen", 20000, "2024-03-11") },
+    ];
+    let imported = Transaction {
+        external_id: Some("mt940:PL1:REF7".into()),
+        category: "transport".to_string(),
+        ..expense("ORLEN Stacja nr 42", 20000, "2024-03-12")
+    };
+    let mut rows = vec![
+        ImportRow { line: 1, cells: vec![], transaction: Some(imported.clone()), error: None, duplicate_of: None },
+        ImportRow { line: 2, cells: vec![], transaction: None, error: Some("x".into()), duplicate_of: None },
+    ];
+    duplicates::mark(&mut rows, &saved);
+
+    let existing = rows[0].duplicate_of.clone().unwrap();
+    assert_eq!(existing.id, saved[1].id);
+    assert_eq!(rows[1].duplicate_of, None);
+
+    let merged = duplicates::merge(&existing, &imported);
+    assert_eq!((merged.id, merged.title.as_str()), (saved[1].id, "Orlen"));
+    assert_eq!(merged.external_id.as_deref(), Some("mt940:PL1:REF7"));
+    assert_eq!(merged.category, "transport");
+
+    assert_eq!(
+        duplicates::search_range([day("2024-03-12"), day("2024-03-10")]),
+        Some((day("2024-03-07"), day("2024-03-16")))
+    );
+}
@@ -0,0 +1,67 @@
+use chrono::NaiveDate;
+use expense_tracker_rust_lib::csv_import;
+use expense_tracker_rust_lib::export;
+use expense_tracker_rust_lib::models::{
+    Category, CurrencyCode, Money, Transaction, TransactionFilter, TransactionId, TransactionKind,
+};
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn transaction(title: &str, minor: i64, kind: TransactionKind, date: &str, category: &str) -> Transaction {
+    Transaction {
+        id: TransactionId::generate(),
+        title: title.to_string(),
+        amount: Money::new(minor, CurrencyCode::PLN),
+        kind,
+        date: day(date),
+        category: category.to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+#[test]
+fn filter_covers_date_range_and_subcategories() {
+    let mut categories = Category::defaults();
+    let mut pets = Category::new(TransactionKind::Expense, "Zwierzęta", "Pets", "#000000", "x");
+    pets.parent = Some("daily".to_string());
+    categories.push(pets.clone());
+
+    let filter = TransactionFilter {
+        from: day("2024-03-01"),
+        to: day("2024-03-31"),
+        categories: vec!["daily".to_string()],
+    };
+    let matches = |t: &Transaction| filter.matches(t, &categories);
+    assert!(matches(&transaction("Karma", 100, TransactionKind::Expense, "2024-03-31", &pets.id)));
+    assert!(!matches(&transaction("Karma", 100, TransactionKind::Expense, "2024-04-01", &pets.id)));
+    assert!(!matches(&transaction("Kino", 100, TransactionKind::Expense, "2024-03-10", "entertainment")));
+
+    let all = TransactionFilter { categories: vec![], ..filter };
+    assert!(all.matches(&transaction("Kino", 100, TransactionKind::Expense, "2024-03-01", "entertainment"), &categories));
+}
+
+#[test]
+fn csv_export_can_be_imported_back() {
+    let categories = Category::defaults();
+    let exported = vec![
+        transaction("Zakupy, \"duże\"", 12345, TransactionKind::Expense, "2024-03-02", "food"),
+        transaction("Pensja", 500000, TransactionKind::Income, "2024-03-10", "salary"),
+    ];
+    let csv = export::transactions_csv(&exported, &categories, "pl");
+    assert!(csv.starts_with("date,title,amount,currency,kind,category,category_name\r\n"));
+    assert!(csv.contains("2024-03-02,\"Zakupy, \"\"duże\"\"\",-123.45,PLN,expense,food,Jedzenie\r\n"));
+
+    let file = csv_import::inspect(csv.as_bytes(), CurrencyCode::PLN).unwrap();
+    let rows = csv_import::preview(csv.as_bytes(), &file.options, &categories).unwrap();
+    let imported: Vec<Transaction> = rows.into_iter().filter_map(|row| row.transaction).collect();
+    assert_eq!(imported.len(), 2);
+    for (before, after) in exported.iter().zip(&imported) {
+        assert_eq!(
+            (&before.title, before.amount, before.kind, before.date, &before.category),
+            (&after.title, after.amount, after.kind, after.date, &after.category)
+        );
+    }
+}
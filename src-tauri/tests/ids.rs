@@ -0,0 +1,56 @@
+use expense_tracker_rust_lib::models::{
+    AppState, CurrencyCode, Money, Transaction, TransactionId, TransactionKind,
+};
+use std::collections::HashSet;
+
+fn tx(id: TransactionId) -> Transaction {
+    Transaction {
+        id,
+        title: "Kino".to_string(),
+        amount: Money::new(4250, CurrencyCode::PLN),
+        kind: TransactionKind::Expense,
+        date: Default::default(),
+        category: "Rozrywka".to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+#[test]
+fn generated_ids_are_uuid_v4() {
+    let id = TransactionId::generate().to_string();
+    assert_eq!(id.len(), 36);
+    assert_eq!(&id[14..15], "4");
+    assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"), "{}", id);
+}
+
+#[test]
+fn generated_ids_do_not_repeat() {
+    let ids: HashSet<TransactionId> = (0..100_000).map(|_| TransactionId::generate()).collect();
+    assert_eq!(ids.len(), 100_000);
+}
+
+#[test]
+fn ids_round_trip_through_text() {
+    let id = TransactionId::generate();
+    assert_eq!(TransactionId::parse(&id.to_string()), Some(id));
+    assert_eq!(
+        TransactionId::legacy(77120, 1).to_string(),
+        "00000000-0000-0001-0000-000000012d40"
+    );
+    assert_eq!(TransactionId::parse("00000000-0000-0000-0000-00000000zzzz"), None);
+    assert!(serde_json::from_str::<TransactionId>("77120").is_err());
+}
+
+#[test]
+fn duplicate_ids_are_rekeyed_keeping_the_first() {
+    let shared = TransactionId::legacy(5561, 0);
+    let mut state = AppState {
+        transactions: vec![tx(shared), tx(shared), tx(TransactionId::legacy(1, 0))],
+        ..AppState::default()
+    };
+    assert_eq!(state.resolve_duplicate_ids(), 1);
+    assert_eq!(state.transactions[0].id, shared);
+    assert_ne!(state.transactions[1].id, shared);
+    assert_eq!(state.resolve_duplicate_ids(), 0);
+}
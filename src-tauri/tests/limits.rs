@@ -0,0 +1,104 @@
+use expense_tracker_rust_lib::models::{
+    Category, CurrencyCode, Money, MonthlyLimitData, Transaction, TransactionId, TransactionKind,
+};
+use std::collections::HashMap;
+
+fn pln(minor: i64) -> Money {
+    Money::new(minor, CurrencyCode::PLN)
+}
+
+fn tx(kind: TransactionKind, category: &str, minor: i64) -> Transaction {
+    Transaction {
+        id: TransactionId::generate(),
+        title: "x".to_string(),
+        amount: pln(minor),
+        kind,
+        date: Default::default(),
+        category: category.to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+fn limits(categories: &[(&str, i64)]) -> MonthlyLimitData {
+    let mut data = MonthlyLimitData::empty(CurrencyCode::PLN);
+    for (category, minor) in categories {
+        data.categories.insert(category.to_string(), pln(*minor));
+    }
+    data
+}
+
+#[test]
+fn compares_spending_with_category_limits() {
+    let txs = [
+        tx(TransactionKind::Expense, "Jedzenie", 60000),
+        tx(TransactionKind::Expense, "Jedzenie", 30000),
+        tx(TransactionKind::Expense, "Rozrywka", 5000),
+        tx(TransactionKind::Income, "Rozrywka", 100000),
+    ];
+    let usages = limits(&[("Jedzenie", 80000), ("Rozrywka", 20000)]).breakdown(&txs, &[], CurrencyCode::PLN, &HashMap::new());
+
+    assert_eq!(usages.len(), 2);
+    assert_eq!(usages[0].category, "Jedzenie");
+    assert_eq!(usages[0].spent, pln(90000));
+    assert_eq!(usages[0].percent(), Some(112));
+    assert!(usages[0].is_over());
+    assert_eq!(usages[1].spent, pln(5000));
+    assert_eq!(usages[1].percent(), Some(25));
+    assert!(!usages[1].is_over());
+}
+
+#[test]
+fn lists_spending_without_a_limit_and_limits_without_spending() {
+    let txs = [tx(TransactionKind::Expense, "Rachunki", 185000)];
+    let usages = limits(&[("Jedzenie", 80000), ("Auto i Transport", 0)]).breakdown(&txs, &[], CurrencyCode::PLN, &HashMap::new());
+
+    let categories: Vec<&str> = usages.iter().map(|u| u.category.as_str()).collect();
+    assert_eq!(categories, ["Jedzenie", "Rachunki"]);
+    assert_eq!(usages[0].spent, pln(0));
+    assert_eq!(usages[1].percent(), None);
+    assert!(!usages[1].is_over());
+}
+
+fn subcategory(id: &str, parent: &str) -> Category {
+    Category {
+        id: id.to_string(),
+        parent: Some(parent.to_string()),
+        ..Category::new(TransactionKind::Expense, id, id, "#ef4444", "x")
+    }
+}
+
+#[test]
+fn spending_rolls_up_to_parent_categories() {
+    let categories = [
+        Category {
+            id: "food".to_string(),
+            ..Category::new(TransactionKind::Expense, "Jedzenie", "Food", "#ef4444", "x")
+        },
+        subcategory("groceries", "food"),
+        subcategory("restaurants", "food"),
+        subcategory("sushi", "restaurants"),
+    ];
+    let txs = [
+        tx(TransactionKind::Expense, "groceries", 40000),
+        tx(TransactionKind::Expense, "sushi", 12000),
+        tx(TransactionKind::Expense, "restaurants", 8000),
+    ];
+    let usages = limits(&[("food", 50000), ("restaurants", 15000)]).breakdown(&txs, &categories, CurrencyCode::PLN, &HashMap::new());
+
+    let rows: Vec<(&str, i64, usize)> = usages
+        .iter()
+        .map(|u| (u.category.as_str(), u.spent.minor, u.depth))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("food", 60000, 0),
+            ("restaurants", 20000, 1),
+            ("sushi", 12000, 2),
+            ("groceries", 40000, 1),
+        ]
+    );
+    assert!(usages[0].is_over());
+    assert_eq!(usages[1].percent(), Some(133));
+}
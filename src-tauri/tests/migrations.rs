@@ -0,0 +1,56 @@
+use expense_tracker_rust_lib::migrations::{self, MigrationError};
+use expense_tracker_rust_lib::models::{AppState, SCHEMA_VERSION};
+use serde_json::Value;
+
+// Każda historyczna wersja dokumentu ma swój plik w tests/fixtures/vN.json.
+// Po migracji wszystkie muszą dać dokładnie to samo co current.json.
+const FIXTURES: &[(u32, &str)] = &[
+    (1, include_str!("fixtures/v1.json")),
+    (2, include_str!("fixtures/v2.json")),
+    (3, include_str!("fixtures/v3.json")),
+    (4, include_str!("fixtures/v4.json")),
+    (5, include_str!("fixtures/v5.json")),
+    (6, include_str!("fixtures/v6.json")),
+    (7, include_str!("fixtures/v7.json")),
+    (8, include_str!("fixtures/v8.json")),
+    (9, include_str!("fixtures/v9.json")),
+    (10, include_str!("fixtures/v10.json")),
+    (11, include_str!("fixtures/v11.json")),
+    (12, include_str!("fixtures/v12.json")),
+];
+
+fn golden() -> AppState {
+    serde_json::from_str(include_str!("fixtures/current.json")).unwrap()
+}
+
+fn migrate_fixture(content: &str) -> AppState {
+    let doc: Value = serde_json::from_str(content).unwrap();
+    serde_json::from_value(migrations::migrate(doc).unwrap()).unwrap()
+}
+
+#[test]
+fn every_historical_version_migrates_to_golden() {
+    for (version, content) in FIXTURES {
+        assert_eq!(migrate_fixture(content), golden(), "fixture v{}", version);
+    }
+}
+
+#[test]
+fn fixtures_cover_every_version() {
+    let versions: Vec<u32> = FIXTURES.iter().map(|(v, _)| *v).collect();
+    assert_eq!(versions, (1..=SCHEMA_VERSION).collect::<Vec<_>>());
+}
+
+#[test]
+fn golden_is_at_current_version() {
+    assert_eq!(golden().version, SCHEMA_VERSION);
+}
+
+#[test]
+fn newer_documents_are_rejected() {
+    let doc = serde_json::json!({ "version": SCHEMA_VERSION + 1, "transactions": [] });
+    assert_eq!(
+        migrations::migrate(doc),
+        Err(MigrationError::UnsupportedVersion(SCHEMA_VERSION + 1))
+    );
+}
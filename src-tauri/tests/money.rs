@@ -0,0 +1,74 @@
+use expense_tracker_rust_lib::models::{CurrencyCode, Money};
+
+fn pln(minor: i64) -> Money {
+    Money::new(minor, CurrencyCode::PLN)
+}
+
+#[test]
+fn parses_amount_input_forms() {
+    assert_eq!(Money::parse("12,34", CurrencyCode::PLN), Some(pln(1234)));
+    assert_eq!(Money::parse("12.34", CurrencyCode::PLN), Some(pln(1234)));
+    assert_eq!(Money::parse("12.5", CurrencyCode::PLN), Some(pln(1250)));
+    assert_eq!(Money::parse("12.", CurrencyCode::PLN), Some(pln(1200)));
+    assert_eq!(Money::parse(",05", CurrencyCode::PLN), Some(pln(5)));
+    assert_eq!(Money::parse("1 234,56", CurrencyCode::PLN), Some(pln(123456)));
+    assert_eq!(Money::parse("-7", CurrencyCode::PLN), Some(pln(-700)));
+}
+
+#[test]
+fn rejects_malformed_amounts() {
+    for input in ["", ".", "12.345", "1.2.3", "12a", "--1"] {
+        assert_eq!(Money::parse(input, CurrencyCode::PLN), None, "{:?}", input);
+    }
+}
+
+#[test]
+fn sums_without_float_drift() {
+    // 0.1 + 0.2 tysiąc razy - na f64 wynik nie byłby równy 150.00
+    let items: Vec<Money> = (0..1000).map(|i| if i % 2 == 0 { pln(10) } else { pln(20) }).collect();
+    assert_eq!(Money::checked_sum(&items, CurrencyCode::PLN), Some(pln(15000)));
+}
+
+#[test]
+fn arithmetic_is_checked() {
+    let usd = Money::new(100, CurrencyCode::new("USD").unwrap());
+    assert_eq!(pln(100).checked_add(usd), None);
+    assert_eq!(pln(i64::MAX).checked_add(pln(1)), None);
+    assert_eq!(pln(500).checked_sub(pln(125)), Some(pln(375)));
+}
+
+#[test]
+fn reads_legacy_float_amounts() {
+    let legacy: Money = serde_json::from_str("84.37").unwrap();
+    assert_eq!(legacy, pln(8437));
+
+    let exact: Money = serde_json::from_str(r#"{"minor":8437,"currency":"EUR"}"#).unwrap();
+    assert_eq!(exact.currency.as_str(), "EUR");
+    assert_eq!(serde_json::to_string(&exact).unwrap(), r#"{"minor":8437,"currency":"EUR"}"#);
+}
+
+#[test]
+fn minor_units_follow_iso_4217() {
+    let jpy = CurrencyCode::new("JPY").unwrap();
+    let kwd = CurrencyCode::new("KWD").unwrap();
+    assert_eq!(Money::parse("1250", jpy), Some(Money::new(1250, jpy)));
+    assert_eq!(Money::parse("12.5", jpy), None);
+    assert_eq!(Money::parse("1,125", kwd), Some(Money::new(1125, kwd)));
+    assert_eq!(Money::from_major(3.5, kwd), Money::new(3500, kwd));
+    assert_eq!(Money::new(1000, jpy).to_input_string(), "1000");
+    assert_eq!(Money::new(1500, kwd).to_input_string(), "1.5");
+    // Kod spoza tabeli (np. z ręcznego kursu) ma domyślnie 2 miejsca
+    assert_eq!(CurrencyCode::new("ABC").unwrap().minor_digits(), 2);
+}
+
+#[test]
+fn formats_by_language_and_currency() {
+    let usd = CurrencyCode::new("USD").unwrap();
+    let jpy = CurrencyCode::new("JPY").unwrap();
+    assert_eq!(pln(123456).format("pl"), "1234,56\u{a0}zł");
+    assert_eq!(pln(1234567).format("pl"), "12\u{a0}345,67\u{a0}zł");
+    assert_eq!(pln(-1250).format("en"), "-12.50\u{a0}zł");
+    assert_eq!(Money::new(123456, usd).format("en"), "$1,234.56");
+    assert_eq!(Money::new(123456, usd).format("pl"), "1234,56\u{a0}$");
+    assert_eq!(Money::new(-1234567, jpy).format("en"), "-¥1,234,567");
+}
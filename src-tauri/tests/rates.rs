@@ -0,0 +1,173 @@
+use chrono::NaiveDate;
+use expense_tracker_rust_lib::models::{
+    in_currency, CashFlow, CurrencyCode, DisplayRates, ExchangeRate, Money, MonthlyLimitData,
+    RateProvider, RateSource, RateTable, Transaction, TransactionId, TransactionKind,
+};
+use expense_tracker_rust_lib::rates;
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn code(s: &str) -> CurrencyCode {
+    CurrencyCode::new(s).unwrap()
+}
+
+fn rate(date: &str, base: &str, quote: &str, rate: f64, source: RateSource) -> ExchangeRate {
+    ExchangeRate {
+        date: day(date),
+        base: code(base),
+        quote: code(quote),
+        rate,
+        source,
+    }
+}
+
+#[test]
+fn ecb_xml_and_csv_give_the_same_rates() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <gesmes:subject>Reference rates</gesmes:subject>
+    <Cube>
+        <Cube time='2024-01-03'>
+            <Cube currency='USD' rate='1.0919'/>
+            <Cube currency='PLN' rate='4.3550'/>
+        </Cube>
+        <Cube time='2024-01-02'>
+            <Cube currency='USD' rate='1.0956'/>
+            <Cube currency='PLN' rate='4.3490'/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+    let csv = "Date,USD,PLN,\n2024-01-03,1.0919,4.3550,\n2024-01-02,1.0956,4.3490,\n";
+
+    let from_xml = rates::parse(xml.as_bytes()).unwrap();
+    let from_csv = rates::parse(csv.as_bytes()).unwrap();
+    assert_eq!(from_xml, from_csv);
+    assert_eq!(from_xml.skipped, 0);
+    assert_eq!(from_xml.rates[0], rate("2024-01-03", "EUR", "USD", 1.0919, RateSource::Ecb));
+    assert_eq!(from_xml.rates.len(), 4);
+}
+
+#[test]
+fn nbp_tables_are_converted_to_pln_per_unit() {
+    let api = "<ArrayOfExchangeRatesTable><ExchangeRatesTable><Table>A</Table><No>001/A/NBP/2024</No>\
+        <EffectiveDate>2024-01-02</EffectiveDate><Rates>\
+        <Rate><Currency>dolar amerykański</Currency><Code>USD</Code><Mid>3.9432</Mid></Rate>\
+        <Rate><Currency>jen (Japonia)</Currency><Code>JPY</Code><Mid>0.027899</Mid></Rate>\
+        </Rates></ExchangeRatesTable></ArrayOfExchangeRatesTable>";
+    // Archiwum w Windows-1250 ("ñ" w nazwie waluty) z przecinkiem dziesiętnym i przelicznikiem
+    let mut archive = b"<?xml version=\"1.0\" encoding=\"ISO-8859-2\"?><tabela_kursow typ=\"A\">\
+        <data_publikacji>2024-01-02</data_publikacji><pozycja><nazwa_waluty>jen (Japonia)</nazwa_waluty>\
+        <przelicznik>100</przelicznik><kod_waluty>JPY</kod_waluty><kurs_sredni>2,7899</kurs_sredni></pozycja>"
+        .to_vec();
+    archive.extend_from_slice(b"<pozycja><nazwa_waluty>dolar ameryka\xf1ski</nazwa_waluty><przelicznik>1</przelicznik>\
+        <kod_waluty>USD</kod_waluty><kurs_sredni>3,9432</kurs_sredni></pozycja></tabela_kursow>");
+    let csv = "data;1USD;100JPY;nr tabeli;pełny numer tabeli\n\
+        ;dolar amerykański;jen (Japonia);;\n\
+        20240102;3,9432;2,7899;1;001/A/NBP/2024\n\
+        20240103;3,9909;n/d;2;002/A/NBP/2024\n";
+
+    let from_api = rates::parse(api.as_bytes()).unwrap();
+    assert_eq!(from_api.rates[0], rate("2024-01-02", "USD", "PLN", 3.9432, RateSource::Nbp));
+
+    let from_archive = rates::parse(&archive).unwrap();
+    assert_eq!(from_archive.rates.len(), 2);
+    assert!((from_archive.rates[0].rate - 0.027899).abs() < 1e-12);
+
+    let from_csv = rates::parse(csv.as_bytes()).unwrap();
+    assert_eq!(from_csv.report().imported, 3);
+    assert_eq!(from_csv.report().skipped, 1);
+    assert!((from_csv.rates[1].rate - 0.027899).abs() < 1e-12);
+    assert_eq!(from_csv.rates[2], rate("2024-01-03", "USD", "PLN", 3.9909, RateSource::Nbp));
+}
+
+#[test]
+fn unknown_files_are_rejected() {
+    assert!(rates::parse(b"<html><body>404</body></html>").is_err());
+    assert!(rates::parse(b"id;title;amount\n1;x;2").is_err());
+}
+
+#[test]
+fn lookup_uses_the_nearest_previous_day() {
+    let table = RateTable::new(&[
+        rate("2024-01-05", "USD", "PLN", 3.95, RateSource::Nbp),
+        rate("2024-01-08", "USD", "PLN", 3.99, RateSource::Nbp),
+    ]);
+    let (usd, pln) = (code("USD"), code("PLN"));
+    // Weekend: obowiązuje kurs z piątku
+    assert_eq!(table.lookup(usd, pln, day("2024-01-07")), Some((day("2024-01-05"), 3.95)));
+    assert_eq!(table.rate(usd, pln, day("2024-01-08")), Some(3.99));
+    assert_eq!(table.rate(pln, usd, day("2024-01-06")), Some(1.0 / 3.95));
+    assert_eq!(table.rate(usd, pln, day("2024-01-04")), None);
+    assert_eq!(table.rate(pln, pln, day("2000-01-01")), Some(1.0));
+}
+
+#[test]
+fn manual_overrides_win_and_cross_rates_are_derived() {
+    let table = RateTable::new(&[
+        rate("2024-01-02", "EUR", "PLN", 4.35, RateSource::Ecb),
+        rate("2024-01-02", "EUR", "PLN", 4.40, RateSource::Manual),
+        rate("2024-01-02", "USD", "PLN", 4.00, RateSource::Nbp),
+    ]);
+    let (eur, usd, pln) = (code("EUR"), code("USD"), code("PLN"));
+    assert_eq!(table.rate(eur, pln, day("2024-01-03")), Some(4.40));
+    // USD -> EUR przez PLN: 4.00 / 4.40
+    let cross = table.rate(usd, eur, day("2024-01-03")).unwrap();
+    assert!((cross - 4.00 / 4.40).abs() < 1e-12);
+}
+
+fn expense(date: &str, minor: i64, currency: &str) -> Transaction {
+    Transaction {
+        id: TransactionId::generate(),
+        title: "test".to_string(),
+        amount: Money::new(minor, code(currency)),
+        kind: TransactionKind::Expense,
+        date: day(date),
+        category: "food".to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+#[test]
+fn reporting_currency_converts_copies_and_reports_missing_rates() {
+    let table = RateTable::new(&[rate("2024-01-05", "USD", "PLN", 4.0, RateSource::Nbp)]);
+    let stored = vec![
+        expense("2024-01-10", 10000, "PLN"),
+        expense("2024-01-10", 1000, "USD"),
+        expense("2024-01-10", 500, "EUR"),
+    ];
+    let pln = code("PLN");
+
+    let (converted, missing) = in_currency(&stored, pln, &table);
+    assert_eq!(missing, 1);
+    assert_eq!(CashFlow::of(&converted, pln).expenses, Money::new(14000, pln));
+    // Zapisane kwoty zostają w oryginalnej walucie
+    assert_eq!(stored[1].amount, Money::new(1000, code("USD")));
+
+    // Tam i z powrotem przez inną walutę raportu - oryginał się nie zmienia, więc nie ma dryfu
+    let (in_usd, _) = in_currency(&stored, code("USD"), &table);
+    assert_eq!(in_usd[0].amount, Money::new(2500, code("USD")));
+    let (back, _) = in_currency(&stored, pln, &table);
+    assert_eq!(back, converted);
+}
+
+#[test]
+fn display_rates_convert_limits_by_first_day_of_month() {
+    let display = DisplayRates {
+        currency: code("PLN"),
+        rates: vec![(code("EUR"), day("2024-02-01"), 4.3)],
+    };
+    let mut limits = MonthlyLimitData::empty(code("EUR"));
+    limits.general = Money::new(10000, code("EUR"));
+    limits.categories.insert("food".to_string(), Money::new(5000, code("PLN")));
+    limits.categories.insert("fun".to_string(), Money::new(100, code("USD")));
+
+    let shown = limits.in_currency("2024-02", code("PLN"), &display);
+    assert_eq!(shown.general, Money::new(43000, code("PLN")));
+    assert_eq!(shown.categories["food"], Money::new(5000, code("PLN")));
+    // Bez kursu limit jest traktowany jak brak limitu
+    assert_eq!(shown.categories["fun"], Money::zero(code("PLN")));
+    assert_eq!(display.convert(Money::new(100, code("EUR")), code("PLN"), day("2024-02-02")), None);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::temp_dir;
+use expense_tracker_rust_lib::models::{LoadError, SCHEMA_VERSION};
+use expense_tracker_rust_lib::recovery;
+use std::fs;
+use std::path::Path;
+
+// Plik bez klucza "version" (wersja 1) ucięty w połowie transakcji "Kino"
+fn truncated_v1() -> &'static str {
+    let full = include_str!("fixtures/v1.json");
+    let cut = full.find("\"Kino\"").unwrap();
+    &full[..cut]
+}
+
+#[test]
+fn truncated_file_keeps_complete_transactions() {
+    let (state, skipped) = recovery::salvage(truncated_v1());
+
+    let titles: Vec<&str> = state.transactions.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, ["Biedronka", "Czynsz"]);
+    assert_eq!(skipped, 0);
+    // Bez klucza "version" fragmenty przechodzą migracje od wersji 1: kwoty w groszach, kategorie po id
+    assert_eq!(state.transactions[0].amount.minor, 8437);
+    assert_eq!(state.transactions[0].category, "food");
+    assert!(state.categories.iter().any(|c| c.id == "food"));
+}
+
+#[test]
+fn strings_with_quotes_and_braces_and_nested_objects_are_scanned_whole() {
+    let content = format!(
+        r#"{{"version": {}, "transactions": [
+            {{"id": "00000000-0000-0000-0000-000000000001", "title": "Sklep \"Pod {{Lipami}}\" ]", "amount": {{"minor": 1250, "currency": "PLN"}},
+              "kind": "expense", "date": "2024-03-02", "category": "food", "note": {{"inner": {{"text": "}}{{"}}}}}},
+            {{"id": "00000000-0000-0000-0000-000000000002", "title": "C:\\temp\\", "amount": {{"minor": 300, "currency": "PLN"}},
+              "kind": "expense", "date": "2024-03-03", "category": "food"}},
+            {{"id": "00000000-0000-0000-0000-000000000003", "title": "Ucięta", "amount": {{"minor": 9"#,
+        SCHEMA_VERSION
+    );
+    let (state, skipped) = recovery::salvage(&content);
+
+    let titles: Vec<&str> = state.transactions.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, ["Sklep \"Pod {Lipami}\" ]", "C:\\temp\\"]);
+    assert_eq!(state.transactions[0].amount.minor, 1250);
+    assert_eq!(skipped, 0);
+}
+
+#[test]
+fn transactions_that_do_not_parse_are_counted_as_skipped() {
+    let content = format!(
+        r#"{{"version": {}, "transactions": [
+            {{"id": "00000000-0000-0000-0000-000000000001", "title": "Dobra", "amount": {{"minor": 100, "currency": "PLN"}},
+              "kind": "expense", "date": "2024-03-02", "category": "food"}},
+            {{"id": "00000000-0000-0000-0000-000000000002", "title": "Zła kwota", "amount": "dużo",
+              "kind": "expense", "date": "2024-03-02", "category": "food"}},
+            {{"id": "00000000-0000-0000-0000-000000000003", "title": "Zła data", "amount": {{"minor": 100, "currency": "PLN"}},
+              "kind": "expense", "date": "wczoraj", "category": "food"}}
+        ], "limits": {{}}}}"#,
+        SCHEMA_VERSION
+    );
+    let (state, skipped) = recovery::salvage(&content);
+    assert_eq!(state.transactions.len(), 1);
+    assert_eq!(skipped, 2);
+
+    // Ucięty plik liczy tak samo - niekompletny ostatni obiekt nie jest pominiętą transakcją
+    let (state, skipped) = recovery::salvage(&content[..content.find("\"Zła data\"").unwrap()]);
+    assert_eq!((state.transactions.len(), skipped), (1, 1));
+}
+
+#[test]
+fn documents_from_a_newer_version_are_not_salvaged_or_quarantined() {
+    let content = format!(
+        r#"{{"version": {}, "transactions": [
+            {{"id": "00000000-0000-0000-0000-000000000001", "title": "Z przyszłości", "amount": {{"minor": 100, "currency": "PLN"}},
+              "kind": "expense", "date": "2024-03-02", "category": "food"}},
+            {{"id": "#,
+        SCHEMA_VERSION + 1
+    );
+    let (state, skipped) = recovery::salvage(&content);
+    assert!(state.transactions.is_empty());
+    assert_eq!(skipped, 1);
+
+    let dir = temp_dir("recovery_newer");
+    let path = dir.join("expense_data.json");
+    fs::write(&path, format!(r#"{{"version": {}, "transactions": []}}"#, SCHEMA_VERSION + 1)).unwrap();
+    assert!(matches!(
+        recovery::load_or_quarantine(&path),
+        Err(LoadError::UnsupportedVersion { found, .. }) if found == SCHEMA_VERSION + 1
+    ));
+    assert!(path.exists());
+}
+
+#[test]
+fn unreadable_files_are_quarantined_under_a_timestamped_name() {
+    let dir = temp_dir("recovery_quarantine");
+    let path = dir.join("expense_data.json");
+
+    let mut quarantined = vec![];
+    for _ in 0..2 {
+        fs::write(&path, truncated_v1()).unwrap();
+        match recovery::load_or_quarantine(&path) {
+            Err(LoadError::Corrupted { quarantined: target, .. }) => quarantined.push(target),
+            other => panic!("{:?}", other),
+        }
+        assert!(!path.exists());
+    }
+
+    // expense_data.corrupt-RRRRMMDD-GGMMSS-N.json; licznik odróżnia pliki z tej samej sekundy
+    let names: Vec<String> = quarantined
+        .iter()
+        .map(|target| Path::new(target).file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    for name in &names {
+        assert!(name.starts_with("expense_data.corrupt-"), "{}", name);
+        assert!(name.ends_with(".json"), "{}", name);
+        assert_eq!(name.len(), "expense_data.corrupt-20240101-120000-1.json".len(), "{}", name);
+    }
+    assert_ne!(names[0], names[1]);
+}
+
+#[test]
+fn salvage_reads_the_newest_quarantined_file() {
+    let dir = temp_dir("recovery_latest");
+    let older = truncated_v1();
+    let full = include_str!("fixtures/v1.json");
+    let newer = &full[..full.find("\"Czynsz\"").unwrap()];
+    fs::write(dir.join("expense_data.corrupt-20240301-120000-1.json"), newer).unwrap();
+    fs::write(dir.join("expense_data.corrupt-20240101-120000-1.json"), older).unwrap();
+    fs::write(dir.join("other.corrupt-20250101-120000-1.json"), older).unwrap();
+
+    let state = recovery::salvage_latest(&dir.join("expense_data.db")).unwrap();
+    let titles: Vec<&str> = state.transactions.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, ["Biedronka"]);
+
+    let empty = temp_dir("recovery_nothing");
+    assert!(matches!(recovery::salvage_latest(&empty.join("expense_data.db")), Err(LoadError::NothingToSalvage)));
+}
@@ -0,0 +1,95 @@
+use chrono::{NaiveDate, Weekday};
+use expense_tracker_rust_lib::models::{
+    Category, CurrencyCode, Frequency, Money, NthWeekday, OccurrenceChange, RecurrenceEnd, RecurringTemplate, Schedule,
+    TransactionKind,
+};
+use expense_tracker_rust_lib::recurring;
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn schedule(start: &str, frequency: Frequency, interval: u32) -> Schedule {
+    Schedule { start: day(start), frequency, interval, nth_weekday: None, end: RecurrenceEnd::Never }
+}
+
+fn first(schedule: &Schedule, n: usize) -> Vec<String> {
+    recurring::dates(schedule).take(n).map(|d| d.to_string()).collect()
+}
+
+fn rent(schedule: Schedule) -> RecurringTemplate {
+    RecurringTemplate::new("Czynsz", Money::new(250000, CurrencyCode::PLN), TransactionKind::Expense, "bills", schedule)
+}
+
+#[test]
+fn schedules_follow_calendar_rules() {
+    assert_eq!(first(&schedule("2024-03-30", Frequency::Daily, 2), 3), ["2024-03-30", "2024-04-01", "2024-04-03"]);
+    assert_eq!(first(&schedule("2024-03-04", Frequency::Weekly, 2), 3), ["2024-03-04", "2024-03-18", "2024-04-01"]);
+    // 31. w krótszym miesiącu to jego ostatni dzień; potem znów 31.
+    assert_eq!(
+        first(&schedule("2024-01-31", Frequency::Monthly, 1), 4),
+        ["2024-01-31", "2024-02-29", "2024-03-31", "2024-04-30"]
+    );
+    assert_eq!(first(&schedule("2024-02-29", Frequency::Yearly, 1), 2), ["2024-02-29", "2025-02-28"]);
+
+    // Druga środa miesiąca, od startu w połowie marca
+    let second_wednesday = Schedule {
+        nth_weekday: Some(NthWeekday { nth: 2, weekday: Weekday::Wed }),
+        ..schedule("2024-03-15", Frequency::Monthly, 1)
+    };
+    assert_eq!(first(&second_wednesday, 2), ["2024-04-10", "2024-05-08"]);
+    let last_friday = Schedule {
+        nth_weekday: Some(NthWeekday { nth: -1, weekday: Weekday::Fri }),
+        ..schedule("2024-01-01", Frequency::Monthly, 1)
+    };
+    assert_eq!(first(&last_friday, 2), ["2024-01-26", "2024-02-23"]);
+
+    let until = Schedule { end: RecurrenceEnd::Until { date: day("2024-03-10") }, ..schedule("2024-01-10", Frequency::Monthly, 1) };
+    assert_eq!(first(&until, 10).len(), 3);
+    let count = Schedule { end: RecurrenceEnd::Count { count: 2 }, ..schedule("2024-01-10", Frequency::Monthly, 1) };
+    assert_eq!(first(&count, 10), ["2024-01-10", "2024-02-10"]);
+}
+
+#[test]
+fn due_occurrences_become_transactions_once() {
+    let mut templates = vec![rent(schedule("2024-01-10", Frequency::Monthly, 1))];
+    templates[0].changes = vec![
+        OccurrenceChange { date: day("2024-02-10"), skip: true, ..Default::default() },
+        OccurrenceChange { date: day("2024-03-10"), amount: Some(Money::new(260000, CurrencyCode::PLN)), ..Default::default() },
+        OccurrenceChange { date: day("2024-05-10"), title: Some("Czynsz + media".into()), ..Default::default() },
+    ];
+
+    let created = recurring::materialize(&mut templates, day("2024-03-15"));
+    let summary: Vec<(String, i64)> = created.iter().map(|t| (t.date.to_string(), t.amount.minor)).collect();
+    assert_eq!(summary, [("2024-01-10".to_string(), 250000), ("2024-03-10".to_string(), 260000)]);
+    assert_eq!(created[0].external_id.as_deref(), Some(format!("recurring:{}:2024-01-10", templates[0].id).as_str()));
+    assert_eq!(templates[0].materialized_until, Some(day("2024-03-10")));
+    assert_eq!(templates[0].changes.len(), 1);
+
+    // Kolejny start tego samego dnia niczego nie dubluje
+    assert!(recurring::materialize(&mut templates, day("2024-03-15")).is_empty());
+
+    let upcoming = recurring::upcoming(&templates, day("2024-05-31"));
+    let titles: Vec<(String, &str)> = upcoming.iter().map(|o| (o.date.to_string(), o.transaction.title.as_str())).collect();
+    assert_eq!(titles, [("2024-04-10".to_string(), "Czynsz"), ("2024-05-10".to_string(), "Czynsz + media")]);
+    assert!(recurring::is_pending(&templates[0], day("2024-04-10")));
+    assert!(!recurring::is_pending(&templates[0], day("2024-03-10")));
+    assert!(!recurring::is_pending(&templates[0], day("2024-04-11")));
+}
+
+#[test]
+fn invalid_templates_are_rejected() {
+    let categories = Category::defaults();
+    assert!(recurring::validate(&rent(schedule("2024-01-10", Frequency::Monthly, 1)), &categories).is_ok());
+
+    let error = |template: RecurringTemplate| recurring::validate(&template, &categories).unwrap_err();
+    assert!(error(RecurringTemplate { category: "salary".into(), ..rent(schedule("2024-01-10", Frequency::Monthly, 1)) }).contains("salary"));
+    assert!(error(rent(schedule("2024-01-10", Frequency::Monthly, 0))).contains("Odstęp"));
+    let weekly_nth = Schedule {
+        nth_weekday: Some(NthWeekday { nth: 1, weekday: Weekday::Mon }),
+        ..schedule("2024-01-10", Frequency::Weekly, 1)
+    };
+    assert!(error(rent(weekly_nth)).contains("co miesiąc"));
+    let ended = Schedule { end: RecurrenceEnd::Until { date: day("2023-12-31") }, ..schedule("2024-01-10", Frequency::Monthly, 1) };
+    assert!(error(rent(ended)).contains("końca"));
+}
@@ -0,0 +1,107 @@
+use chrono::NaiveDate;
+use expense_tracker_rust_lib::models::{
+    Category, CategoryRule, CurrencyCode, Money, RuleConditions, Transaction, TransactionId, TransactionKind,
+    FALLBACK_CATEGORY,
+};
+use expense_tracker_rust_lib::rules::{self, RuleSet};
+
+fn transaction(title: &str, minor: i64, kind: TransactionKind) -> Transaction {
+    Transaction {
+        id: TransactionId::generate(),
+        title: title.to_string(),
+        amount: Money::new(minor, CurrencyCode::PLN),
+        kind,
+        date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        category: FALLBACK_CATEGORY.to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+fn rule(category: &str, conditions: RuleConditions) -> CategoryRule {
+    CategoryRule { conditions, ..CategoryRule::new(category) }
+}
+
+fn contains(text: &str) -> RuleConditions {
+    RuleConditions { title_contains: text.to_string(), ..Default::default() }
+}
+
+fn pln(minor: i64) -> Option<Money> {
+    Some(Money::new(minor, CurrencyCode::PLN))
+}
+
+#[test]
+fn first_matching_rule_wins_and_only_for_its_kind() {
+    let categories = Category::defaults();
+    let list = vec![
+        rule("transport", contains("orlen")),
+        rule("food", contains("orlen")),
+        CategoryRule { enabled: false, ..rule("bills", contains("orange")) },
+        rule("refunds", contains("zwrot")),
+    ];
+    let set = RuleSet::new(&list, &categories);
+
+    let fuel = transaction("ORLEN Stacja nr 42", 20000, TransactionKind::Expense);
+    assert_eq!(set.find(&fuel).map(|r| r.category.as_str()), Some("transport"));
+    assert!(set.find(&transaction("Orange – Abonament", 5000, TransactionKind::Expense)).is_none());
+
+    // Reguła kategorii przychodu nie zmienia wydatku o pasującym tytule
+    assert!(set.find(&transaction("Zwrot za bilet", 1500, TransactionKind::Expense)).is_none());
+    assert!(set.find(&transaction("Zwrot za bilet", 1500, TransactionKind::Income)).is_some());
+}
+
+#[test]
+fn conditions_cover_regex_payee_and_amount_range() {
+    let categories = Category::defaults();
+    let list = vec![
+        rule("bills", RuleConditions { title_regex: r"^faktura \d+/\d{4}$".to_string(), ..Default::default() }),
+        rule("entertainment", RuleConditions { payee: "Netflix".to_string(), ..Default::default() }),
+        rule("food", RuleConditions { title_contains: "sklep".to_string(), min_amount: pln(1000), max_amount: pln(5000), ..Default::default() }),
+    ];
+    let set = RuleSet::new(&list, &categories);
+    let category = |t: &Transaction| set.find(t).map(|r| r.category.clone());
+
+    assert_eq!(category(&transaction("FAKTURA 12/2024", 9900, TransactionKind::Expense)).as_deref(), Some("bills"));
+    assert_eq!(category(&transaction("Faktura 12/2024 korekta", 9900, TransactionKind::Expense)), None);
+    assert_eq!(category(&transaction("NETFLIX – Subskrypcja", 4300, TransactionKind::Expense)).as_deref(), Some("entertainment"));
+    assert_eq!(category(&transaction("Netflixowy sklep", 500, TransactionKind::Expense)), None);
+    assert_eq!(category(&transaction("Sklep osiedlowy", 1000, TransactionKind::Expense)).as_deref(), Some("food"));
+    assert_eq!(category(&transaction("Sklep osiedlowy", 5001, TransactionKind::Expense)), None);
+
+    // Granice kwoty w złotych nie pasują do transakcji w euro
+    let euro = Transaction { amount: Money::new(2000, CurrencyCode::EUR), ..transaction("Sklep", 0, TransactionKind::Expense) };
+    assert_eq!(category(&euro), None);
+}
+
+#[test]
+fn apply_sets_category_and_adds_missing_tags() {
+    let categories = Category::defaults();
+    let list = vec![CategoryRule { tags: vec!["auto".into(), "paliwo".into()], ..rule("transport", contains("orlen")) }];
+    let set = RuleSet::new(&list, &categories);
+
+    let mut fuel = Transaction { tags: vec!["paliwo".into()], ..transaction("Orlen", 20000, TransactionKind::Expense) };
+    assert!(rules::is_uncategorized(&fuel));
+    assert!(set.apply(&mut fuel));
+    assert_eq!(fuel.category, "transport");
+    assert_eq!(fuel.tags, vec!["paliwo".to_string(), "auto".to_string()]);
+    assert!(!rules::is_uncategorized(&fuel));
+    assert!(!set.apply(&mut fuel));
+
+    let mut other = transaction("Lidl", 3000, TransactionKind::Expense);
+    assert!(!set.apply(&mut other));
+    assert_eq!(other.category, FALLBACK_CATEGORY);
+}
+
+#[test]
+fn invalid_rules_are_rejected_before_saving() {
+    let categories = Category::defaults();
+    let error = |r: CategoryRule| rules::validate(&r, &categories).unwrap_err();
+
+    assert!(rules::validate(&rule("food", contains("biedronka")), &categories).is_ok());
+    assert!(error(rule("food", RuleConditions::default())).contains("warunek"));
+    assert!(error(rule("nope", contains("x"))).contains("nope"));
+    assert!(error(rule("food", RuleConditions { title_regex: "(".to_string(), ..Default::default() })).contains("wyrażenie"));
+    assert!(error(rule("food", RuleConditions { min_amount: pln(500), max_amount: pln(100), ..Default::default() })).contains("minimalna"));
+    let mixed = RuleConditions { min_amount: pln(100), max_amount: Some(Money::new(500, CurrencyCode::EUR)), ..Default::default() };
+    assert!(error(rule("food", mixed)).contains("walucie"));
+}
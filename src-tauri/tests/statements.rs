@@ -0,0 +1,189 @@
+use chrono::NaiveDate;
+use expense_tracker_rust_lib::models::{Category, CurrencyCode, Money, TransactionKind};
+use expense_tracker_rust_lib::statement;
+use std::collections::HashSet;
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+const OFX_SGML: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+CHARSET:1252
+
+<OFX>
+<BANKMSGSRSV1><STMTTRNRS><STMTRS>
+<CURDEF>EUR
+<BANKACCTFROM><BANKID>123<ACCTID>987654<ACCTTYPE>CHECKING</BANKACCTFROM>
+<BANKTRANLIST><DTSTART>20240101<DTEND>20240131
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20240105120000.000[-5:EST]
+<TRNAMT>-42.50
+<FITID>A1
+<NAME>Tesco &amp; Co
+<MEMO>Card 1234
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20240125
+<TRNAMT>2500.00
+<FITID>A2
+<NAME>ACME Payroll
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>2024
+<TRNAMT>-1.00
+<FITID>A3
+<NAME>Broken date
+</STMTTRN>
+</BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>
+";
+
+#[test]
+fn ofx_entries_become_transactions_in_statement_currency() {
+    let entries = statement::parse(OFX_SGML.as_bytes(), CurrencyCode::PLN).unwrap();
+    let rows = statement::to_rows(entries, &Category::defaults(), &HashSet::new());
+    assert_eq!(rows.len(), 3);
+
+    let eur = CurrencyCode::EUR;
+    let tesco = rows[0].transaction.as_ref().unwrap();
+    assert_eq!(tesco.title, "Tesco & Co – Card 1234");
+    assert_eq!(tesco.amount, Money::new(4250, eur));
+    assert_eq!((tesco.kind, tesco.date), (TransactionKind::Expense, day("2024-01-05")));
+    assert_eq!(tesco.external_id.as_deref(), Some("ofx:987654:A1"));
+    assert_eq!(rows[0].line, 11);
+
+    let salary = rows[1].transaction.as_ref().unwrap();
+    assert_eq!((salary.kind, salary.amount), (TransactionKind::Income, Money::new(250000, eur)));
+    assert_eq!(rows[2].error.as_deref(), Some("Niepoprawna data"));
+}
+
+#[test]
+fn known_fitids_are_skipped() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="220"?>
+<OFX><CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS>
+<CURDEF>USD</CURDEF>
+<CCACCTFROM><ACCTID>4111</ACCTID></CCACCTFROM>
+<BANKTRANLIST>
+<STMTTRN><TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20240201</DTPOSTED><TRNAMT>-10.00</TRNAMT><FITID>X1</FITID><NAME>Cafe</NAME></STMTTRN>
+<STMTTRN><TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20240202</DTPOSTED><TRNAMT>-12.00</TRNAMT><FITID>X2</FITID><NAME>Cafe</NAME></STMTTRN>
+<STMTTRN><TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20240202</DTPOSTED><TRNAMT>-12.00</TRNAMT><FITID>X2</FITID><NAME>Cafe</NAME></STMTTRN>
+</BANKTRANLIST></CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1></OFX>"#;
+    let known = HashSet::from(["ofx:4111:X1".to_string()]);
+    let entries = statement::parse(xml.as_bytes(), CurrencyCode::PLN).unwrap();
+    let rows = statement::to_rows(entries, &[], &known);
+
+    assert!(rows[0].error.as_deref().unwrap().contains("już zaimportowana"));
+    let cafe = rows[1].transaction.as_ref().unwrap();
+    assert_eq!(cafe.amount, Money::new(1200, CurrencyCode::new("USD").unwrap()));
+    assert!(rows[2].error.as_deref().unwrap().contains("powtarza"));
+}
+
+#[test]
+fn qif_records_map_payee_category_and_dates() {
+    let qif = "!Account\nNChecking\nTBank\n^\n!Type:Bank\n\
+        D1/31'24\nT-1,234.56\nPLandlord\nMJanuary rent\nLBills\n^\n\
+        D02/01/2024\nT100.00\nPFriend\nL[Savings]\n^\n\
+        D2/1'24\nTabc\nPBad amount\n^\n\
+        !Type:Invst\nD2/2'24\nNBuy\nYACME\nT500.00\n^\n";
+    let entries = statement::parse(qif.as_bytes(), CurrencyCode::PLN).unwrap();
+    let rows = statement::to_rows(entries, &Category::defaults(), &HashSet::new());
+    assert_eq!(rows.len(), 4);
+
+    let rent = rows[0].transaction.as_ref().unwrap();
+    assert_eq!(rent.title, "Landlord – January rent");
+    assert_eq!(rent.amount, Money::new(123456, CurrencyCode::PLN));
+    assert_eq!((rent.date, rent.category.as_str()), (day("2024-01-31"), "bills"));
+    assert_eq!(rows[0].line, 6);
+
+    let gift = rows[1].transaction.as_ref().unwrap();
+    assert_eq!((gift.kind, gift.category.as_str()), (TransactionKind::Income, "other_income"));
+    assert_eq!(gift.date, day("2024-02-01"));
+    assert!(gift.external_id.is_some());
+
+    assert_eq!(rows[2].error.as_deref(), Some("Niepoprawna kwota"));
+    assert!(rows[3].error.as_deref().unwrap().contains("inwestycyjne"));
+}
+
+#[test]
+fn unknown_statement_formats_are_rejected() {
+    assert!(statement::parse(b"date;title;amount\n", CurrencyCode::PLN).is_err());
+}
+
+#[test]
+fn mt940_lines_use_booking_date_and_structured_details() {
+    let mt940 = ":20:ST240131\r\n:25:/PL61109010140000071219812874\r\n:28C:1/1\r\n\
+        :60F:C231229EUR1000,00\r\n\
+        :61:2312290102D42,50NTRFNONREF//BR0001\r\n\
+        :86:020~00Przelew~20Faktura FV/12/2023 za prą~21d~32Energa Obr\r\nót SA~38PL27114020040000\r\n\
+        :61:240105C2500,00NTRF\r\n\
+        :86:Wynagrodzenie za grudzień\r\nACME sp. z o.o.\r\n\
+        :61:240105XX1,00NTRFNONREF\r\n\
+        :62F:C240131EUR3457,50\r\n-\r\n";
+    let entries = statement::parse(mt940.as_bytes(), CurrencyCode::PLN).unwrap();
+    let rows = statement::to_rows(entries, &[], &HashSet::new());
+    assert_eq!(rows.len(), 3);
+
+    let energy = rows[0].transaction.as_ref().unwrap();
+    assert_eq!(energy.title, "Energa Obrót SA – Faktura FV/12/2023 za prąd");
+    assert_eq!((energy.kind, energy.date), (TransactionKind::Expense, day("2024-01-02")));
+    assert_eq!(energy.amount, Money::new(4250, CurrencyCode::EUR));
+    assert_eq!(energy.external_id.as_deref(), Some("mt940:PL61109010140000071219812874:BR0001"));
+    assert_eq!(rows[0].line, 5);
+
+    let salary = rows[1].transaction.as_ref().unwrap();
+    assert_eq!(salary.title, "Wynagrodzenie za grudzień ACME sp. z o.o.");
+    assert_eq!((salary.kind, salary.date), (TransactionKind::Income, day("2024-01-05")));
+    assert!(salary.external_id.as_deref().unwrap().starts_with("mt940:PL61109010140000071219812874:2024-01-05:250000:"));
+
+    assert_eq!(rows[2].error.as_deref(), Some("Niepoprawna kwota"));
+}
+
+#[test]
+fn camt053_entries_take_counterparty_by_direction() {
+    let camt = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.08">
+<BkToCstmrStmt><Stmt><Id>1</Id>
+<Acct><Id><IBAN>PL61109010140000071219812874</IBAN></Id></Acct>
+<Ntry>
+  <Amt Ccy="PLN">120.00</Amt><CdtDbtInd>DBIT</CdtDbtInd><Sts><Cd>BOOK</Cd></Sts>
+  <BookgDt><Dt>2024-03-04</Dt></BookgDt><ValDt><Dt>2024-03-05</Dt></ValDt>
+  <AcctSvcrRef>REF-1</AcctSvcrRef>
+  <NtryDtls><TxDtls>
+    <RltdPties><Dbtr><Pty><Nm>Jan Kowalski</Nm></Pty></Dbtr><Cdtr><Pty><Nm>Orange Polska</Nm></Pty></Cdtr></RltdPties>
+    <RmtInf><Ustrd>Abonament</Ustrd><Ustrd>marzec 2024</Ustrd></RmtInf>
+  </TxDtls></NtryDtls>
+</Ntry>
+<Ntry>
+  <Amt Ccy="EUR">15.5</Amt><CdtDbtInd>CRDT</CdtDbtInd><Sts>BOOK</Sts>
+  <BookgDt><DtTm>2024-03-06T09:15:00</DtTm></BookgDt>
+  <NtryDtls><TxDtls><RltdPties><Dbtr><Nm>Zwrot &amp; Spółka</Nm></Dbtr></RltdPties></TxDtls></NtryDtls>
+  <AddtlNtryInf>Zwrot za bilet</AddtlNtryInf>
+</Ntry>
+<Ntry>
+  <Amt Ccy="PLN">9.99</Amt><CdtDbtInd>DBIT</CdtDbtInd><Sts>PDNG</Sts>
+  <BookgDt><Dt>2024-03-07</Dt></BookgDt><AcctSvcrRef>REF-3</AcctSvcrRef>
+</Ntry>
+</Stmt></BkToCstmrStmt></Document>"#;
+    let entries = statement::parse(camt.as_bytes(), CurrencyCode::new("USD").unwrap()).unwrap();
+    let rows = statement::to_rows(entries, &[], &HashSet::new());
+    assert_eq!(rows.iter().map(|r| r.line).collect::<Vec<_>>(), [5, 14, 20]);
+
+    let phone = rows[0].transaction.as_ref().unwrap();
+    assert_eq!(phone.title, "Orange Polska – Abonament marzec 2024");
+    assert_eq!((phone.kind, phone.date), (TransactionKind::Expense, day("2024-03-04")));
+    assert_eq!(phone.amount, Money::new(12000, CurrencyCode::PLN));
+    assert_eq!(phone.external_id.as_deref(), Some("camt:PL61109010140000071219812874:REF-1"));
+
+    let refund = rows[1].transaction.as_ref().unwrap();
+    assert_eq!(refund.title, "Zwrot & Spółka – Zwrot za bilet");
+    assert_eq!((refund.kind, refund.date), (TransactionKind::Income, day("2024-03-06")));
+    assert_eq!(refund.amount, Money::new(1550, CurrencyCode::EUR));
+
+    assert!(rows[2].error.as_deref().unwrap().contains("zaksięgowana"));
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use common::temp_dir;
+use expense_tracker_rust_lib::storage::{self, BACKUP_GENERATIONS};
+use std::fs;
+
+#[test]
+fn each_write_moves_backups_one_generation_back() {
+    let dir = temp_dir("storage_rotation");
+    let path = dir.join("expense_data.json");
+
+    for n in 1..=BACKUP_GENERATIONS + 3 {
+        storage::write_atomic(&path, format!("zapis {}", n).as_bytes()).unwrap();
+    }
+
+    let last = BACKUP_GENERATIONS + 3;
+    assert_eq!(fs::read_to_string(&path).unwrap(), format!("zapis {}", last));
+    for generation in 1..=BACKUP_GENERATIONS {
+        let backup = fs::read_to_string(storage::backup_path(&path, generation)).unwrap();
+        assert_eq!(backup, format!("zapis {}", last - generation));
+    }
+    // Najstarsze kopie znikają, a po zapisie nie zostaje plik tymczasowy
+    assert!(!storage::backup_path(&path, BACKUP_GENERATIONS + 1).exists());
+    let files: Vec<String> = fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().into_owned()).collect();
+    assert_eq!(files.len(), BACKUP_GENERATIONS + 1);
+    assert!(files.iter().all(|name| !name.ends_with(".tmp")), "{:?}", files);
+}
+
+#[test]
+fn identical_content_does_not_rotate_backups() {
+    let dir = temp_dir("storage_identical");
+    let path = dir.join("expense_data.json");
+
+    storage::write_atomic(&path, b"pierwszy").unwrap();
+    storage::write_atomic(&path, b"drugi").unwrap();
+    storage::write_atomic(&path, b"drugi").unwrap();
+
+    assert_eq!(fs::read_to_string(storage::backup_path(&path, 1)).unwrap(), "pierwszy");
+    assert!(!storage::backup_path(&path, 2).exists());
+}
@@ -0,0 +1,79 @@
+use chrono::NaiveDate;
+use expense_tracker_rust_lib::models::{
+    CurrencyCode, Frequency, Money, RecurrenceEnd, RecurringTemplate, Schedule, Transaction, TransactionId,
+    TransactionKind,
+};
+use expense_tracker_rust_lib::subscriptions;
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn expense(title: &str, minor: i64, date: &str) -> Transaction {
+    Transaction {
+        id: TransactionId::generate(),
+        title: title.to_string(),
+        amount: Money::new(minor, CurrencyCode::PLN),
+        kind: TransactionKind::Expense,
+        date: day(date),
+        category: "entertainment".to_string(),
+        external_id: None,
+        tags: vec![],
+    }
+}
+
+fn history() -> Vec<Transaction> {
+    vec![
+        expense("NETFLIX.COM 1001", 4300, "2024-01-05"),
+        expense("Netflix.com 1002", 4300, "2024-02-06"),
+        expense("NETFLIX.COM 1003", 4900, "2024-03-05"),
+        expense("NETFLIX.COM 1004", 4900, "2024-04-05"),
+        // Zakupy w różnych kwotach i odstępach to nie subskrypcja
+        expense("Biedronka", 5620, "2024-01-03"),
+        expense("Biedronka", 12999, "2024-01-20"),
+        expense("Biedronka", 3150, "2024-03-30"),
+        expense("Siłownia – karnet", 9900, "2024-01-15"),
+        expense("Siłownia – karnet", 9900, "2024-02-14"),
+        expense("Siłownia – karnet", 9900, "2024-03-15"),
+        expense("Domena example.pl", 6000, "2023-02-01"),
+        expense("Domena example.pl", 6500, "2024-02-02"),
+    ]
+}
+
+#[test]
+fn regular_payments_become_subscriptions_with_yearly_cost_and_price_increases() {
+    let found = subscriptions::detect(&history(), &[], day("2024-04-20"));
+    let summary: Vec<(&str, usize, i64)> = found.iter().map(|s| (s.payee.as_str(), s.payments, s.yearly_cost.minor)).collect();
+    assert_eq!(summary, [("Siłownia", 3, 118800), ("NETFLIX.COM", 4, 58800), ("Domena example.pl", 2, 6500)]);
+
+    let netflix = &found[1];
+    assert_eq!(netflix.price_increases.len(), 1);
+    assert_eq!(netflix.price_increases[0].date, day("2024-03-05"));
+    assert_eq!((netflix.price_increases[0].from.minor, netflix.price_increases[0].to.minor), (4300, 4900));
+
+    // Szablon od następnej płatności, z ostatnią ceną
+    let template = &netflix.template;
+    assert_eq!((template.schedule.frequency, template.schedule.interval), (Frequency::Monthly, 1));
+    assert_eq!(template.schedule.start, day("2024-05-05"));
+    assert_eq!(template.amount.minor, 4900);
+    assert_eq!(template.category, "entertainment");
+    assert_eq!(found[2].template.schedule.frequency, Frequency::Yearly);
+}
+
+#[test]
+fn known_stale_and_materialized_payments_are_skipped() {
+    let gym = Schedule { start: day("2024-04-15"), frequency: Frequency::Monthly, interval: 1, nth_weekday: None, end: RecurrenceEnd::Never };
+    let templates = [RecurringTemplate::new("Siłownia", Money::new(9900, CurrencyCode::PLN), TransactionKind::Expense, "sport", gym)];
+    let payees: Vec<String> = subscriptions::detect(&history(), &templates, day("2024-04-20")).into_iter().map(|s| s.payee).collect();
+    assert_eq!(payees, ["NETFLIX.COM", "Domena example.pl"]);
+
+    // Po trzech miesiącach bez płatności subskrypcja przestaje być aktywna
+    let payees: Vec<String> = subscriptions::detect(&history(), &templates, day("2024-07-20")).into_iter().map(|s| s.payee).collect();
+    assert_eq!(payees, ["Domena example.pl"]);
+
+    let mut materialized = history();
+    for t in &mut materialized {
+        t.external_id = Some(format!("recurring:rt-1:{}", t.date));
+    }
+    assert!(subscriptions::detect(&materialized, &[], day("2024-04-20")).is_empty());
+}
@@ -1,3 +1,5 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use wasm_bindgen::prelude::*;
 
 // Komunikacja z Tauri
@@ -5,4 +7,49 @@ use wasm_bindgen::prelude::*;
 extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
     pub async fn invoke(cmd: &str, args: JsValue) -> JsValue;
-}
\ No newline at end of file
+
+    // Wariant zwracający błąd komendy (odrzucony Promise) zamiast wyjątku
+    #[wasm_bindgen(catch, js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
+    pub async fn try_invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    // Okno wyboru pliku z wtyczki dialog; zwraca ścieżkę albo null po anulowaniu
+    #[wasm_bindgen(catch, js_namespace = ["window", "__TAURI__", "dialog"], js_name = open)]
+    async fn open_dialog(options: JsValue) -> Result<JsValue, JsValue>;
+}
+
+// Wywołanie komendy z typowanymi argumentami i wynikiem; błąd jako tekst z backendu
+pub async fn call<A: Serialize, R: DeserializeOwned>(cmd: &str, args: &A) -> Result<R, String> {
+    let args = serde_wasm_bindgen::to_value(args).map_err(|e| e.to_string())?;
+    match try_invoke(cmd, args).await {
+        Ok(value) => serde_wasm_bindgen::from_value(value).map_err(|e| e.to_string()),
+        Err(err) => Err(err.as_string().unwrap_or_else(|| format!("{:?}", err))),
+    }
+}
+
+#[derive(Serialize)]
+pub struct NoArgs {}
+
+#[derive(Serialize)]
+struct FileFilter {
+    name: String,
+    extensions: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct OpenOptions {
+    multiple: bool,
+    filters: Vec<FileFilter>,
+}
+
+// Wybór jednego pliku o podanych rozszerzeniach; None, gdy użytkownik zrezygnował
+pub async fn pick_file(name: &str, extensions: &[&str]) -> Option<String> {
+    let options = OpenOptions {
+        multiple: false,
+        filters: vec![FileFilter {
+            name: name.to_string(),
+            extensions: extensions.iter().map(|e| e.to_string()).collect(),
+        }],
+    };
+    let options = serde_wasm_bindgen::to_value(&options).ok()?;
+    open_dialog(options).await.ok()?.as_string()
+}
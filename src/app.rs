@@ -4,18 +4,43 @@ use leptos::task::spawn_local;
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
-use crate::api::invoke;
+use crate::api::{call, try_invoke, NoArgs};
+use crate::breakdown::CategoryBreakdown;
+use crate::categories::CategoryManager;
+use crate::rules::RuleManager;
+use crate::recurring::RecurringManager;
+use crate::duplicates::DuplicateNotice;
+use crate::export::ExportPanel;
+use crate::import::ImportWizard;
+use crate::rates::RateManager;
 use crate::models::*;
+use crate::recovery::RecoveryScreen;
 use crate::translate::t;
 use crate::utils::*;
 
+#[derive(serde::Serialize)]
+struct TransactionArgs {
+    transaction: Transaction,
+}
+
 #[component]
 pub fn App() -> impl IntoView {
     // 1. STANY APLIKACJI
 
     // Dane
     let (transactions, set_transactions) = signal::<Vec<Transaction>>(vec![]);
+    let (year_transactions, set_year_transactions) = signal::<Vec<Transaction>>(vec![]);
     let (all_limits, set_all_limits) = signal::<HashMap<String, MonthlyLimitData>>(HashMap::new());
+    let (limits_notice, set_limits_notice) = signal::<Option<String>>(None);
+    let (budget, set_budget) = signal(Budget::default());
+    // Kwoty przeniesione z poprzednich miesięcy do wybranego i do edytowanego miesiąca
+    let (carried, set_carried) = signal::<HashMap<String, Money>>(HashMap::new());
+    let (editing_carried, set_editing_carried) = signal::<HashMap<String, Money>>(HashMap::new());
+    // Przeniesienia do każdego miesiąca roku w podsumowaniu rocznym
+    let (year_carried, set_year_carried) = signal::<Vec<HashMap<String, Money>>>(vec![]);
+    let (categories, set_categories) = signal::<Vec<Category>>(vec![]);
+    // Kursy na walutę wyświetlania dla widocznych transakcji i limitów
+    let (display_rates, set_display_rates) = signal(DisplayRates::empty(CurrencyCode::PLN));
 
     // Konfiguracja
     let (theme, set_theme) = signal("light".to_string());
@@ -27,115 +52,337 @@ pub fn App() -> impl IntoView {
     let (show_settings, set_show_settings) = signal(false);
     let (show_yearly, set_show_yearly) = signal(false);
     let (show_save_toast, set_show_save_toast) = signal(false);
+    let (export_msg, set_export_msg) = signal::<Option<String>>(None);
     let (is_loaded, set_is_loaded) = signal(false);
+    let (load_error, set_load_error) = signal::<Option<LoadError>>(None);
 
     // Formularz
     let (title, set_title) = signal("".to_string());
     let (amount, set_amount) = signal("".to_string());
     let (date, set_date) = signal(Local::now().format("%Y-%m-%d").to_string());
-    let (category, set_category) = signal("Ogólne".to_string());
+    let (category, set_category) = signal(FALLBACK_CATEGORY.to_string());
+    let (kind, set_kind) = signal(TransactionKind::Expense);
+    // Waluta wpisywanej kwoty; None = waluta wyświetlania
+    let (form_currency, set_form_currency) = signal::<Option<CurrencyCode>>(None);
+    // Tagi po przecinku
+    let (tags, set_tags) = signal(String::new());
+    // Kategorię wybrano ręcznie - podpowiedzi z reguł już jej nie zmieniają
+    let (category_touched, set_category_touched) = signal(false);
+    // Podpowiedź kategorii z historii, gdy żadna reguła nie pasuje
+    let (suggestion, set_suggestion) = signal::<Option<CategorySuggestion>>(None);
+    // Edytowana transakcja; None = formularz dodaje nową
+    let (editing, set_editing) = signal::<Option<Transaction>>(None);
+    // Dodana transakcja i zapisane, które mogą być jej duplikatami
+    let (possible_duplicates, set_possible_duplicates) = signal::<Option<(Transaction, Vec<Transaction>)>>(None);
 
     // Filtry dat
     let (selected_month_str, set_selected_month_str) = signal(Local::now().format("%Y-%m").to_string());
     let (limits_month_str, set_limits_month_str) = signal(Local::now().format("%Y-%m").to_string());
 
-    // Listy statyczne
-    let categories_list = vec![
-        "Codzienne Wydatki",
-        "Rachunki",
-        "Jedzenie",
-        "Auto i Transport",
-        "Rozrywka",
-        "Nieskategoryzowane",
-    ];
-    let categories_list_limits = categories_list.clone();
-
     // Helper: czy ciemny motyw
     let is_dark = move || theme.get() == "dark";
+    let display_currency = Memo::new(move |_| CurrencyCode::new(&currency.get()).unwrap_or(CurrencyCode::PLN));
+    // Miejsca po przecinku waluty wpisywanej kwoty (JPY 0, PLN 2, KWD 3)
+    let amount_digits = move || form_currency.get().unwrap_or(display_currency.get()).minor_digits() as usize;
 
     // 2. STORAGE (Komunikacja z Backendem)
+    // Dane należą do backendu: frontend trzyma tylko wyświetlany miesiąc (i rok w podsumowaniu),
+    // a każda zmiana idzie osobną komendą. Po zapisie podbijamy `revision`, co odświeża listy.
+
+    let (revision, set_revision) = signal(0u32);
+    let refresh = move || set_revision.update(|r| *r += 1);
+
+    let reload_categories = move || {
+        spawn_local(async move {
+            if let Ok(list) = call::<_, Vec<Category>>("load_categories", &NoArgs {}).await {
+                set_categories.set(list);
+            }
+        });
+    };
+
+    let apply_settings = move |settings: Settings| {
+        set_theme.set(settings.theme);
+        set_language.set(settings.language);
+        set_currency.set(settings.currency);
+        set_load_error.set(None);
+        spawn_local(async move {
+            if let Ok(limits) = call::<_, HashMap<String, MonthlyLimitData>>("load_limits", &NoArgs {}).await {
+                set_all_limits.set(limits);
+            }
+            if let Ok(loaded) = call::<_, Budget>("load_budget", &NoArgs {}).await {
+                set_budget.set(loaded);
+            }
+            if let Ok(list) = call::<_, Vec<Category>>("load_categories", &NoArgs {}).await {
+                set_categories.set(list);
+            }
+            set_is_loaded.set(true);
+        });
+    };
 
-    // Ładowanie
+    // Ładowanie. Przy błędzie nic nie jest zapisywane (is_loaded == false) do czasu
+    // decyzji użytkownika na ekranie odzyskiwania.
     Effect::new(move |_| {
         spawn_local(async move {
-            let result = invoke("load_data", JsValue::NULL).await;
-            if let Ok(state) = serde_wasm_bindgen::from_value::<AppState>(result) {
-                set_transactions.set(state.transactions);
-                set_all_limits.set(state.limits);
-                set_theme.set(state.theme);
-                set_language.set(state.language);
-                set_currency.set(state.currency);
-                set_is_loaded.set(true);
+            match try_invoke("load_data", JsValue::NULL).await {
+                Ok(result) => {
+                    if let Ok(settings) = serde_wasm_bindgen::from_value::<Settings>(result) {
+                        apply_settings(settings);
+                    }
+                }
+                Err(err) => {
+                    let error = serde_wasm_bindgen::from_value::<LoadError>(err.clone()).unwrap_or(LoadError::Io {
+                        message: err.as_string().unwrap_or_default(),
+                    });
+                    set_load_error.set(Some(error));
+                }
             }
         });
     });
+    let on_recovered = Callback::new(apply_settings);
 
-    // Zapis
+    // Transakcje wybranego miesiąca
     Effect::new(move |_| {
-        let current_transactions = transactions.get();
-        let current_limits = all_limits.get();
-        let current_theme = theme.get();
-        let current_language = language.get();
-        let current_currency = currency.get();
-        let loaded = is_loaded.get();
-
-        if loaded {
-            let state_data = AppState {
-                transactions: current_transactions,
-                limits: current_limits,
-                theme: current_theme,
-                language: current_language,
-                currency: current_currency,
-            };
+        let month = selected_month_str.get();
+        revision.track();
+        if is_loaded.get() {
+            spawn_local(async move {
+                #[derive(serde::Serialize)]
+                struct QueryArgs {
+                    month: String,
+                }
+                if let Ok(list) = call("query_transactions", &QueryArgs { month }).await {
+                    set_transactions.set(list);
+                }
+            });
+        }
+    });
 
-            #[derive(serde::Serialize)]
-            struct SaveArgs {
-                state: AppState,
-            }
+    // Transakcje całego roku - tylko gdy podsumowanie roczne jest rozwinięte
+    Effect::new(move |_| {
+        let year = parsed_date_from_str(&selected_month_str.get()).year();
+        revision.track();
+        if is_loaded.get() && show_yearly.get() {
+            spawn_local(async move {
+                #[derive(serde::Serialize)]
+                struct YearArgs {
+                    year: i32,
+                }
+                if let Ok(list) = call("query_year", &YearArgs { year }).await {
+                    set_year_transactions.set(list);
+                }
+            });
+        }
+    });
 
-            let args_wrapper = SaveArgs { state: state_data };
+    // Przeniesienia limitów do miesięcy roku - jak na pulpicie, liczy je backend
+    Effect::new(move |_| {
+        let year = parsed_date_from_str(&selected_month_str.get()).year();
+        let currency = display_currency.get();
+        all_limits.track();
+        budget.track();
+        revision.track();
+        if is_loaded.get() && show_yearly.get() {
+            spawn_local(async move {
+                #[derive(serde::Serialize)]
+                struct YearCarriedArgs {
+                    year: i32,
+                    currency: CurrencyCode,
+                }
+                if let Ok(found) = call("year_carried_limits", &YearCarriedArgs { year, currency }).await {
+                    set_year_carried.set(found);
+                }
+            });
+        }
+    });
 
+    // Kursy do przeliczeń: rok wybranego miesiąca i (jeśli inny) rok edytowanych limitów
+    Effect::new(move |_| {
+        let mut years = vec![
+            parsed_date_from_str(&selected_month_str.get()).year(),
+            parsed_date_from_str(&limits_month_str.get()).year(),
+        ];
+        years.dedup();
+        let currency = display_currency.get();
+        revision.track();
+        if is_loaded.get() {
             spawn_local(async move {
-                let args_js = serde_wasm_bindgen::to_value(&args_wrapper).unwrap();
-                let _ = invoke("save_data", args_js).await;
+                #[derive(serde::Serialize)]
+                struct RatesArgs {
+                    year: i32,
+                    currency: CurrencyCode,
+                }
+                let mut rates = DisplayRates::empty(currency);
+                for year in years {
+                    if let Ok(found) = call::<_, DisplayRates>("display_rates", &RatesArgs { year, currency }).await {
+                        rates.rates.extend(found.rates);
+                    }
+                }
+                set_display_rates.set(rates);
+            });
+        }
+    });
+
+    // Przeniesienia zależą od limitów i wydatków poprzednich miesięcy - liczy je backend
+    Effect::new(move |_| {
+        let (selected, editing) = (selected_month_str.get(), limits_month_str.get());
+        let currency = display_currency.get();
+        all_limits.track();
+        budget.track();
+        revision.track();
+        if is_loaded.get() {
+            spawn_local(async move {
+                #[derive(serde::Serialize)]
+                struct CarriedArgs {
+                    month: String,
+                    currency: CurrencyCode,
+                }
+                if let Ok(found) = call("carried_limits", &CarriedArgs { month: selected, currency }).await {
+                    set_carried.set(found);
+                }
+                if let Ok(found) = call("carried_limits", &CarriedArgs { month: editing, currency }).await {
+                    set_editing_carried.set(found);
+                }
+            });
+        }
+    });
+
+    // Zapis ustawień
+    Effect::new(move |_| {
+        let settings = Settings {
+            theme: theme.get(),
+            language: language.get(),
+            currency: currency.get(),
+        };
+        if is_loaded.get() {
+            #[derive(serde::Serialize)]
+            struct SettingsArgs {
+                settings: Settings,
+            }
+            spawn_local(async move {
+                let _ = call::<_, ()>("update_settings", &SettingsArgs { settings }).await;
             });
         }
     });
 
     // 3. OBLICZENIA
 
+    // Kategorie do wyboru w formularzu: aktywne, pasujące do rodzaju transakcji
+    // (plus zarchiwizowana kategoria edytowanej transakcji, żeby jej nie zgubić)
+    let form_categories = Memo::new(move |_| {
+        let kind = kind.get();
+        let kept = editing.get().map(|tx| tx.category);
+        let list = categories.get();
+        Category::tree(&list)
+            .into_iter()
+            .map(|(c, _)| c)
+            .filter(|c| c.kind == kind && (!c.archived || kept.as_ref() == Some(&c.id)))
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    // Wybrana kategoria musi należeć do listy (np. po zmianie rodzaju albo scaleniu)
+    Effect::new(move |_| {
+        let list = form_categories.get();
+        let current = category.get_untracked();
+        if !list.is_empty() && !list.iter().any(|c| c.id == current) {
+            set_category.set(list[0].id.clone());
+        }
+    });
+
+    // Podpowiedź z reguł kategoryzacji: dopóki użytkownik sam nie wybrał kategorii,
+    // nowa transakcja dostaje kategorię i tagi pierwszej pasującej reguły. Bez pasującej
+    // reguły kategorię podpowiada historia - nadajemy ją tylko przy dużej pewności.
+    Effect::new(move |_| {
+        let (draft_title, draft_amount, draft_kind) = (title.get(), amount.get(), kind.get());
+        let draft_currency = form_currency.get().unwrap_or(display_currency.get());
+        if category_touched.get_untracked() || editing.get_untracked().is_some() || draft_title.trim().is_empty() {
+            set_suggestion.set(None);
+            return;
+        }
+        let transaction = Transaction {
+            id: TransactionId::generate(),
+            title: draft_title,
+            amount: Money::parse(&draft_amount, draft_currency).unwrap_or(Money::zero(draft_currency)),
+            kind: draft_kind,
+            date: NaiveDate::parse_from_str(&date.get_untracked(), "%Y-%m-%d").unwrap_or_else(|_| Local::now().date_naive()),
+            category: String::new(),
+            external_id: None,
+            tags: vec![],
+        };
+        spawn_local(async move {
+            let args = TransactionArgs { transaction };
+            if let Ok(Some(rule)) = call::<_, Option<CategoryRule>>("match_rule", &args).await {
+                set_suggestion.set(None);
+                set_category.set(rule.category);
+                set_tags.set(rule.tags.join(", "));
+                return;
+            }
+            let learned = call::<_, Option<CategorySuggestion>>("suggest_category", &args).await.ok().flatten();
+            if let Some(s) = learned.as_ref().filter(|s| s.confidence >= AUTO_APPLY_CONFIDENCE) {
+                set_category.set(s.category.clone());
+            }
+            set_suggestion.set(learned);
+        });
+    });
+
+    // Kategorie wydatków w kolejności drzewa, z poziomem zagłębienia
+    let expense_categories = Memo::new(move |_| {
+        let list = categories.get();
+        Category::tree(&list)
+            .into_iter()
+            .filter(|(c, _)| c.kind == TransactionKind::Expense && !c.archived)
+            .map(|(c, depth)| (c.clone(), depth))
+            .collect::<Vec<_>>()
+    });
+
+    // Bieżący miesiąc "RRRR-MM" - od niego miesiące bez własnych limitów biorą szablon
+    let this_month = move || Local::now().format("%Y-%m").to_string();
+    let month_limits = move |key: &str| all_limits.with(|limits| budget.with(|b| b.limits_for(limits, key, &this_month())));
+
+    // Limity wybranego miesiąca w walucie wyświetlania
     let current_month_limits = Memo::new(move |_| {
         let key = selected_month_str.get();
-        all_limits
-            .get()
-            .get(&key)
-            .cloned()
-            .unwrap_or(MonthlyLimitData {
-                general: 0.0,
-                categories: HashMap::new(),
-            })
+        let currency = display_currency.get();
+        month_limits(&key)
+            .map(|limits| display_rates.with(|rates| limits.in_currency(&key, currency, rates)))
+            .unwrap_or(MonthlyLimitData::empty(currency))
     });
 
+    // Limity edytowanego miesiąca tak, jak są zapisane (każda kwota w swojej walucie)
     let editing_month_limits = Memo::new(move |_| {
         let key = limits_month_str.get();
-        all_limits
-            .get()
-            .get(&key)
-            .cloned()
-            .unwrap_or(MonthlyLimitData {
-                general: 0.0,
-                categories: HashMap::new(),
-            })
+        month_limits(&key).unwrap_or(MonthlyLimitData::empty(display_currency.get()))
     });
 
-    let current_month_total = Memo::new(move |_| {
+    // ... i przeliczone do pól formularza
+    let editing_month_limits_shown = Memo::new(move |_| {
+        let key = limits_month_str.get();
+        display_rates.with(|rates| editing_month_limits.get().in_currency(&key, display_currency.get(), rates))
+    });
+
+    // Transakcje miesiąca przeliczone na walutę wyświetlania + liczba pominiętych bez kursu
+    let current_month_converted = Memo::new(move |_| {
         let sel_str = selected_month_str.get();
-        transactions
-            .get()
-            .iter()
-            .filter(|t| t.date.starts_with(&sel_str))
-            .map(|t| t.amount)
-            .sum::<f64>()
+        display_rates.with(|rates| {
+            in_currency(
+                transactions.get().iter().filter(|t| is_in_month(t.date, &sel_str)),
+                display_currency.get(),
+                rates,
+            )
+        })
+    });
+
+    let current_month_flow = Memo::new(move |_| {
+        CashFlow::of(&current_month_converted.get().0, display_currency.get())
+    });
+
+    let current_month_breakdown = Memo::new(move |_| {
+        current_month_limits.get().breakdown(
+            &current_month_converted.get().0,
+            &categories.get(),
+            display_currency.get(),
+            &carried.get(),
+        )
     });
 
     let current_month_count = Memo::new(move |_| {
@@ -143,130 +390,237 @@ pub fn App() -> impl IntoView {
         transactions
             .get()
             .iter()
-            .filter(|t| t.date.starts_with(&sel_str))
+            .filter(|t| is_in_month(t.date, &sel_str))
             .count()
     });
 
     let yearly_summary = Memo::new(move |_| {
         let sel_year = parsed_date_from_str(&selected_month_str.get()).year();
-        let mut summary = vec![(0.0, 0.0); 12];
-        let txs = transactions.get();
-        let limits_map = all_limits.get();
-
-        for t in txs {
-            if let Ok(d) = NaiveDate::parse_from_str(&t.date, "%Y-%m-%d") {
-                if d.year() == sel_year {
-                    summary[(d.month() - 1) as usize].0 += t.amount;
-                }
-            }
-        }
-        for month_num in 1..=12 {
-            let key = format!("{}-{:02}", sel_year, month_num);
-            if let Some(data) = limits_map.get(&key) {
-                summary[(month_num - 1) as usize].1 = data.general;
+        let currency = display_currency.get();
+        let category_list = categories.get();
+        let rates = display_rates.get();
+        let (converted, _) = in_currency(&year_transactions.get(), currency, &rates);
+        let carried_by_month = year_carried.get();
+        let mut by_month: Vec<Vec<Transaction>> = vec![vec![]; 12];
+        for t in converted {
+            if t.date.year() == sel_year {
+                by_month[(t.date.month() - 1) as usize].push(t);
             }
         }
-        summary
+
+        // (przepływy, limit ogólny, limity kategorii) dla każdego miesiąca
+        by_month
+            .iter()
+            .enumerate()
+            .map(|(i, txs)| {
+                let key = format!("{}-{:02}", sel_year, i + 1);
+                let limits = month_limits(&key)
+                    .map(|limits| limits.in_currency(&key, currency, &rates))
+                    .unwrap_or(MonthlyLimitData::empty(currency));
+                let carried = carried_by_month.get(i).cloned().unwrap_or_default();
+                (CashFlow::of(txs, currency), limits.general, limits.breakdown(txs, &category_list, currency, &carried))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let yearly_flow = Memo::new(move |_| {
+        let sel_year = parsed_date_from_str(&selected_month_str.get()).year();
+        let currency = display_currency.get();
+        let (converted, _) = display_rates.with(|rates| {
+            in_currency(
+                year_transactions.get().iter().filter(|t| t.date.year() == sel_year),
+                currency,
+                rates,
+            )
+        });
+        CashFlow::of(&converted, currency)
     });
 
     // 4. FUNKCJE OBSŁUGUJĄCE ZDARZENIA
 
-    let add_transaction = move |_| {
-        let parsed_amount = amount.get().parse::<f64>().unwrap_or(0.0);
-        if !title.get().is_empty() && parsed_amount > 0.0 {
-            let new_transaction = Transaction {
-                id: rand::random(),
+    let reset_form = move || {
+        set_title.set("".to_string());
+        set_amount.set("".to_string());
+        set_form_currency.set(None);
+        set_tags.set(String::new());
+        set_category_touched.set(false);
+        set_suggestion.set(None);
+        set_editing.set(None);
+    };
+
+    // Dodawanie i edycja korzystają z tego samego formularza i tej samej walidacji.
+    // Edycja zachowuje id transakcji i zapisuje ją komendą update_transaction.
+    // Kwota zostaje w walucie, w której ją wpisano - waluta wyświetlania jej nie zmienia.
+    let submit_transaction = move |_| {
+        let edited = editing.get();
+        let amount_currency = form_currency.get().unwrap_or(display_currency.get());
+        let parsed_amount = Money::parse(&amount.get(), amount_currency).unwrap_or(Money::zero(amount_currency));
+        let parsed_date = NaiveDate::parse_from_str(&date.get(), "%Y-%m-%d").ok();
+        if let Some(parsed_date) = parsed_date.filter(|_| !title.get().is_empty() && parsed_amount.is_positive()) {
+            let transaction = Transaction {
+                id: edited.as_ref().map(|tx| tx.id).unwrap_or_else(TransactionId::generate),
                 title: title.get(),
                 amount: parsed_amount,
-                date: date.get(),
+                kind: kind.get(),
+                date: parsed_date,
                 category: category.get(),
+                external_id: edited.as_ref().and_then(|tx| tx.external_id.clone()),
+                tags: parse_tags(&tags.get()),
             };
-            set_transactions.update(|list| list.push(new_transaction));
-            set_title.set("".to_string());
-            set_amount.set("".to_string());
+            set_possible_duplicates.set(None);
+            spawn_local(async move {
+                let args = TransactionArgs { transaction: transaction.clone() };
+                let saved = if edited.is_some() {
+                    call::<_, ()>("update_transaction", &args).await.is_ok()
+                } else {
+                    match call::<_, Vec<Transaction>>("add_transaction", &args).await {
+                        Ok(candidates) => {
+                            if !candidates.is_empty() {
+                                set_possible_duplicates.set(Some((transaction, candidates)));
+                            }
+                            true
+                        }
+                        Err(_) => false,
+                    }
+                };
+                if saved {
+                    reset_form();
+                    refresh();
+                }
+            });
         }
     };
 
+    let start_edit = move |tx: Transaction| {
+        set_title.set(tx.title.clone());
+        set_amount.set(tx.amount.to_input_string());
+        set_date.set(tx.date.format("%Y-%m-%d").to_string());
+        set_category.set(tx.category.clone());
+        set_kind.set(tx.kind);
+        set_form_currency.set(Some(tx.amount.currency));
+        set_tags.set(tx.tags.join(", "));
+        set_category_touched.set(true);
+        set_editing.set(Some(tx));
+    };
+
+    // Kategorię dopasuje efekt pilnujący listy form_categories
+    let change_kind = move |new_kind: TransactionKind| set_kind.set(new_kind);
+
     let remove_transaction = move |tx: Transaction| {
-        let list = transactions.get();
-        for t in list {
-            if t.id == tx.id {
-                set_transactions.update(|list| {
-                    if let Some(pos) = list.iter().position(|x| x.id == t.id) {
-                        list.remove(pos);
-                    }
-                });
-                break;
-            }
+        #[derive(serde::Serialize)]
+        struct DeleteArgs {
+            id: TransactionId,
         }
+        spawn_local(async move {
+            if call::<_, ()>("delete_transaction", &DeleteArgs { id: tx.id }).await.is_ok() {
+                if editing.get_untracked().is_some_and(|edited| edited.id == tx.id) {
+                    reset_form();
+                }
+                refresh();
+            }
+        });
     };
 
-    let update_general_limit = move |val_str: String| {
-        let val = val_str.parse::<f64>().unwrap_or(0.0).abs();
-        let key = limits_month_str.get();
+    // Zmiana w limitach danego miesiąca: lokalnie od razu, do backendu cały miesiąc
+    let save_month_limit = move |month: String, limits: MonthlyLimitData| {
         set_all_limits.update(|map| {
-            map.entry(key)
-                .or_insert(MonthlyLimitData {
-                    general: 0.0,
-                    categories: HashMap::new(),
-                })
-                .general = val;
+            map.insert(month.clone(), limits.clone());
+        });
+        #[derive(serde::Serialize)]
+        struct LimitArgs {
+            month: String,
+            limits: MonthlyLimitData,
+        }
+        spawn_local(async move {
+            let _ = call::<_, ()>("set_month_limit", &LimitArgs { month, limits }).await;
         });
     };
 
-    let update_cat_limit = move |cat: String, val_str: String| {
-        let val = val_str.parse::<f64>().unwrap_or(0.0).abs();
-        let key = limits_month_str.get();
-        set_all_limits.update(|map| {
-            map.entry(key)
-                .or_insert(MonthlyLimitData {
-                    general: 0.0,
-                    categories: HashMap::new(),
-                })
-                .categories
-                .insert(cat, val);
+    let save_budget = move |updated: Budget| {
+        set_budget.set(updated.clone());
+        #[derive(serde::Serialize)]
+        struct BudgetArgs {
+            budget: Budget,
+        }
+        spawn_local(async move {
+            let _ = call::<_, ()>("save_budget", &BudgetArgs { budget: updated }).await;
         });
     };
 
+    // Limity poprzedniego miesiąca (zapisane albo z szablonu) do edytowanego
+    let copy_previous_limits = move |_| {
+        let month = limits_month_str.get();
+        let previous = parsed_date_from_str(&month)
+            .checked_sub_months(chrono::Months::new(1))
+            .map(|d| d.format("%Y-%m").to_string())
+            .and_then(|previous| month_limits(&previous));
+        match previous {
+            Some(limits) => {
+                set_limits_notice.set(None);
+                save_month_limit(month, limits);
+            }
+            None => set_limits_notice.set(Some(t("no_previous_limits", &language.get()))),
+        }
+    };
+
+    let toggle_rollover = move |category: String| {
+        let mut updated = budget.get();
+        if updated.rolls_over(&category) {
+            updated.rollover.retain(|id| *id != category);
+        } else {
+            updated.rollover.push(category);
+        }
+        save_budget(updated);
+    };
+
+    let update_general_limit = move |val_str: String| {
+        let val = Money::parse(&val_str, display_currency.get()).unwrap_or(Money::zero(display_currency.get())).abs();
+        let mut limits = editing_month_limits.get();
+        limits.general = val;
+        save_month_limit(limits_month_str.get(), limits);
+    };
+
+    let update_cat_limit = move |cat: String, val_str: String| {
+        let val = Money::parse(&val_str, display_currency.get()).unwrap_or(Money::zero(display_currency.get())).abs();
+        let mut limits = editing_month_limits.get();
+        limits.categories.insert(cat, val);
+        save_month_limit(limits_month_str.get(), limits);
+    };
+
+    // Zmienia tylko walutę wyświetlania; zapisane kwoty zostają w oryginalnych walutach
     let change_currency = move |new_currency: String| {
-        let old_currency = currency.get();
-        if old_currency == new_currency {
-            return;
+        if CurrencyCode::new(&new_currency).is_some() {
+            set_currency.set(new_currency);
         }
-        let ratio = get_exchange_rate(&old_currency) / get_exchange_rate(&new_currency);
+    };
 
-        set_transactions.update(|list| {
-            for t in list {
-                t.amount *= ratio;
-            }
-        });
-        set_all_limits.update(|map| {
-            for data in map.values_mut() {
-                data.general *= ratio;
-                for v in data.categories.values_mut() {
-                    *v *= ratio;
-                }
-            }
+    let export_json = move |_| {
+        spawn_local(async move {
+            let msg = match try_invoke("export_json", JsValue::NULL).await {
+                Ok(path) => format!("{}: {}", t("exported_to", &language.get_untracked()), path.as_string().unwrap_or_default()),
+                Err(err) => err.as_string().unwrap_or_default(),
+            };
+            set_export_msg.set(Some(msg));
         });
-        set_currency.set(new_currency);
     };
 
     let clear_storage = move |_| {
-        set_transactions.set(vec![]);
-        set_all_limits.set(HashMap::new());
         set_show_settings.set(false);
         spawn_local(async move {
-            let _ = invoke(
-                "save_data",
-                serde_wasm_bindgen::to_value(&AppState::default()).unwrap(),
-            )
-            .await;
+            if call::<_, ()>("clear_data", &NoArgs {}).await.is_ok() {
+                set_all_limits.set(HashMap::new());
+                set_budget.update(|b| b.template = None);
+                refresh();
+            }
         });
     };
 
     // 5. WIDOK
 
     view! {
+        {move || load_error.get().map(|error| view! { <RecoveryScreen error=error language=language on_recovered=on_recovered/> })}
+
+        <Show when=move || load_error.get().is_none()>
         <style> "input[type=number]::-webkit-inner-spin-button, input[type=number]::-webkit-outer-spin-button { -webkit-appearance: none; margin: 0; }" </style>
 
         <div
@@ -293,10 +647,14 @@ pub fn App() -> impl IntoView {
                 <div class="flex mb-6 padding-b-2 gap-4">
                     <button class={move || get_tab_style(active_tab.get() == 0, is_dark())} on:click=move |_| set_active_tab.set(0)>{move || t("dashboard", &language.get())}</button>
                     <button class={move || get_tab_style(active_tab.get() == 1, is_dark())} on:click=move |_| set_active_tab.set(1)>{move || t("limits", &language.get())}</button>
+                    <button class={move || get_tab_style(active_tab.get() == 2, is_dark())} on:click=move |_| set_active_tab.set(2)>{move || t("categories", &language.get())}</button>
+                    <button class={move || get_tab_style(active_tab.get() == 3, is_dark())} on:click=move |_| set_active_tab.set(3)>{move || t("exchange_rates", &language.get())}</button>
+                    <button class={move || get_tab_style(active_tab.get() == 4, is_dark())} on:click=move |_| set_active_tab.set(4)>{move || t("import_export", &language.get())}</button>
+                    <button class={move || get_tab_style(active_tab.get() == 5, is_dark())} on:click=move |_| set_active_tab.set(5)>{move || t("recurring", &language.get())}</button>
                 </div>
 
                 // ZAKŁADKA 1: DASHBOARD
-                <Show when=move || active_tab.get() == 0 fallback=|| view! {}>
+                <Show when=move || active_tab.get() == 0 fallback=|| ()>
                     <div class="flex justify-end mb-4 items-center gap-2">
                         <label class="text-sm font-bold opacity-70">{move || t("select_month", &language.get())}</label>
                         <input type="month" class={move || get_input_style(is_dark())} on:input=move |ev| set_selected_month_str.set(event_target_value(&ev)) prop:value=selected_month_str />
@@ -308,31 +666,66 @@ pub fn App() -> impl IntoView {
                              <h2 class="text-xs font-bold tracking-wider opacity-60 uppercase mb-1">{move || t("transactions_count", &language.get())}</h2>
                              <p class="text-3xl font-bold">{current_month_count}</p>
                         </div>
+                        <div class={move || get_box_style(is_dark())}>
+                             <h2 class="text-xs font-bold tracking-wider opacity-60 uppercase mb-1">{move || t("income", &language.get())}</h2>
+                             <p class="text-3xl font-bold text-emerald-500">{move || format_currency(current_month_flow.get().income, &language.get())}</p>
+                        </div>
                         <div class={move || get_box_style(is_dark())}>
                              <h2 class="text-xs font-bold tracking-wider opacity-60 uppercase mb-1">{move || t("spent", &language.get())}</h2>
                              <div class="flex justify-between items-end">
-                                <p class={move || if current_month_limits.get().general > 0.0 && current_month_total.get() > current_month_limits.get().general { "text-3xl font-bold text-red-500" } else { "text-3xl font-bold text-emerald-500" }}>
-                                    {move || format_currency(current_month_total.get(), &currency.get(), &language.get())}
+                                <p class={move || if current_month_flow.get().expenses.is_over(&current_month_limits.get().general) { "text-3xl font-bold text-red-500" } else { "text-3xl font-bold text-emerald-500" }}>
+                                    {move || format_currency(current_month_flow.get().expenses, &language.get())}
                                 </p>
                                 <span class="text-xs opacity-80 mb-1 font-medium border-2 border-black-200 dark:border-white-700 px-2 py-1 rounded">
-                                    {move || format!("{}: {}", t("general_limit", &language.get()), format_currency(current_month_limits.get().general, &currency.get(), &language.get()))}
+                                    {move || format!("{}: {}", t("general_limit", &language.get()), format_currency(current_month_limits.get().general, &language.get()))}
                                 </span>
                              </div>
                         </div>
+                        <div class={move || get_box_style(is_dark())}>
+                             <h2 class="text-xs font-bold tracking-wider opacity-60 uppercase mb-1">{move || t("net_balance", &language.get())}</h2>
+                             <p class={move || if current_month_flow.get().net().minor < 0 { "text-3xl font-bold text-red-500" } else { "text-3xl font-bold text-emerald-500" }}>
+                                {move || format_currency(current_month_flow.get().net(), &language.get())}
+                             </p>
+                        </div>
+                    </div>
+
+                    {move || {
+                        let missing = current_month_converted.get().1;
+                        (missing > 0).then(|| view! {
+                            <div class="mb-8 p-3 bg-amber-100 border border-amber-400 text-amber-800 rounded-lg text-center font-medium">
+                                {format!("{}: {}", t("missing_rates", &language.get()), missing)}
+                            </div>
+                        })
+                    }}
+
+                    // Limity kategorii
+                    <div class={move || format!("{} mb-8", get_box_style(is_dark()))}>
+                        <h3 class="text-xl font-bold mb-4">{move || t("cat_limits", &language.get())}</h3>
+                        {move || view! { <CategoryBreakdown usages=current_month_breakdown.get() categories=categories language=language/> }}
                     </div>
 
                     // Formularz
                     <div class={move || get_box_style(is_dark())}>
                         <h3 class="text-xl font-bold mb-4 flex items-center gap-2">
-                            {move || t("add_transaction", &language.get())}
+                            {move || if editing.get().is_some() { t("edit_transaction", &language.get()) } else { t("add_transaction", &language.get()) }}
                         </h3>
-                        <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-4 gap-4">
+                        <div class="flex gap-2 mb-4">
+                            {TransactionKind::ALL.into_iter().map(|k| view! {
+                                <button class={move || get_tab_style(kind.get() == k, is_dark())} on:click=move |_| change_kind(k)>
+                                    {move || t(&format!("kind_{}", k.as_str()), &language.get())}
+                                </button>
+                            }).collect::<Vec<_>>()}
+                        </div>
+                        <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-5 gap-4">
                             <input type="text" placeholder={move || t("name_placeholder", &language.get())} class={move || get_input_style(is_dark())} on:input=move |ev| set_title.set(event_target_value(&ev)) prop:value=title />
 
                             <input
                                 type="text"
                                 inputmode="decimal"
-                                pattern="[0-9]*[.,]?[0-9]{0,2}"
+                                pattern=move || match amount_digits() {
+                                    0 => "[0-9]*".to_string(),
+                                    digits => format!("[0-9]*[.,]?[0-9]{{0,{}}}", digits),
+                                }
                                 placeholder={move || t("amount_placeholder", &language.get())}
                                 class={move || get_input_style(is_dark())}
                                 on:keydown=move |ev| {
@@ -340,84 +733,154 @@ pub fn App() -> impl IntoView {
                                     let current_value = amount.get();
                                     let is_digit = key.chars().all(|c| c.is_ascii_digit());
                                     let is_decimal = key == "." || key == ",";
+                                    let digits = amount_digits();
                                     if key.len() > 1 && !is_digit && !is_decimal { return; }
                                     if !is_digit && !is_decimal { ev.prevent_default(); return; }
-                                    if is_decimal && current_value.contains('.') { ev.prevent_default(); return; }
+                                    if is_decimal && (digits == 0 || current_value.contains('.')) { ev.prevent_default(); return; }
                                     if is_digit && current_value.contains('.') {
                                         if let Some(decimal_part) = current_value.split('.').nth(1) {
-                                            if decimal_part.len() >= 2 { ev.prevent_default(); }
+                                            if decimal_part.len() >= digits { ev.prevent_default(); }
                                         }
                                     }
                                 }
-                                on:input=move |ev| {
-                                    let value = event_target_value(&ev);
-                                    let mut sanitized_value = String::new();
-                                    let mut decimal_point_found = false;
-                                    for c in value.chars() {
-                                        if c.is_ascii_digit() {
-                                            sanitized_value.push(c);
-                                        } else if (c == '.' || c == ',') && !decimal_point_found {
-                                            sanitized_value.push('.');
-                                            decimal_point_found = true;
-                                        }
-                                    }
-                                    if decimal_point_found {
-                                        if let Some((integer_part, decimal_part)) = sanitized_value.split_once('.') {
-                                            let trimmed_decimal = decimal_part.chars().take(2).collect::<String>();
-                                            sanitized_value = format!("{}.{}", integer_part, trimmed_decimal);
-                                        }
-                                    }
-                                    set_amount.set(sanitized_value);
-                                }
+                                on:input=move |ev| set_amount.set(sanitize_amount(&event_target_value(&ev), amount_digits()))
                                 prop:value=amount
                             />
 
-                            <select class={move || get_input_style(is_dark())} on:change=move |ev| set_category.set(event_target_value(&ev)) prop:value=category>
-                                {categories_list.iter().map(|c| {
-                                    let c_string = c.to_string();
-                                    view! { <option class="text-slate-800 dark:text-slate-800" value=c.to_string()>{move || t(&c_string, &language.get())}</option> }
+                            <select class={move || get_input_style(is_dark())} on:change=move |ev| {
+                                set_form_currency.set(CurrencyCode::new(&event_target_value(&ev)));
+                                // Po zmianie waluty ucinamy miejsca po przecinku, których ona nie ma
+                                set_amount.set(sanitize_amount(&amount.get_untracked(), amount_digits()));
+                            }>
+                                {move || {
+                                    let selected = form_currency.get().unwrap_or(display_currency.get());
+                                    let lang = language.get();
+                                    CURRENCIES.iter().map(|info| {
+                                        view! { <option class="text-slate-800 dark:text-slate-800" value=info.0 selected=selected.as_str() == info.0>{currency_label(info, &lang)}</option> }
+                                    }).collect::<Vec<_>>()
+                                }}
+                            </select>
+
+                            <select class={move || get_input_style(is_dark())} on:change=move |ev| { set_category_touched.set(true); set_category.set(event_target_value(&ev)) } prop:value=category>
+                                {move || form_categories.get().into_iter().map(|c| {
+                                    let selected = category.get_untracked() == c.id;
+                                    let value = c.id.clone();
+                                    view! { <option class="text-slate-800 dark:text-slate-800" value=value selected=selected>{move || format!("{} {}", c.icon, categories.with(|list| category_path(list, &c.id, &language.get())))}</option> }
                                 }).collect::<Vec<_>>()}
                             </select>
 
                             <input type="date" class={move || get_input_style(is_dark())} on:input=move |ev| set_date.set(event_target_value(&ev)) prop:value=date />
                         </div>
-                        <button class="mt-4 w-full bg-emerald-600 text-white font-bold py-3 px-4 rounded-lg hover:bg-emerald-700 transition shadow-lg shadow-emerald-600/20" on:click=add_transaction>
-                             {move || t("add_transaction", &language.get())}
+                        <div class="mt-4">
+                            <input type="text" placeholder={move || t("tags_placeholder", &language.get())} class={move || get_input_style(is_dark())} on:input=move |ev| set_tags.set(event_target_value(&ev)) prop:value=tags />
+                        </div>
+                        // Podpowiedź z historii: nadana sama albo do przyjęcia jednym kliknięciem
+                        {move || suggestion.get().map(|s| {
+                            let lang = language.get();
+                            let label = categories.with(|list| category_path(list, &s.category, &lang));
+                            let applied = category.get() == s.category;
+                            let text = format!("{}: {} ({:.0}%)", t("suggested_category", &lang), label, s.confidence * 100.0);
+                            view! {
+                                <div class="mt-2 flex items-center gap-3 text-sm opacity-80">
+                                    <span>{text}</span>
+                                    <Show when=move || !applied>
+                                        <button class="text-emerald-600 font-bold hover:underline" on:click={
+                                            let id = s.category.clone();
+                                            move |_| { set_category_touched.set(true); set_category.set(id.clone()) }
+                                        }>
+                                            {move || t("use_suggestion", &language.get())}
+                                        </button>
+                                    </Show>
+                                </div>
+                            }
+                        })}
+                        <button class="mt-4 w-full bg-emerald-600 text-white font-bold py-3 px-4 rounded-lg hover:bg-emerald-700 transition shadow-lg shadow-emerald-600/20" on:click=submit_transaction>
+                             {move || if editing.get().is_some() { t("save_changes", &language.get()) } else { t("add_transaction", &language.get()) }}
                         </button>
+                        <Show when=move || editing.get().is_some()>
+                            <button class="mt-2 w-full bg-slate-500 text-white font-bold py-2 px-4 rounded-lg hover:bg-slate-600 transition" on:click=move |_| reset_form()>
+                                {move || t("cancel_edit", &language.get())}
+                            </button>
+                        </Show>
+                        {move || possible_duplicates.get().map(|(added, candidates)| view! {
+                            <DuplicateNotice language=language added=added candidates=candidates
+                                on_resolved=Callback::new(move |_| { set_possible_duplicates.set(None); refresh(); })/>
+                        })}
                     </div>
 
                     // Lista
                     <div class="mb-8">
                         <ul class="divide-y divide-slate-200 dark:divide-slate-700">
                             <For
-                                each=move || { let sel = selected_month_str.get(); transactions.get().into_iter().filter(move |t| t.date.starts_with(&sel)).collect::<Vec<_>>() }
-                                key=move |t| (t.id, currency.get())
+                                each=move || { let sel = selected_month_str.get(); transactions.get().into_iter().filter(move |t| is_in_month(t.date, &sel)).collect::<Vec<_>>() }
+                                key=|t| t.id
                                 children=move |tx| {
-                                    let tx_clone = tx.clone();
+                                    // Wiersz czyta transakcję po id z bieżącej listy - po edycji odświeża się w miejscu
+                                    let id = tx.id;
+                                    let row = Memo::new(move |_| transactions.with(|list| list.iter().find(|t| t.id == id).cloned()).unwrap_or_else(|| tx.clone()));
                                     view! {
                                         <li class="py-4 flex justify-between items-center px-3 hover:bg-slate-50 dark:hover:bg-slate-700/50 rounded-lg transition overflow-hidden">
                                             <div class="flex items-center gap-3 flex-1 min-w-0">
-                                                <div class="w-2 h-10 bg-emerald-500 rounded-full opacity-50 shrink-0"></div>
+                                                <div class="w-2 h-10 rounded-full opacity-70 shrink-0" style={
+                                                    move || row.with(|tx| format!("background-color: {}", categories.with(|list| list.iter().find(|c| c.id == tx.category).map(|c| c.color.clone()).unwrap_or("#94a3b8".to_string()))))
+                                                }></div>
                                                 <div class="min-w-0">
-                                                    <p class="font-bold text-lg truncate pr-2">{tx.title}</p>
+                                                    <p class="font-bold text-lg truncate pr-2">{move || row.get().title}</p>
                                                     <p class="text-sm opacity-60 font-medium flex gap-2 truncate">
-                                                        <span>{move || format_date_display(&tx.date, &language.get())}</span>
+                                                        <span>{move || format_date_display(row.get().date, &language.get())}</span>
                                                         <span class="opacity-50">"•"</span>
-                                                        <span class="text-emerald-600 dark:text-emerald-400 truncate">{move || t(&tx.category, &language.get())}</span>
+                                                        <span class="text-emerald-600 dark:text-emerald-400 truncate">{move || row.with(|tx| categories.with(|list| {
+                                                            let icon = list.iter().find(|c| c.id == tx.category).map(|c| c.icon.clone()).unwrap_or_default();
+                                                            format!("{} {}", icon, category_path(list, &tx.category, &language.get()))
+                                                        }))}</span>
+                                                        {move || row.get().tags.into_iter().map(|tag| view! {
+                                                            <span class="px-2 rounded-full bg-slate-200 dark:bg-slate-700">{format!("#{}", tag)}</span>
+                                                        }).collect::<Vec<_>>()}
                                                     </p>
                                                 </div>
                                             </div>
 
                                             <div class="text-right shrink-0 ml-2">
-                                                <p class="font-bold text-lg text-red-600 dark:text-red-400 whitespace-nowrap">
-                                                    {move || format!("-{}", format_currency(tx.amount, &currency.get(), "any").replace("-", ""))}
+                                                <p class={move || match row.get().kind {
+                                                    TransactionKind::Expense => "font-bold text-lg text-red-600 dark:text-red-400 whitespace-nowrap",
+                                                    TransactionKind::Income => "font-bold text-lg text-emerald-600 dark:text-emerald-400 whitespace-nowrap",
+                                                    TransactionKind::Transfer => "font-bold text-lg opacity-70 whitespace-nowrap",
+                                                }}>
+                                                    {move || {
+                                                        let tx = row.get();
+                                                        let sign = match tx.kind {
+                                                            TransactionKind::Expense => "-",
+                                                            TransactionKind::Income => "+",
+                                                            TransactionKind::Transfer => "\u{21C4} ",
+                                                        };
+                                                        format!("{}{}", sign, format_currency(tx.amount.abs(), &language.get()))
+                                                    }}
                                                 </p>
+                                                // Kwota w innej walucie: obok przeliczenie na walutę wyświetlania
+                                                {move || {
+                                                    let tx = row.get();
+                                                    let shown = display_currency.get();
+                                                    (tx.amount.currency != shown).then(|| {
+                                                        let converted = display_rates.with(|rates| rates.convert(tx.amount.abs(), shown, tx.date));
+                                                        let text = match converted {
+                                                            Some(money) => format!("\u{2248} {}", format_currency(money, &language.get())),
+                                                            None => t("no_rate", &language.get()),
+                                                        };
+                                                        view! { <p class="text-xs opacity-60 whitespace-nowrap">{text}</p> }
+                                                    })
+                                                }}
                                                 <button
                                                     class="mt-4 w-full bg-emerald-600 text-white font-bold py-3 px-4 rounded-lg hover:bg-emerald-700 transition shadow-lg shadow-emerald-600/20"
-                                                    on:click=move |_| { remove_transaction(tx_clone.clone()); }
+                                                    on:click=move |_| { remove_transaction(row.get_untracked()); }
                                                 >
                                                     {move || t("remove_transaction", &language.get())}
                                                 </button>
+                                                <button
+                                                    class="mt-2 w-full bg-slate-500 text-white font-bold py-2 px-4 rounded-lg hover:bg-slate-600 transition"
+                                                    on:click=move |_| { start_edit(row.get_untracked()); }
+                                                >
+                                                    {move || t("edit_transaction", &language.get())}
+                                                </button>
                                             </div>
                                         </li>
                                     }
@@ -433,20 +896,27 @@ pub fn App() -> impl IntoView {
                          </button>
                     </div>
 
-                    <Show when=move || show_yearly.get() fallback=|| view! {}>
+                    <Show when=move || show_yearly.get() fallback=|| ()>
                         <div class={move || get_box_style(is_dark())}>
                             <h3 class="text-lg font-bold mb-4 text-center">{move || t("year_summary", &language.get())} {move || parsed_date_from_str(&selected_month_str.get()).year()}</h3>
+                            <div class="flex justify-center gap-6 mb-4 text-sm font-bold">
+                                <span class="text-emerald-600 dark:text-emerald-400">{move || format!("{}: {}", t("income", &language.get()), format_currency(yearly_flow.get().income, &language.get()))}</span>
+                                <span class="text-red-600 dark:text-red-400">{move || format!("{}: {}", t("spent", &language.get()), format_currency(yearly_flow.get().expenses, &language.get()))}</span>
+                                <span>{move || format!("{}: {}", t("net_balance", &language.get()), format_currency(yearly_flow.get().net(), &language.get()))}</span>
+                            </div>
                             <div class="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-4 gap-4">
                                 {move || {
                                     let summary = yearly_summary.get();
-                                    (0..12).map(|i| {
-                                        let (spent, limit) = summary[i];
+                                    summary.into_iter().enumerate().map(|(i, (flow, limit, usages))| {
                                         let month_key = format!("month_short_{}", i + 1);
                                         view! {
                                             <div class={get_box_style(is_dark())}>
                                                 <p class="text-xs opacity-60 uppercase font-bold mb-1">{move || t(&month_key, &language.get())}</p>
-                                                <p class={if limit > 0.0 && spent > limit { "font-bold text-red-500" } else { "font-bold text-emerald-600 dark:text-emerald-400" }}>{format_currency(spent, &currency.get(), &language.get())}</p>
-                                                <p class="text-xs opacity-40">{format!("Limit: {}", format_currency(limit, &currency.get(), &language.get()))}</p>
+                                                <p class={if flow.expenses.is_over(&limit) { "font-bold text-red-500" } else { "font-bold text-emerald-600 dark:text-emerald-400" }}>{format!("-{}", format_currency(flow.expenses, &language.get()))}</p>
+                                                <p class="text-sm text-emerald-600 dark:text-emerald-400">{format!("+{}", format_currency(flow.income, &language.get()))}</p>
+                                                <p class="text-sm font-bold">{format!("{}: {}", t("net_balance", &language.get()), format_currency(flow.net(), &language.get()))}</p>
+                                                <p class="text-xs opacity-40">{format!("Limit: {}", format_currency(limit, &language.get()))}</p>
+                                                <CategoryBreakdown usages=usages categories=categories language=language compact=true/>
                                             </div>
                                         }
                                     }).collect::<Vec<_>>()
@@ -457,7 +927,7 @@ pub fn App() -> impl IntoView {
                 </Show>
 
                 // ZAKŁADKA 2: LIMITY
-                <Show when=move || active_tab.get() == 1 fallback=|| view! {}>
+                <Show when=move || active_tab.get() == 1 fallback=|| ()>
                     <div class={move || get_box_style(is_dark())}>
                         <div class="flex justify-between items-center mb-6">
                             <h2 class="text-xl font-bold">{move || t("limits", &language.get())}</h2>
@@ -466,30 +936,61 @@ pub fn App() -> impl IntoView {
                                 set_timeout(move || set_show_save_toast.set(false), std::time::Duration::from_secs(2));
                             }>{move || t("save_limits", &language.get())}</button>
                         </div>
-                        <Show when=move || show_save_toast.get() fallback=|| view! {}>
+                        <Show when=move || show_save_toast.get() fallback=|| ()>
                             <div class="mb-6 p-3 bg-emerald-100 border border-emerald-400 text-emerald-800 rounded-lg text-center font-medium shadow-sm">{move || t("saved_msg", &language.get())}</div>
                         </Show>
                         <div class="mb-6">
                             <label class="block font-bold mb-2 text-sm uppercase opacity-70">{move || t("edit_limits_month", &language.get())}</label>
-                            <input type="month" class={move || get_input_style(is_dark())} on:input=move |ev| set_limits_month_str.set(event_target_value(&ev)) prop:value=limits_month_str />
+                            <input type="month" class={move || get_input_style(is_dark())} on:input=move |ev| { set_limits_notice.set(None); set_limits_month_str.set(event_target_value(&ev)) } prop:value=limits_month_str />
                         </div>
+                        <div class="flex flex-wrap gap-3 mb-2">
+                            <button class="bg-slate-500 text-white font-bold py-2 px-4 rounded-lg hover:bg-slate-600 transition text-sm" on:click=copy_previous_limits>
+                                {move || t("copy_previous_limits", &language.get())}
+                            </button>
+                            <button class="bg-slate-500 text-white font-bold py-2 px-4 rounded-lg hover:bg-slate-600 transition text-sm"
+                                on:click=move |_| save_budget(Budget { template: Some(editing_month_limits.get()), ..budget.get() })>
+                                {move || t("save_limit_template", &language.get())}
+                            </button>
+                            <Show when=move || budget.with(|b| b.template.is_some())>
+                                <button class="bg-red-500/10 text-red-500 hover:bg-red-500 hover:text-white font-bold py-2 px-4 rounded-lg transition text-sm"
+                                    on:click=move |_| save_budget(Budget { template: None, ..budget.get() })>
+                                    {move || t("clear_limit_template", &language.get())}
+                                </button>
+                            </Show>
+                        </div>
+                        <p class="text-sm opacity-60 mb-6">
+                            {move || t(if budget.with(|b| b.template.is_some()) { "limit_template_set" } else { "limit_template_none" }, &language.get())}
+                        </p>
+                        {move || limits_notice.get().map(|notice| view! {
+                            <div class="mb-6 p-3 bg-amber-100 border border-amber-400 text-amber-800 rounded-lg text-center font-medium">{notice}</div>
+                        })}
                         <hr class="my-6 border-slate-300 dark:border-slate-600"/>
                         <div class="mb-6">
                             <span class="font-bold mb-2 text-lg p-2">{move || format!("{} ({})", t("general_limit", &language.get()), currency.get())}</span>
-                            <input type="number" min="0" step="0.01" class={move || get_input_style(is_dark())} on:input=move |ev| update_general_limit(event_target_value(&ev)) prop:value=move || editing_month_limits.get().general/>
+                            <input type="number" min="0" step="0.01" class={move || get_input_style(is_dark())} on:input=move |ev| update_general_limit(event_target_value(&ev)) prop:value=move || editing_month_limits_shown.get().general.to_input_string()/>
                         </div>
                         <span class="text-lg font-bold mb-2 p-2">{move || format!("{} ({})", t("cat_limits", &language.get()), currency.get())}</span>
                         <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
-                             {categories_list_limits.iter().map(|cat| {
-                                let c_label = cat.to_string();
-                                let c_input = cat.to_string();
-                                let c_val = cat.to_string();
+                             {move || expense_categories.get().into_iter().map(|(cat, depth)| {
+                                let c_input = cat.id.clone();
+                                let c_val = cat.id.clone();
+                                let (c_rollover, c_toggle, c_carried) = (cat.id.clone(), cat.id.clone(), cat.id.clone());
                                 view! {
-                                    <div class={get_box_style(is_dark())}>
-                                        <label class="block text-xs font-bold opacity-60 mb-2 uppercase">{move || t(&c_label, &language.get())}</label>
+                                    <div class={get_box_style(is_dark())} style=format!("margin-left: {}rem", depth)>
+                                        <label class="block text-xs font-bold opacity-60 mb-2 uppercase">{move || format!("{} {}", cat.icon, cat.label(&language.get()))}</label>
                                         <input type="number" min="0" step="0.01" class={get_input_style(is_dark())}
                                             on:input=move |ev| update_cat_limit(c_input.clone(), event_target_value(&ev))
-                                            prop:value=move || editing_month_limits.get().categories.get(&c_val).copied().unwrap_or(0.0) />
+                                            prop:value=move || editing_month_limits_shown.get().categories.get(&c_val).filter(|m| m.is_positive()).map(|m| m.to_input_string()).unwrap_or_default() />
+                                        <label class="flex items-center gap-2 mt-2 text-xs opacity-70">
+                                            <input type="checkbox" prop:checked=move || budget.with(|b| b.rolls_over(&c_rollover))
+                                                on:change=move |_| toggle_rollover(c_toggle.clone()) />
+                                            {move || t("rollover", &language.get())}
+                                        </label>
+                                        {move || editing_carried.get().get(&c_carried).map(|amount| view! {
+                                            <p class={if amount.minor < 0 { "mt-1 text-xs font-bold text-red-500" } else { "mt-1 text-xs font-bold text-emerald-500" }}>
+                                                {format!("{}: {}", t("carried_over", &language.get()), format_carried(*amount, &language.get()))}
+                                            </p>
+                                        })}
                                     </div>
                                 }
                             }).collect::<Vec<_>>()}
@@ -497,8 +998,45 @@ pub fn App() -> impl IntoView {
                     </div>
                 </Show>
 
+                // ZAKŁADKA 3: KATEGORIE
+                <Show when=move || active_tab.get() == 2>
+                    <div class="flex flex-col gap-8">
+                        <CategoryManager categories=categories language=language is_dark=Signal::derive(is_dark) on_changed=Callback::new(move |_| {
+                            reload_categories();
+                            refresh();
+                            spawn_local(async move {
+                                if let Ok(limits) = call("load_limits", &NoArgs {}).await {
+                                    set_all_limits.set(limits);
+                                }
+                            });
+                        })/>
+                        <RuleManager categories=categories language=language is_dark=Signal::derive(is_dark) currency=Signal::derive(move || display_currency.get())
+                            on_changed=Callback::new(move |_| refresh())/>
+                    </div>
+                </Show>
+
+                // ZAKŁADKA 4: KURSY WALUT
+                <Show when=move || active_tab.get() == 3>
+                    <RateManager language=language is_dark=Signal::derive(is_dark) on_changed=Callback::new(move |_| refresh())/>
+                </Show>
+
+                // ZAKŁADKA 5: IMPORT WYCIĄGU I EKSPORT
+                <Show when=move || active_tab.get() == 4>
+                    <div class="flex flex-col gap-8">
+                        <ImportWizard language=language is_dark=Signal::derive(is_dark) currency=Signal::derive(move || display_currency.get())
+                            categories=categories on_changed=Callback::new(move |_| refresh())/>
+                        <ExportPanel language=language is_dark=Signal::derive(is_dark) categories=categories/>
+                    </div>
+                </Show>
+
+                // ZAKŁADKA 6: TRANSAKCJE CYKLICZNE
+                <Show when=move || active_tab.get() == 5>
+                    <RecurringManager categories=categories language=language is_dark=Signal::derive(is_dark) currency=Signal::derive(move || display_currency.get())
+                        on_changed=Callback::new(move |_| refresh())/>
+                </Show>
+
                 // MENU USTAWIEŃ
-                <Show when=move || show_settings.get() fallback=|| view! {}>
+                <Show when=move || show_settings.get() fallback=|| ()>
                     <div class="fixed inset-0 bg-slate-900/60 backdrop-blur-sm flex justify-center items-center z-50 transition-opacity">
                         <div class={move || if is_dark() { "p-8 rounded-2xl shadow-2xl w-96 bg-slate-800 text-white border border-slate-700" } else { "p-8 rounded-2xl shadow-2xl w-96 bg-white text-slate-800" }}>
                             <h2 class="text-2xl font-bold mb-6">{move || t("settings", &language.get())}</h2>
@@ -514,9 +1052,9 @@ pub fn App() -> impl IntoView {
                             <div class="mb-5">
                                 <label class="block mb-2 font-bold text-sm uppercase opacity-60">{move || t("currency", &language.get())}</label>
                                 <select class={move || get_input_style(is_dark())} on:change=move |ev| change_currency(event_target_value(&ev))>
-                                    <option class="text-slate-800" value="PLN" selected={move || currency.get() == "PLN"}>PLN</option>
-                                    <option class="text-slate-800" value="USD" selected={move || currency.get() == "USD"}>USD</option>
-                                    <option class="text-slate-800" value="EUR" selected={move || currency.get() == "EUR"}>EUR</option>
+                                    {CURRENCIES.iter().map(|info| view! {
+                                        <option class="text-slate-800" value=info.0 selected={move || currency.get() == info.0}>{move || currency_label(info, &language.get())}</option>
+                                    }).collect::<Vec<_>>()}
                                 </select>
                             </div>
 
@@ -528,6 +1066,11 @@ pub fn App() -> impl IntoView {
                                 </select>
                             </div>
 
+                            <div class="mb-8">
+                                <button class="w-full bg-slate-200 text-slate-700 hover:bg-slate-300 dark:bg-slate-700 dark:text-white dark:hover:bg-slate-600 font-bold py-3 rounded-lg transition" on:click=export_json>{move || t("export_json", &language.get())}</button>
+                                {move || export_msg.get().map(|msg| view! { <p class="text-xs opacity-60 mt-2 break-all">{msg}</p> })}
+                            </div>
+
                             <div class="flex gap-3">
                                 <button class="flex-1 bg-red-500/10 text-red-500 hover:bg-red-500 hover:text-white font-bold py-3 rounded-lg transition" on:click=clear_storage>{move || t("clear_data", &language.get())}</button>
                                 <button class="flex-1 bg-slate-200 text-slate-700 hover:bg-slate-300 dark:bg-slate-700 dark:text-white dark:hover:bg-slate-600 font-bold py-3 rounded-lg transition" on:click=move |_| set_show_settings.set(false)>{move || t("close", &language.get())}</button>
@@ -537,5 +1080,6 @@ pub fn App() -> impl IntoView {
                 </Show>
             </div>
         </div>
+        </Show>
     }
 }
\ No newline at end of file
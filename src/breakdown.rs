@@ -0,0 +1,75 @@
+use leptos::prelude::*;
+
+use crate::models::{Category, CategoryUsage};
+use crate::translate::t;
+use crate::utils::{category_label, format_carried, format_currency};
+
+// Wydatki w kategoriach na tle limitów: kwoty, procent i pasek postępu.
+// Wersja `compact` (karty podsumowania rocznego) pokazuje tylko nazwę i procent.
+#[component]
+pub fn CategoryBreakdown(
+    usages: Vec<CategoryUsage>,
+    categories: ReadSignal<Vec<Category>>,
+    language: ReadSignal<String>,
+    #[prop(optional)] compact: bool,
+) -> impl IntoView {
+    if usages.is_empty() {
+        return view! { <p class="text-xs opacity-40">{move || t("no_category_spending", &language.get())}</p> }.into_any();
+    }
+
+    view! {
+        <div class={if compact { "space-y-1 mt-2" } else { "space-y-3" }}>
+            {usages.into_iter().map(|usage| {
+                let percent = usage.percent();
+                let over = usage.is_over();
+                let (spent, limit, carried) = (usage.spent, usage.limit, usage.carried);
+                let category = usage.category;
+                // Podkategorie wcięte pod kategorią nadrzędną
+                let indent = format!("padding-left: {}rem", usage.depth);
+                let bar_style = format!("width: {}%", percent.unwrap_or(0).min(100));
+                let bar_class = match percent {
+                    _ if over => "h-full bg-red-500",
+                    Some(p) if p >= 80 => "h-full bg-amber-500",
+                    _ => "h-full bg-emerald-500",
+                };
+                view! {
+                    <div style=indent>
+                        <div class={if compact { "flex justify-between text-xs" } else { "flex justify-between text-sm font-medium mb-1" }}>
+                            <span class="truncate pr-2">{move || categories.with(|list| category_label(list, &category, &language.get()))}</span>
+                            <span class={if over { "text-red-500 font-bold whitespace-nowrap" } else { "opacity-70 whitespace-nowrap" }}>
+                                {move || {
+                                    let lang = language.get();
+                                    match percent {
+                                        Some(p) if compact => format!("{}%", p),
+                                        Some(p) if carried.minor != 0 => format!(
+                                            "{} / {} {} ({}%)",
+                                            format_currency(spent, &lang),
+                                            format_currency(limit, &lang),
+                                            format_carried(carried, &lang),
+                                            p
+                                        ),
+                                        Some(p) => format!("{} / {} ({}%)", format_currency(spent, &lang), format_currency(limit, &lang), p),
+                                        None if compact => format_currency(spent, &lang),
+                                        None => format!("{} ({})", format_currency(spent, &lang), t("no_limit", &lang)),
+                                    }
+                                }}
+                            </span>
+                        </div>
+                        // Przeniesione z poprzedniego miesiąca - osobno, obok limitu tego miesiąca
+                        <Show when=move || !compact && carried.minor != 0>
+                            <p class={if carried.minor < 0 { "text-xs text-red-500 mb-1" } else { "text-xs text-emerald-500 mb-1" }}>
+                                {move || format!("{}: {}", t("carried_over", &language.get()), format_carried(carried, &language.get()))}
+                            </p>
+                        </Show>
+                        <Show when=move || percent.is_some()>
+                            <div class={if compact { "h-1 rounded-full bg-slate-200 dark:bg-slate-700 overflow-hidden" } else { "h-2 rounded-full bg-slate-200 dark:bg-slate-700 overflow-hidden" }}>
+                                <div class=bar_class style=bar_style.clone()></div>
+                            </div>
+                        </Show>
+                    </div>
+                }
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+    .into_any()
+}
@@ -0,0 +1,231 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::api::call;
+use crate::models::*;
+use crate::translate::t;
+use crate::utils::*;
+
+#[derive(serde::Serialize)]
+struct CategoryArgs {
+    category: Category,
+}
+
+#[derive(serde::Serialize)]
+struct MergeArgs {
+    from: String,
+    into: String,
+}
+
+// Zakładka zarządzania kategoriami: dodawanie, zmiana nazw/koloru/ikony, archiwizacja
+// i scalanie. Po każdej zapisanej zmianie `on_changed` przeładowuje dane w App.
+#[component]
+pub fn CategoryManager(
+    categories: ReadSignal<Vec<Category>>,
+    language: ReadSignal<String>,
+    is_dark: Signal<bool>,
+    on_changed: Callback<()>,
+) -> impl IntoView {
+    let (error, set_error) = signal::<Option<String>>(None);
+
+    // Formularz nowej kategorii
+    let (new_kind, set_new_kind) = signal(TransactionKind::Expense);
+    let (new_pl, set_new_pl) = signal(String::new());
+    let (new_en, set_new_en) = signal(String::new());
+    let (new_color, set_new_color) = signal("#10b981".to_string());
+    let (new_icon, set_new_icon) = signal("\u{1F3F7}".to_string());
+    let (new_parent, set_new_parent) = signal(String::new());
+
+    let save = move |category: Category| {
+        spawn_local(async move {
+            match call::<_, ()>("save_category", &CategoryArgs { category }).await {
+                Ok(()) => {
+                    set_error.set(None);
+                    on_changed.run(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let add_category = move |_| {
+        let (pl, en) = (new_pl.get().trim().to_string(), new_en.get().trim().to_string());
+        if pl.is_empty() && en.is_empty() {
+            return;
+        }
+        // Brakującą nazwę w drugim języku uzupełniamy tą podaną
+        let (pl, en) = match (pl.is_empty(), en.is_empty()) {
+            (true, _) => (en.clone(), en),
+            (_, true) => (pl.clone(), pl),
+            _ => (pl, en),
+        };
+        let parent = Some(new_parent.get()).filter(|p| !p.is_empty());
+        save(Category { parent, ..Category::new(new_kind.get(), &pl, &en, &new_color.get(), &new_icon.get()) });
+        set_new_pl.set(String::new());
+        set_new_en.set(String::new());
+    };
+
+    let merge = move |from: String, into: String| {
+        spawn_local(async move {
+            match call::<_, ()>("merge_categories", &MergeArgs { from, into }).await {
+                Ok(()) => {
+                    set_error.set(None);
+                    on_changed.run(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div class={move || get_box_style(is_dark.get())}>
+            <h2 class="text-xl font-bold mb-6">{move || t("categories", &language.get())}</h2>
+
+            // Nowa kategoria
+            <div class="grid grid-cols-1 md:grid-cols-7 gap-3 mb-8">
+                <select class={move || get_input_style(is_dark.get())} on:change=move |ev| {
+                    if let Some(kind) = TransactionKind::parse(&event_target_value(&ev)) {
+                        set_new_kind.set(kind);
+                        set_new_parent.set(String::new());
+                    }
+                }>
+                    {TransactionKind::ALL.into_iter().map(|k| view! {
+                        <option class="text-slate-800" value=k.as_str() selected=move || new_kind.get() == k>{move || t(&format!("kind_{}", k.as_str()), &language.get())}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+                <select class={move || get_input_style(is_dark.get())} on:change=move |ev| set_new_parent.set(event_target_value(&ev))>
+                    {move || {
+                        let all = categories.get();
+                        let kind = new_kind.get();
+                        let options = Category::tree(&all)
+                            .into_iter()
+                            .filter(|(c, _)| c.kind == kind && !c.archived)
+                            .map(|(c, _)| {
+                                let value = c.id.clone();
+                                let selected = new_parent.get_untracked() == c.id;
+                                view! { <option class="text-slate-800" value=value selected=selected>{format!("{} {}", c.icon, category_path(&all, &c.id, &language.get()))}</option> }
+                            })
+                            .collect::<Vec<_>>();
+                        view! {
+                            <option class="text-slate-800" value="" selected=new_parent.get_untracked().is_empty()>{t("no_parent", &language.get())}</option>
+                            {options}
+                        }
+                    }}
+                </select>
+                <input type="text" placeholder={move || t("label_pl", &language.get())} class={move || get_input_style(is_dark.get())} on:input=move |ev| set_new_pl.set(event_target_value(&ev)) prop:value=new_pl />
+                <input type="text" placeholder={move || t("label_en", &language.get())} class={move || get_input_style(is_dark.get())} on:input=move |ev| set_new_en.set(event_target_value(&ev)) prop:value=new_en />
+                <input type="color" class="h-10 w-full rounded cursor-pointer" on:input=move |ev| set_new_color.set(event_target_value(&ev)) prop:value=new_color />
+                <input type="text" maxlength="4" class={move || get_input_style(is_dark.get())} on:input=move |ev| set_new_icon.set(event_target_value(&ev)) prop:value=new_icon />
+                <button class="bg-emerald-600 text-white font-bold py-2 px-4 rounded-lg hover:bg-emerald-700 transition" on:click=add_category>
+                    {move || t("add_category", &language.get())}
+                </button>
+            </div>
+
+            {move || error.get().map(|e| view! {
+                <div class="mb-6 p-3 bg-red-100 border border-red-400 text-red-800 rounded-lg text-center font-medium">{e}</div>
+            })}
+
+            // Istniejące kategorie, pogrupowane wg rodzaju
+            {move || {
+                let all = categories.get();
+                TransactionKind::ALL.into_iter().map(|kind| {
+                    let group: Vec<Category> = Category::tree(&all)
+                        .into_iter()
+                        .filter(|(c, _)| c.kind == kind)
+                        .map(|(c, _)| c.clone())
+                        .collect();
+                    let siblings = group.clone();
+                    let all = all.clone();
+                    view! {
+                        <h3 class="text-sm font-bold uppercase opacity-60 mt-6 mb-2">{move || t(&format!("kind_{}", kind.as_str()), &language.get())}</h3>
+                        <div class="flex flex-col gap-2">
+                            {group.into_iter().map(|category| {
+                                let targets: Vec<Category> = siblings.iter().filter(|c| c.id != category.id && !c.archived).cloned().collect();
+                                // Możliwi rodzice: ta sama grupa, bez samej kategorii i jej potomków
+                                let parents: Vec<(String, String)> = siblings
+                                    .iter()
+                                    .filter(|c| Category::valid_parent(&all, &category.id, kind, &c.id))
+                                    .map(|c| (c.id.clone(), format!("{} {}", c.icon, category_path(&all, &c.id, &language.get_untracked()))))
+                                    .collect();
+                                let depth = Category::ancestors(&all, &category.id).len() - 1;
+                                view! { <CategoryRow category=category targets=targets parents=parents depth=depth language=language is_dark=is_dark on_save=Callback::new(save) on_merge=Callback::new(move |(from, into)| merge(from, into))/> }
+                            }).collect::<Vec<_>>()}
+                        </div>
+                    }
+                }).collect::<Vec<_>>()
+            }}
+        </div>
+    }
+}
+
+// Jeden wiersz listy; każde pole zapisuje się po zatwierdzeniu zmiany (on:change)
+#[component]
+fn CategoryRow(
+    category: Category,
+    targets: Vec<Category>,
+    // (id, ścieżka) kategorii, pod które można przenieść tę kategorię
+    parents: Vec<(String, String)>,
+    depth: usize,
+    language: ReadSignal<String>,
+    is_dark: Signal<bool>,
+    on_save: Callback<Category>,
+    on_merge: Callback<(String, String)>,
+) -> impl IntoView {
+    let (merge_target, set_merge_target) = signal(targets.first().map(|c| c.id.clone()).unwrap_or_default());
+    let has_targets = !targets.is_empty();
+    let archived = category.archived;
+
+    let edit = {
+        let category = category.clone();
+        move |change: &dyn Fn(&mut Category)| {
+            let mut updated = category.clone();
+            change(&mut updated);
+            on_save.run(updated);
+        }
+    };
+    let edit_pl = edit.clone();
+    let edit_en = edit.clone();
+    let edit_color = edit.clone();
+    let edit_icon = edit.clone();
+    let edit_archived = edit.clone();
+    let edit_parent = edit;
+    let parent = category.parent.clone().unwrap_or_default();
+    let id = category.id.clone();
+
+    view! {
+        <div class={if archived { "grid grid-cols-1 md:grid-cols-8 gap-2 items-center opacity-50" } else { "grid grid-cols-1 md:grid-cols-8 gap-2 items-center" }} style=format!("padding-left: {}rem", depth)>
+            <input type="text" maxlength="4" class={move || get_input_style(is_dark.get())} prop:value=category.icon.clone()
+                on:change=move |ev| { let icon = event_target_value(&ev); edit_icon(&|c| c.icon = icon.clone()); } />
+            <input type="color" class="h-10 w-full rounded cursor-pointer" prop:value=category.color.clone()
+                on:change=move |ev| { let color = event_target_value(&ev); edit_color(&|c| c.color = color.clone()); } />
+            <input type="text" class={move || get_input_style(is_dark.get())} prop:value=category.labels.get("pl").cloned().unwrap_or_default()
+                on:change=move |ev| { let label = event_target_value(&ev); edit_pl(&|c| { c.labels.insert("pl".to_string(), label.clone()); }); } />
+            <input type="text" class={move || get_input_style(is_dark.get())} prop:value=category.labels.get("en").cloned().unwrap_or_default()
+                on:change=move |ev| { let label = event_target_value(&ev); edit_en(&|c| { c.labels.insert("en".to_string(), label.clone()); }); } />
+            <select class={move || get_input_style(is_dark.get())} on:change=move |ev| {
+                let parent = Some(event_target_value(&ev)).filter(|p| !p.is_empty());
+                edit_parent(&|c| c.parent = parent.clone());
+            }>
+                <option class="text-slate-800" value="" selected=parent.is_empty()>{move || t("no_parent", &language.get())}</option>
+                {parents.into_iter().map(|(value, path)| {
+                    let selected = value == parent;
+                    view! { <option class="text-slate-800" value=value selected=selected>{path}</option> }
+                }).collect::<Vec<_>>()}
+            </select>
+            <button class="bg-slate-500 text-white font-bold py-2 px-3 rounded-lg hover:bg-slate-600 transition text-sm" on:click=move |_| edit_archived(&|c| c.archived = !archived)>
+                {move || if archived { t("unarchive", &language.get()) } else { t("archive", &language.get()) }}
+            </button>
+            <select class={move || get_input_style(is_dark.get())} disabled=!has_targets on:change=move |ev| set_merge_target.set(event_target_value(&ev))>
+                {targets.iter().map(|target| {
+                    let target = target.clone();
+                    let value = target.id.clone();
+                    view! { <option class="text-slate-800" value=value>{move || format!("{} {}", target.icon, target.label(&language.get()))}</option> }
+                }).collect::<Vec<_>>()}
+            </select>
+            <button class="bg-red-500/10 text-red-500 hover:bg-red-500 hover:text-white font-bold py-2 px-3 rounded-lg transition text-sm" disabled=!has_targets
+                on:click=move |_| on_merge.run((id.clone(), merge_target.get()))>
+                {move || t("merge_into", &language.get())}
+            </button>
+        </div>
+    }
+}
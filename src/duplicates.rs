@@ -0,0 +1,59 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::api::call;
+use crate::models::*;
+use crate::translate::t;
+use crate::utils::*;
+
+#[derive(serde::Serialize)]
+struct MergeArgs {
+    merge: DuplicateMerge,
+}
+
+// Zapisana transakcja w jednym wierszu: data, tytuł i kwota
+pub fn duplicate_summary(tx: &Transaction, lang: &str) -> String {
+    format!("{} · {} · {}", format_date_display(tx.date, lang), tx.title, format_currency(tx.amount, lang))
+}
+
+// Ostrzeżenie po dodaniu transakcji podobnej do zapisanych: można ją scalić z jedną
+// z nich (dodana znika) albo zachować obie
+#[component]
+pub fn DuplicateNotice(
+    language: ReadSignal<String>,
+    added: Transaction,
+    candidates: Vec<Transaction>,
+    on_resolved: Callback<()>,
+) -> impl IntoView {
+    let merge = move |kept: Transaction, duplicate: Transaction| {
+        spawn_local(async move {
+            let merge = DuplicateMerge { kept, duplicate };
+            if call::<_, Transaction>("merge_transactions", &MergeArgs { merge }).await.is_ok() {
+                on_resolved.run(());
+            }
+        });
+    };
+
+    view! {
+        <div class="mt-4 p-3 bg-amber-100 border border-amber-400 text-amber-800 rounded-lg">
+            <p class="font-bold mb-2">{move || t("possible_duplicate", &language.get())}</p>
+            {candidates.into_iter().map(|kept| {
+                let summary = kept.clone();
+                let duplicate = added.clone();
+                view! {
+                    <div class="flex justify-between items-center gap-4 mb-2">
+                        <span class="truncate">{move || duplicate_summary(&summary, &language.get())}</span>
+                        <button class="bg-amber-500 text-white font-bold py-1 px-3 rounded-lg hover:bg-amber-600 transition"
+                            on:click=move |_| merge(kept.clone(), duplicate.clone())>
+                            {move || t("merge_duplicate", &language.get())}
+                        </button>
+                    </div>
+                }
+            }).collect::<Vec<_>>()}
+            <button class="w-full bg-slate-500 text-white font-bold py-1 px-3 rounded-lg hover:bg-slate-600 transition"
+                on:click=move |_| on_resolved.run(())>
+                {move || t("keep_both", &language.get())}
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{Datelike, Local, NaiveDate};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::api::{call, NoArgs};
+use crate::models::*;
+use crate::translate::t;
+use crate::utils::*;
+
+#[derive(serde::Serialize)]
+struct ExportCsvArgs {
+    filter: TransactionFilter,
+    language: String,
+}
+
+// Eksport transakcji do CSV (zakres dat i kategorie) oraz całych danych do JSON.
+// Backend zapisuje plik w katalogu exports/ i pokazuje go w menedżerze plików.
+#[component]
+pub fn ExportPanel(
+    language: ReadSignal<String>,
+    is_dark: Signal<bool>,
+    categories: ReadSignal<Vec<Category>>,
+) -> impl IntoView {
+    let today = Local::now().date_naive();
+    let (from, set_from) = signal(today.with_ordinal(1).unwrap_or(today));
+    let (to, set_to) = signal(today);
+    // Pusta lista - wszystkie kategorie
+    let (selected, set_selected) = signal::<Vec<String>>(vec![]);
+    let (message, set_message) = signal::<Option<String>>(None);
+
+    let show_result = move |result: Result<String, String>| {
+        let msg = match result {
+            Ok(path) => format!("{}: {}", t("exported_to", &language.get_untracked()), path),
+            Err(e) => e,
+        };
+        set_message.set(Some(msg));
+    };
+
+    let export_csv = move |_| {
+        let filter = TransactionFilter {
+            from: from.get_untracked(),
+            to: to.get_untracked(),
+            categories: selected.get_untracked(),
+        };
+        let language = language.get_untracked();
+        spawn_local(async move {
+            show_result(call::<_, String>("export_csv", &ExportCsvArgs { filter, language }).await);
+        });
+    };
+
+    let export_json = move |_| {
+        spawn_local(async move {
+            show_result(call::<_, String>("export_json", &NoArgs {}).await);
+        });
+    };
+
+    let toggle = move |id: String, checked: bool| {
+        set_selected.update(|list| {
+            list.retain(|c| *c != id);
+            if checked {
+                list.push(id);
+            }
+        });
+    };
+
+    let date_input = move |value: ReadSignal<NaiveDate>, set: WriteSignal<NaiveDate>| {
+        view! {
+            <input type="date" class={move || get_input_style(is_dark.get())}
+                on:input=move |ev| { if let Ok(date) = NaiveDate::parse_from_str(&event_target_value(&ev), "%Y-%m-%d") { set.set(date) } }
+                prop:value=move || value.get().format("%Y-%m-%d").to_string() />
+        }
+    };
+
+    view! {
+        <div class={move || get_box_style(is_dark.get())}>
+            <div class="flex justify-between items-center mb-6 gap-4">
+                <h2 class="text-xl font-bold">{move || t("export", &language.get())}</h2>
+                <button class="bg-slate-500 text-white font-bold py-2 px-4 rounded-lg hover:bg-slate-600 transition" on:click=export_json>
+                    {move || t("export_json", &language.get())}
+                </button>
+            </div>
+
+            {move || message.get().map(|msg| view! {
+                <div class="mb-6 p-3 bg-slate-100 dark:bg-slate-700 rounded-lg text-center font-medium break-all">{msg}</div>
+            })}
+
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-3 mb-4">
+                <label class="text-sm font-bold opacity-70">{move || t("date_from", &language.get())} {date_input(from, set_from)}</label>
+                <label class="text-sm font-bold opacity-70">{move || t("date_to", &language.get())} {date_input(to, set_to)}</label>
+            </div>
+
+            // Zaznaczenie kategorii obejmuje jej podkategorie
+            <p class="text-sm font-bold opacity-70 mb-2">{move || t("export_categories", &language.get())}</p>
+            <div class="grid grid-cols-2 md:grid-cols-3 gap-1 mb-6 text-sm">
+                {move || categories.with(|list| Category::tree(list).into_iter().map(|(c, depth)| {
+                    let id = c.id.clone();
+                    let checked_id = c.id.clone();
+                    let label = format!("{} {}", c.icon, c.label(&language.get()));
+                    view! {
+                        <label class="flex items-center gap-2" style=format!("padding-left: {}rem", depth)>
+                            <input type="checkbox" prop:checked=move || selected.with(|s| s.contains(&checked_id))
+                                on:change=move |ev| toggle(id.clone(), event_target_checked(&ev)) />
+                            {label}
+                        </label>
+                    }
+                }).collect::<Vec<_>>())}
+            </div>
+
+            <button class="bg-emerald-600 text-white font-bold py-2 px-4 rounded-lg hover:bg-emerald-700 transition" on:click=export_csv>
+                {move || t("export_csv", &language.get())}
+            </button>
+        </div>
+    }
+}
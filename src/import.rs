@@ -0,0 +1,406 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use std::collections::HashSet;
+
+use crate::api::{call, pick_file};
+use crate::duplicates::duplicate_summary;
+use crate::models::*;
+use crate::translate::t;
+use crate::utils::*;
+
+#[derive(serde::Serialize)]
+struct InspectArgs {
+    path: String,
+    currency: CurrencyCode,
+}
+
+#[derive(serde::Serialize)]
+struct OptionsArgs {
+    path: String,
+    options: CsvOptions,
+}
+
+#[derive(serde::Serialize)]
+struct StatementArgs {
+    path: String,
+    currency: CurrencyCode,
+}
+
+#[derive(serde::Serialize)]
+struct CommitArgs {
+    transactions: Vec<Transaction>,
+    merges: Vec<DuplicateMerge>,
+}
+
+const DELIMITER_CHOICES: [char; 4] = [';', ',', '\t', '|'];
+
+// Pola transakcji, które da się przypisać do kolumn: (klucz tłumaczenia, odczyt, zapis)
+type MappedField = (&'static str, fn(&ColumnMapping) -> Option<usize>, fn(&mut ColumnMapping, Option<usize>));
+
+const MAPPED_FIELDS: [MappedField; 4] = [
+    ("column_date", |m| m.date, |m, column| m.date = column),
+    ("column_title", |m| m.title, |m, column| m.title = column),
+    ("column_amount", |m| m.amount, |m, column| m.amount = column),
+    ("column_category", |m| m.category, |m, column| m.category = column),
+];
+
+// Nazwy kolumn do list wyboru: z nagłówka, a bez niego "Kolumna 1", "Kolumna 2"...
+fn column_names(csv: &CsvFile, lang: &str) -> Vec<String> {
+    let width = csv.sample.iter().map(Vec::len).chain([csv.headers.len()]).max().unwrap_or(0);
+    (0..width)
+        .map(|i| match csv.headers.get(i).map(|h| h.trim()).filter(|h| !h.is_empty()) {
+            Some(header) => header.to_string(),
+            None => format!("{} {}", t("column", lang), i + 1),
+        })
+        .collect()
+}
+
+fn encoding_label(encoding: TextEncoding) -> &'static str {
+    match encoding {
+        TextEncoding::Utf8 => "UTF-8",
+        TextEncoding::Windows1250 => "Windows-1250",
+    }
+}
+
+const STATEMENT_EXTENSIONS: [&str; 9] = ["csv", "txt", "ofx", "qfx", "qif", "sta", "mt940", "940", "xml"];
+
+// Pliki, które nie są CSV, czytamy jako wyciągi OFX/QFX/QIF/MT940/camt.053
+fn is_csv(path: &str) -> bool {
+    let extension = path.rsplit('.').next().unwrap_or_default().to_lowercase();
+    extension == "csv" || extension == "txt"
+}
+
+// Zakładka importu wyciągu: CSV z poprawkami wykrytych ustawień i kolumn albo OFX/QFX/QIF,
+// MT940 i camt.053 od razu w podglądzie. Błędne i już zaimportowane wiersze są zaznaczone, poprawne
+// zapisujemy jedną partią.
+#[component]
+pub fn ImportWizard(
+    language: ReadSignal<String>,
+    is_dark: Signal<bool>,
+    currency: Signal<CurrencyCode>,
+    categories: ReadSignal<Vec<Category>>,
+    on_changed: Callback<()>,
+) -> impl IntoView {
+    let (path, set_path) = signal::<Option<String>>(None);
+    let (file, set_file) = signal::<Option<CsvFile>>(None);
+    // Waluta wyciągu (gdy plik jej nie podaje); Some oznacza import wyciągu, nie CSV
+    let (statement_currency, set_statement_currency) = signal::<Option<CurrencyCode>>(None);
+    let (rows, set_rows) = signal::<Vec<ImportRow>>(vec![]);
+    // Numery wierszy-duplikatów, które użytkownik chce zapisać mimo to; pozostałe scalamy
+    let keep_both = RwSignal::new(HashSet::<usize>::new());
+    let (message, set_message) = signal::<Option<String>>(None);
+
+    let load_statement = move |chosen: String, currency: CurrencyCode| {
+        spawn_local(async move {
+            let args = StatementArgs { path: chosen.clone(), currency };
+            match call::<_, Vec<ImportRow>>("preview_statement", &args).await {
+                Ok(list) => {
+                    set_path.set(Some(chosen));
+                    set_file.set(None);
+                    set_statement_currency.set(Some(currency));
+                    keep_both.set(HashSet::new());
+                    set_rows.set(list);
+                    set_message.set(None);
+                }
+                Err(e) => set_message.set(Some(e)),
+            }
+        });
+    };
+
+    let choose_file = move |_| {
+        spawn_local(async move {
+            let Some(chosen) = pick_file("CSV / OFX / QIF / MT940 / camt.053", &STATEMENT_EXTENSIONS).await else {
+                return;
+            };
+            if !is_csv(&chosen) {
+                load_statement(chosen, currency.get_untracked());
+                return;
+            }
+            let args = InspectArgs { path: chosen.clone(), currency: currency.get_untracked() };
+            match call::<_, CsvFile>("inspect_csv", &args).await {
+                Ok(csv) => {
+                    set_path.set(Some(chosen));
+                    set_file.set(Some(csv));
+                    set_statement_currency.set(None);
+                    set_rows.set(vec![]);
+                    set_message.set(None);
+                }
+                Err(e) => set_message.set(Some(e)),
+            }
+        });
+    };
+
+    // Zmiana separatora, kodowania czy nagłówka zmienia kolumny - plik czytamy od nowa
+    let reread = move |change: &dyn Fn(&mut CsvOptions)| {
+        let (Some(path), Some(csv)) = (path.get_untracked(), file.get_untracked()) else {
+            return;
+        };
+        let mut options = csv.options;
+        change(&mut options);
+        set_rows.set(vec![]);
+        spawn_local(async move {
+            match call::<_, CsvFile>("reread_csv", &OptionsArgs { path, options }).await {
+                Ok(csv) => set_file.set(Some(csv)),
+                Err(e) => set_message.set(Some(e)),
+            }
+        });
+    };
+
+    // Zmiany, które nie wpływają na kolumny (przypisanie pól, waluta) - tylko nowy podgląd
+    let update_options = move |change: &dyn Fn(&mut CsvOptions)| {
+        set_file.update(|csv| {
+            if let Some(csv) = csv {
+                change(&mut csv.options);
+            }
+        });
+        set_rows.set(vec![]);
+    };
+
+    let show_preview = move |_| {
+        let (Some(path), Some(csv)) = (path.get_untracked(), file.get_untracked()) else {
+            return;
+        };
+        spawn_local(async move {
+            match call::<_, Vec<ImportRow>>("preview_csv", &OptionsArgs { path, options: csv.options }).await {
+                Ok(list) => {
+                    set_message.set(None);
+                    keep_both.set(HashSet::new());
+                    set_rows.set(list);
+                }
+                Err(e) => set_message.set(Some(e)),
+            }
+        });
+    };
+
+    let commit = move |_| {
+        let list = rows.get_untracked();
+        let kept = keep_both.get_untracked();
+        let invalid = list.iter().filter(|row| row.transaction.is_none()).count();
+        let (mut transactions, mut merges) = (vec![], vec![]);
+        for row in list {
+            match (row.transaction, row.duplicate_of) {
+                (Some(duplicate), Some(existing)) if !kept.contains(&row.line) => {
+                    merges.push(DuplicateMerge { kept: existing, duplicate })
+                }
+                (Some(transaction), _) => transactions.push(transaction),
+                (None, _) => {}
+            }
+        }
+        if transactions.is_empty() && merges.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            let lang = language.get_untracked();
+            match call::<_, ImportReport>("commit_import", &CommitArgs { transactions, merges }).await {
+                Ok(report) => {
+                    set_message.set(Some(format!(
+                        "{}: {}, {}: {}, {}: {}",
+                        t("imported", &lang),
+                        report.imported,
+                        t("merged", &lang),
+                        report.merged,
+                        t("skipped", &lang),
+                        report.skipped + invalid
+                    )));
+                    set_path.set(None);
+                    set_file.set(None);
+                    set_statement_currency.set(None);
+                    set_rows.set(vec![]);
+                    on_changed.run(());
+                }
+                Err(e) => set_message.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div class={move || get_box_style(is_dark.get())}>
+            <div class="flex justify-between items-center mb-6 gap-4">
+                <h2 class="text-xl font-bold">{move || t("import_statement", &language.get())}</h2>
+                <button class="bg-emerald-600 text-white font-bold py-2 px-4 rounded-lg hover:bg-emerald-700 transition" on:click=choose_file>
+                    {move || t("choose_file", &language.get())}
+                </button>
+            </div>
+
+            {move || message.get().map(|msg| view! {
+                <div class="mb-6 p-3 bg-slate-100 dark:bg-slate-700 rounded-lg text-center font-medium">{msg}</div>
+            })}
+
+            // Wyciąg OFX/QIF/MT940/camt.053: tylko waluta operacji, reszta wynika z pliku
+            {move || statement_currency.get().map(|selected| {
+                let lang = language.get();
+                view! {
+                    <p class="text-sm opacity-60 mb-4 truncate">{path.get().unwrap_or_default()}</p>
+                    <label class="block text-sm font-bold opacity-70 mb-6 md:w-1/3">{t("statement_currency", &lang)}
+                        <select class={move || get_input_style(is_dark.get())}
+                            on:change=move |ev| { if let (Some(c), Some(p)) = (CurrencyCode::new(&event_target_value(&ev)), path.get_untracked()) { load_statement(p, c) } }>
+                            {CURRENCIES.iter().map(|info| view! {
+                                <option class="text-slate-800" value=info.0 selected=selected.as_str() == info.0>{currency_label(info, &lang)}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                    </label>
+                }
+            })}
+
+            {move || file.get().map(|csv| {
+                let lang = language.get();
+                let options = csv.options.clone();
+                let names = column_names(&csv, &lang);
+                view! {
+                    <p class="text-sm opacity-60 mb-4 truncate">{path.get().unwrap_or_default()}</p>
+
+                    // Ustawienia odczytu pliku
+                    <div class="grid grid-cols-2 md:grid-cols-5 gap-3 mb-6 items-end">
+                        <label class="text-sm font-bold opacity-70">{t("delimiter", &lang)}
+                            <select class={move || get_input_style(is_dark.get())}
+                                on:change=move |ev| { if let Some(d) = event_target_value(&ev).parse::<usize>().ok().and_then(|i| DELIMITER_CHOICES.get(i)) { reread(&|o| o.delimiter = *d) } }>
+                                {DELIMITER_CHOICES.iter().enumerate().map(|(i, d)| {
+                                    let label = if *d == '\t' { t("tab_character", &lang) } else { format!("\"{}\"", d) };
+                                    view! { <option class="text-slate-800" value=i.to_string() selected=*d == options.delimiter>{label}</option> }
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </label>
+                        <label class="text-sm font-bold opacity-70">{t("encoding", &lang)}
+                            <select class={move || get_input_style(is_dark.get())}
+                                on:change=move |ev| { if let Some(e) = TextEncoding::parse(&event_target_value(&ev)) { reread(&|o| o.encoding = e) } }>
+                                {TextEncoding::ALL.into_iter().map(|e| view! {
+                                    <option class="text-slate-800" value=e.as_str() selected=e == options.encoding>{encoding_label(e)}</option>
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </label>
+                        <label class="text-sm font-bold opacity-70">{t("skip_rows", &lang)}
+                            <input type="number" min="0" class={move || get_input_style(is_dark.get())} prop:value=options.skip_rows.to_string()
+                                on:change=move |ev| { if let Ok(n) = event_target_value(&ev).parse::<usize>() { reread(&|o| o.skip_rows = n) } } />
+                        </label>
+                        <label class="text-sm font-bold opacity-70">{t("currency", &lang)}
+                            <select class={move || get_input_style(is_dark.get())}
+                                on:change=move |ev| { if let Some(c) = CurrencyCode::new(&event_target_value(&ev)) { update_options(&|o| o.currency = c) } }>
+                                {CURRENCIES.iter().map(|info| view! {
+                                    <option class="text-slate-800" value=info.0 selected=options.currency.as_str() == info.0>{currency_label(info, &lang)}</option>
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </label>
+                        <label class="flex items-center gap-2 text-sm font-bold opacity-70 pb-3">
+                            <input type="checkbox" prop:checked=options.has_header
+                                on:change=move |ev| { let checked = event_target_checked(&ev); reread(&|o| o.has_header = checked) } />
+                            {t("has_header", &lang)}
+                        </label>
+                    </div>
+
+                    // Przypisanie kolumn do pól transakcji
+                    <div class="grid grid-cols-2 md:grid-cols-4 gap-3 mb-6">
+                        {MAPPED_FIELDS.into_iter().map(|(key, get, set)| {
+                            let selected = get(&options.mapping);
+                            view! {
+                                <label class="text-sm font-bold opacity-70">{t(key, &lang)}
+                                    <select class={move || get_input_style(is_dark.get())}
+                                        on:change=move |ev| { let column = event_target_value(&ev).parse::<usize>().ok(); update_options(&|o| set(&mut o.mapping, column)) }>
+                                        <option class="text-slate-800" value="" selected=selected.is_none()>{t("not_mapped", &lang)}</option>
+                                        {names.iter().enumerate().map(|(i, name)| view! {
+                                            <option class="text-slate-800" value=i.to_string() selected=selected == Some(i)>{name.clone()}</option>
+                                        }).collect::<Vec<_>>()}
+                                    </select>
+                                </label>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+
+                    // Próbka pliku z nazwami kolumn
+                    <div class="overflow-x-auto mb-6">
+                        <table class="text-sm w-full">
+                            <thead><tr>
+                                {names.iter().map(|name| view! { <th class="text-left p-1 opacity-60">{name.clone()}</th> }).collect::<Vec<_>>()}
+                            </tr></thead>
+                            <tbody>
+                                {csv.sample.iter().take(5).map(|row| view! {
+                                    <tr>{row.iter().map(|cell| view! { <td class="p-1 truncate max-w-xs">{cell.clone()}</td> }).collect::<Vec<_>>()}</tr>
+                                }).collect::<Vec<_>>()}
+                            </tbody>
+                        </table>
+                    </div>
+
+                    <button class="bg-slate-500 text-white font-bold py-2 px-4 rounded-lg hover:bg-slate-600 transition mb-6" on:click=show_preview>
+                        {t("show_preview", &lang)}
+                    </button>
+                }
+            })}
+
+            {move || {
+                let list = rows.get();
+                if list.is_empty() {
+                    return None;
+                }
+                let valid = list.iter().filter(|row| row.transaction.is_some()).count();
+                let invalid = list.len() - valid;
+                Some(view! {
+                    <div class="flex justify-between items-center mb-4 gap-4">
+                        <span class="font-bold">
+                            {move || format!("{}: {}, {}: {}", t("rows_valid", &language.get()), valid, t("rows_invalid", &language.get()), invalid)}
+                        </span>
+                        <button class="bg-emerald-600 text-white font-bold py-2 px-4 rounded-lg hover:bg-emerald-700 transition disabled:opacity-40"
+                            disabled=valid == 0 on:click=commit>
+                            {move || t("commit_import", &language.get())}
+                        </button>
+                    </div>
+                    <PreviewTable rows=list language=language categories=categories keep_both=keep_both/>
+                })
+            }}
+        </div>
+    }
+}
+
+// Wiersze podglądu: odczytane transakcje, a pod nimi na czerwono wiersze z błędami.
+// Możliwe duplikaty zapisanych transakcji są na żółto, z wyborem: scalić czy zachować obie.
+#[component]
+fn PreviewTable(
+    rows: Vec<ImportRow>,
+    language: ReadSignal<String>,
+    categories: ReadSignal<Vec<Category>>,
+    keep_both: RwSignal<HashSet<usize>>,
+) -> impl IntoView {
+    view! {
+        <div class="flex flex-col gap-1 text-sm">
+            {rows.into_iter().map(|row| {
+                let line = row.line;
+                match row.transaction {
+                    Some(tx) => {
+                        let sign = if tx.kind == TransactionKind::Expense { "-" } else { "" };
+                        let style = if row.duplicate_of.is_some() {
+                            "p-2 rounded-lg bg-amber-500/10"
+                        } else {
+                            "p-2 rounded-lg bg-slate-100/50 dark:bg-slate-700/50"
+                        };
+                        view! {
+                            <div class=style>
+                                <div class="grid grid-cols-12 gap-2">
+                                    <span class="col-span-1 opacity-50">{line}</span>
+                                    <span class="col-span-2">{move || format_date_display(tx.date, &language.get())}</span>
+                                    <span class="col-span-4 truncate">{tx.title.clone()}</span>
+                                    <span class="col-span-2 text-right font-bold">{move || format!("{}{}", sign, format_currency(tx.amount, &language.get()))}</span>
+                                    <span class="col-span-3 truncate opacity-70">{move || categories.with(|list| category_label(list, &tx.category, &language.get()))}</span>
+                                </div>
+                                {row.duplicate_of.map(|existing| view! {
+                                    <div class="flex justify-between items-center gap-4 mt-1 text-amber-600">
+                                        <span class="truncate">{move || format!("{}: {}", t("possible_duplicate", &language.get()), duplicate_summary(&existing, &language.get()))}</span>
+                                        <label class="flex items-center gap-2 font-bold whitespace-nowrap">
+                                            <input type="checkbox" prop:checked=move || keep_both.with(|kept| kept.contains(&line))
+                                                on:change=move |ev| { let checked = event_target_checked(&ev); keep_both.update(|kept| { if checked { kept.insert(line); } else { kept.remove(&line); } }) } />
+                                            {move || t("keep_both", &language.get())}
+                                        </label>
+                                    </div>
+                                })}
+                            </div>
+                        }.into_any()
+                    }
+                    None => view! {
+                        <div class="grid grid-cols-12 gap-2 p-2 rounded-lg bg-red-500/10 text-red-500">
+                            <span class="col-span-1 opacity-70">{line}</span>
+                            <span class="col-span-7 truncate">{row.cells.join(" | ")}</span>
+                            <span class="col-span-4 font-bold">{row.error.unwrap_or_default()}</span>
+                        </div>
+                    }.into_any(),
+                }
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
@@ -1,7 +1,18 @@
 mod api;
+// Współdzielone z backendem (src-tauri) - nie każdy typ jest używany po obu stronach
+#[allow(dead_code)]
 mod models;
 mod utils;
 mod translate;
+mod recovery;
+mod breakdown;
+mod categories;
+mod rules;
+mod recurring;
+mod rates;
+mod duplicates;
+mod import;
+mod export;
 mod app;
 
 use app::*;
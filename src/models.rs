@@ -1,38 +1,1517 @@
+use chrono::{NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+// Wersja formatu zapisywanego dokumentu. Każda zmiana struktur poniżej wymaga
+// podbicia wersji i dopisania kroku migracji w src-tauri/src/migrations.rs
+pub const SCHEMA_VERSION: u32 = 12;
+
+// Kod waluty ISO 4217 (trzy wielkie litery), np. "PLN"
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CurrencyCode([u8; 3]);
+
+impl CurrencyCode {
+    pub const PLN: CurrencyCode = CurrencyCode(*b"PLN");
+    pub const EUR: CurrencyCode = CurrencyCode(*b"EUR");
+
+    pub fn new(code: &str) -> Option<CurrencyCode> {
+        let bytes: [u8; 3] = code.as_bytes().try_into().ok()?;
+        bytes
+            .iter()
+            .all(u8::is_ascii_uppercase)
+            .then_some(CurrencyCode(bytes))
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Konstruktor dopuszcza tylko litery ASCII
+        std::str::from_utf8(&self.0).unwrap_or("???")
+    }
+
+    // Dane z tabeli ISO 4217; None dla kodu spoza listy (np. z ręcznie dodanego kursu)
+    pub fn info(&self) -> Option<&'static CurrencyInfo> {
+        CURRENCIES.iter().find(|info| info.0 == self.as_str())
+    }
+
+    // Liczba miejsc po przecinku (jednostek podstawowych); nieznane waluty - 2
+    pub fn minor_digits(&self) -> u32 {
+        self.info().map(|info| info.1).unwrap_or(2)
+    }
+
+    fn minor_per_major(&self) -> i64 {
+        10i64.pow(self.minor_digits())
+    }
+
+    pub fn symbol(&self) -> &str {
+        self.info().map(|info| info.2).unwrap_or(self.as_str())
+    }
+
+    // Nazwa waluty w danym języku (pl albo en); nieznane - sam kod
+    pub fn name(&self, lang: &str) -> &str {
+        match self.info() {
+            Some(info) if lang == "pl" => info.4,
+            Some(info) => info.5,
+            None => self.as_str(),
+        }
+    }
+}
+
+impl TryFrom<String> for CurrencyCode {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        CurrencyCode::new(&code).ok_or_else(|| format!("niepoprawny kod waluty: {}", code))
+    }
+}
+
+impl From<CurrencyCode> for String {
+    fn from(code: CurrencyCode) -> String {
+        code.as_str().to_string()
+    }
+}
+
+// Kwota w jednostkach podstawowych (groszach, centach) - bez błędów zaokrągleń f64
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "MoneyRepr")]
+pub struct Money {
+    pub minor: i64,
+    pub currency: CurrencyCode,
+}
+
+// Odczyt przyjmuje też starą kwotę zapisaną jako f64. Waluty takiej liczby nie znamy -
+// przyjmujemy domyślną; migracja dokumentu (v2 -> v3) podstawia właściwą z ustawień.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MoneyRepr {
+    Exact { minor: i64, currency: CurrencyCode },
+    Legacy(f64),
+}
+
+impl From<MoneyRepr> for Money {
+    fn from(repr: MoneyRepr) -> Money {
+        match repr {
+            MoneyRepr::Exact { minor, currency } => Money { minor, currency },
+            MoneyRepr::Legacy(major) => Money::from_major(major, CurrencyCode::PLN),
+        }
+    }
+}
+
+impl Money {
+    pub fn new(minor: i64, currency: CurrencyCode) -> Money {
+        Money { minor, currency }
+    }
+
+    pub fn zero(currency: CurrencyCode) -> Money {
+        Money::new(0, currency)
+    }
+
+    // Tylko do migracji starych danych i przeliczeń kursowych - zaokrągla do jednostki
+    // podstawowej waluty (grosza, centa; dla JPY do jena)
+    pub fn from_major(major: f64, currency: CurrencyCode) -> Money {
+        Money::new((major * currency.minor_per_major() as f64).round() as i64, currency)
+    }
+
+    pub fn to_major(self) -> f64 {
+        self.minor as f64 / self.currency.minor_per_major() as f64
+    }
+
+    // "12,34", "12.34", "-5", "1 234,5" -> Money; więcej miejsc po przecinku, niż ma
+    // waluta (2 dla PLN, 0 dla JPY, 3 dla KWD), to błąd
+    pub fn parse(input: &str, currency: CurrencyCode) -> Option<Money> {
+        let cleaned: String = input
+            .trim()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let (negative, digits) = match cleaned.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, cleaned.as_str()),
+        };
+        let (whole, fraction) = match digits.split_once(['.', ',']) {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (digits, ""),
+        };
+        let digits_allowed = currency.minor_digits() as usize;
+        if whole.is_empty() && fraction.is_empty()
+            || fraction.len() > digits_allowed
+            || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+        let fraction: i64 = if digits_allowed == 0 {
+            0
+        } else {
+            format!("{:0<width$}", fraction, width = digits_allowed).parse().ok()?
+        };
+        let minor = whole.checked_mul(currency.minor_per_major())?.checked_add(fraction)?;
+        Some(Money::new(if negative { -minor } else { minor }, currency))
+    }
+
+    // Wartość do pola formularza: "12.5", "12", "0.05" (bez zbędnych zer)
+    pub fn to_input_string(self) -> String {
+        let text = self.to_decimal_string();
+        if text.contains('.') {
+            text.trim_end_matches('0').trim_end_matches('.').to_string()
+        } else {
+            text
+        }
+    }
+
+    // Kwota jako "-1234.50" - z tyloma miejscami po przecinku, ile ma waluta (eksport, formularze).
+    // Liczona na liczbach całkowitych, żeby duże kwoty nie traciły groszy.
+    pub fn to_decimal_string(self) -> String {
+        let digits = self.currency.minor_digits() as usize;
+        let abs = self.minor.unsigned_abs();
+        let per_major = self.currency.minor_per_major().unsigned_abs();
+        let sign = if self.minor < 0 { "-" } else { "" };
+        if digits == 0 {
+            format!("{}{}", sign, abs)
+        } else {
+            format!("{}{}.{:0width$}", sign, abs / per_major, abs % per_major, width = digits)
+        }
+    }
+
+    // Kwota do wyświetlenia wg zasad języka: "1 234,50 zł" (pl), "$1,234.50" (en).
+    // Separatory i miejsce symbolu zależą od języka, liczba miejsc po przecinku od waluty.
+    pub fn format(self, lang: &str) -> String {
+        let locale = Locale::of(lang);
+        let text = self.abs().to_decimal_string();
+        let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+
+        let mut grouped = String::new();
+        let group = whole.len() >= locale.min_grouping;
+        for (i, digit) in whole.chars().enumerate() {
+            if group && i > 0 && (whole.len() - i) % 3 == 0 {
+                grouped.push(locale.group);
+            }
+            grouped.push(digit);
+        }
+        if !fraction.is_empty() {
+            grouped.push(locale.decimal);
+            grouped.push_str(fraction);
+        }
+
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let symbol = self.currency.symbol();
+        let symbol_first = !locale.symbol_after && self.currency.info().is_some_and(|info| info.3);
+        if symbol_first {
+            format!("{}{}{}", sign, symbol, grouped)
+        } else {
+            format!("{}{}\u{a0}{}", sign, grouped, symbol)
+        }
+    }
+
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(Money::new(self.minor.checked_add(other.minor)?, self.currency))
+    }
+
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(Money::new(self.minor.checked_sub(other.minor)?, self.currency))
+    }
+
+    pub fn checked_sum<'a>(
+        items: impl IntoIterator<Item = &'a Money>,
+        currency: CurrencyCode,
+    ) -> Option<Money> {
+        items
+            .into_iter()
+            .try_fold(Money::zero(currency), |total, m| total.checked_add(*m))
+    }
+
+    pub fn abs(self) -> Money {
+        Money::new(self.minor.saturating_abs(), self.currency)
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.minor > 0
+    }
+
+    // Czy kwota przekracza ustawiony (dodatni) limit w tej samej walucie
+    pub fn is_over(&self, limit: &Money) -> bool {
+        limit.is_positive() && self.currency == limit.currency && self.minor > limit.minor
+    }
+}
+
+// 128-bitowy identyfikator transakcji zapisywany jak UUID ("xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx").
+// Nowe id to losowe UUID v4 z TransactionId::generate - jedynego miejsca, które je tworzy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TransactionId(u128);
+
+impl TransactionId {
+    pub fn generate() -> TransactionId {
+        const VERSION_MASK: u128 = 0xf << 76;
+        const VARIANT_MASK: u128 = 0x3 << 62;
+        let bits = rand::random::<u128>();
+        // Wersja 4 i wariant RFC 4122
+        TransactionId(bits & !VERSION_MASK & !VARIANT_MASK | (0x4 << 76) | (0x2 << 62))
+    }
+
+    // Id nadane przy migracji ze starego losowego u32. Pierwsze wystąpienie danego id
+    // dostaje occurrence 0, kolejne (kolizje) 1, 2, ... - wynik jest deterministyczny.
+    pub fn legacy(old: u64, occurrence: u64) -> TransactionId {
+        TransactionId(((occurrence as u128) << 64) | old as u128)
+    }
+
+    pub fn parse(text: &str) -> Option<TransactionId> {
+        let hex: String = text.chars().filter(|c| *c != '-').collect();
+        if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        u128::from_str_radix(&hex, 16).ok().map(TransactionId)
+    }
+}
+
+impl fmt::Display for TransactionId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hex = format!("{:032x}", self.0);
+        write!(f, "{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+    }
+}
+
+impl TryFrom<String> for TransactionId {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        TransactionId::parse(&text).ok_or_else(|| format!("niepoprawne id transakcji: {}", text))
+    }
+}
+
+impl From<TransactionId> for String {
+    fn from(id: TransactionId) -> String {
+        id.to_string()
+    }
+}
+
+// Rodzaj transakcji. Kwota jest zawsze dodatnia - kierunek wynika z rodzaju.
+// Przelew (np. na konto oszczędnościowe) nie jest ani przychodem, ani wydatkiem.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    #[default]
+    Expense,
+    Income,
+    Transfer,
+}
+
+impl TransactionKind {
+    pub const ALL: [TransactionKind; 3] = [
+        TransactionKind::Expense,
+        TransactionKind::Income,
+        TransactionKind::Transfer,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TransactionKind::Expense => "expense",
+            TransactionKind::Income => "income",
+            TransactionKind::Transfer => "transfer",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<TransactionKind> {
+        TransactionKind::ALL.into_iter().find(|kind| kind.as_str() == text)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Transaction {
-    pub id: u32,
+    pub id: TransactionId,
     pub title: String,
-    pub amount: f64,
-    pub date: String,
+    pub amount: Money,
+    pub kind: TransactionKind,
+    pub date: NaiveDate,
+    // Category::id
+    pub category: String,
+    // Identyfikator operacji nadany przez bank (np. FITID z OFX) - ta sama operacja
+    // z kolejnego wyciągu nie zostanie zaimportowana drugi raz
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    // Dowolne etykiety, np. nadane przez regułę kategoryzacji ("wakacje", "firma")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+// Kategoria zdefiniowana przez użytkownika. Transakcje i limity odwołują się do niej przez
+// `id`, które nigdy się nie zmienia - zmiana nazwy dotyczy tylko etykiet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Category {
+    pub id: String,
+    // Formularz pokazuje tylko kategorie pasujące do rodzaju transakcji
+    pub kind: TransactionKind,
+    // Kod języka ("pl", "en") -> nazwa
+    pub labels: BTreeMap<String, String>,
+    // Kolor w formacie "#rrggbb"
+    pub color: String,
+    pub icon: String,
+    // Zarchiwizowana nie pojawia się w formularzu, ale zostaje przy starych transakcjach
+    pub archived: bool,
+    // Kategoria nadrzędna (tego samego rodzaju); None = kategoria główna
+    pub parent: Option<String>,
+}
+
+// Kategorie startowe: (id, rodzaj, nazwa pl, nazwa en, kolor, ikona). Nazwy pl to dawne
+// wartości pola `category`, po których migracja rozpoznaje kategorię.
+pub const DEFAULT_CATEGORIES: &[(&str, TransactionKind, &str, &str, &str, &str)] = &[
+    ("general", TransactionKind::Expense, "Ogólne", "General", "#64748b", "\u{1F4E6}"),
+    ("daily", TransactionKind::Expense, "Codzienne Wydatki", "Daily Expenses", "#0ea5e9", "\u{1F6D2}"),
+    ("bills", TransactionKind::Expense, "Rachunki", "Bills", "#f59e0b", "\u{1F9FE}"),
+    ("food", TransactionKind::Expense, "Jedzenie", "Food", "#ef4444", "\u{1F37D}"),
+    ("transport", TransactionKind::Expense, "Auto i Transport", "Car & Transport", "#8b5cf6", "\u{1F697}"),
+    ("entertainment", TransactionKind::Expense, "Rozrywka", "Entertainment", "#ec4899", "\u{1F3AC}"),
+    ("uncategorized", TransactionKind::Expense, "Nieskategoryzowane", "Uncategorized", "#94a3b8", "\u{2753}"),
+    ("salary", TransactionKind::Income, "Wynagrodzenie", "Salary", "#10b981", "\u{1F4BC}"),
+    ("bonus", TransactionKind::Income, "Premia", "Bonus", "#22c55e", "\u{1F381}"),
+    ("refunds", TransactionKind::Income, "Zwroty", "Refunds", "#14b8a6", "\u{21A9}"),
+    ("other_income", TransactionKind::Income, "Inne Przychody", "Other Income", "#84cc16", "\u{1F4B0}"),
+    ("savings", TransactionKind::Transfer, "Oszczędności", "Savings", "#6366f1", "\u{1F3E6}"),
+    ("own_transfer", TransactionKind::Transfer, "Przelew Własny", "Own Transfer", "#64748b", "\u{1F501}"),
+];
+
+// Kategoria, do której trafiają transakcje bez rozpoznanej kategorii
+pub const FALLBACK_CATEGORY: &str = "uncategorized";
+
+const LEGACY_PREFIX: &str = "legacy-";
+
+impl Category {
+    pub fn new(kind: TransactionKind, label_pl: &str, label_en: &str, color: &str, icon: &str) -> Category {
+        Category {
+            id: format!("c-{:016x}", rand::random::<u64>()),
+            kind,
+            labels: BTreeMap::from([
+                ("pl".to_string(), label_pl.to_string()),
+                ("en".to_string(), label_en.to_string()),
+            ]),
+            color: color.to_string(),
+            icon: icon.to_string(),
+            archived: false,
+            parent: None,
+        }
+    }
+
+    pub fn defaults() -> Vec<Category> {
+        DEFAULT_CATEGORIES
+            .iter()
+            .map(|(id, kind, pl, en, color, icon)| Category {
+                id: id.to_string(),
+                ..Category::new(*kind, pl, en, color, icon)
+            })
+            .collect()
+    }
+
+    // Id dla dawnej nazwy spoza listy domyślnych. Zapisuje nazwę (szesnastkowo), więc ta sama
+    // nazwa zawsze daje to samo id, a kategorię da się odtworzyć z samego id.
+    pub fn legacy_id(name: &str) -> String {
+        let hex: String = name.bytes().map(|b| format!("{:02x}", b)).collect();
+        format!("{}{}", LEGACY_PREFIX, hex)
+    }
+
+    // Kategoria odtworzona z samego id - dla transakcji, których kategorii brakuje na liście
+    pub fn placeholder(id: &str) -> Category {
+        let name = id
+            .strip_prefix(LEGACY_PREFIX)
+            .and_then(|hex| {
+                let bytes: Option<Vec<u8>> = (0..hex.len())
+                    .step_by(2)
+                    .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+                    .collect();
+                String::from_utf8(bytes?).ok()
+            })
+            .unwrap_or_else(|| id.to_string());
+        Category {
+            id: id.to_string(),
+            ..Category::new(TransactionKind::Expense, &name, &name, "#94a3b8", "\u{1F3F7}")
+        }
+    }
+
+    // Łańcuch id od `id` w górę drzewa: [id, rodzic, dziadek, ...]. Brakujący rodzic
+    // albo cykl (np. po ręcznej edycji pliku) kończą łańcuch.
+    pub fn ancestors<'a>(categories: &'a [Category], id: &'a str) -> Vec<&'a str> {
+        let mut chain = vec![id];
+        let mut current = id;
+        while let Some(parent) = categories
+            .iter()
+            .find(|c| c.id == current)
+            .and_then(|c| c.parent.as_deref())
+        {
+            if chain.contains(&parent) {
+                break;
+            }
+            chain.push(parent);
+            current = parent;
+        }
+        chain
+    }
+
+    // Kategorie w kolejności drzewa (rodzic, potem jego podkategorie) z poziomem zagłębienia.
+    // Kategorie z nieistniejącym rodzicem są traktowane jak główne.
+    pub fn tree(categories: &[Category]) -> Vec<(&Category, usize)> {
+        let is_root = |c: &Category| {
+            c.parent
+                .as_deref()
+                .is_none_or(|parent| !categories.iter().any(|other| other.id == parent))
+        };
+        let mut ordered: Vec<(&Category, usize)> = vec![];
+        let mut stack: Vec<(&Category, usize)> = categories.iter().filter(|c| is_root(c)).map(|c| (c, 0)).rev().collect();
+        while let Some((category, depth)) = stack.pop() {
+            if ordered.iter().any(|(c, _)| c.id == category.id) {
+                continue;
+            }
+            ordered.push((category, depth));
+            stack.extend(
+                categories
+                    .iter()
+                    .filter(|c| c.parent.as_deref() == Some(category.id.as_str()))
+                    .map(|c| (c, depth + 1))
+                    .rev(),
+            );
+        }
+        // Kategorie w cyklu nie mają korzenia - pokazujemy je na końcu jako główne
+        for category in categories {
+            if !ordered.iter().any(|(c, _)| c.id == category.id) {
+                ordered.push((category, 0));
+            }
+        }
+        ordered
+    }
+
+    // Czy ustawienie `parent` jako rodzica kategorii `id` jest poprawne: rodzic istnieje,
+    // jest tego samego rodzaju i nie leży w poddrzewie `id` (inaczej powstałby cykl)
+    pub fn valid_parent(categories: &[Category], id: &str, kind: TransactionKind, parent: &str) -> bool {
+        categories.iter().any(|c| c.id == parent && c.kind == kind)
+            && !Category::ancestors(categories, parent).contains(&id)
+    }
+
+    // Nazwa w danym języku; gdy jej brak - polska, a w ostateczności id
+    pub fn label(&self, lang: &str) -> &str {
+        [lang, "pl"]
+            .iter()
+            .filter_map(|lang| self.labels.get(*lang))
+            .map(String::as_str)
+            .find(|label| !label.trim().is_empty())
+            .unwrap_or(&self.id)
+    }
+}
+
+// Reguła automatycznej kategoryzacji: transakcja, która spełnia wszystkie podane warunki,
+// dostaje kategorię i tagi reguły. O pierwszeństwie decyduje kolejność na liście reguł.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub id: String,
+    pub enabled: bool,
+    pub conditions: RuleConditions,
+    // Category::id; reguła pasuje tylko do transakcji tego samego rodzaju co kategoria
+    pub category: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+// Warunki reguły; puste pola są pomijane
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleConditions {
+    // Fragment tytułu, bez względu na wielkość liter
+    #[serde(default)]
+    pub title_contains: String,
+    // Wyrażenie regularne dopasowywane do tytułu
+    #[serde(default)]
+    pub title_regex: String,
+    // Kontrahent: część tytułu przed " – " (tak zapisujemy operacje z wyciągów) albo cały tytuł
+    #[serde(default)]
+    pub payee: String,
+    // Zakres kwoty (włącznie) w walucie transakcji; inne waluty nie pasują
+    #[serde(default)]
+    pub min_amount: Option<Money>,
+    #[serde(default)]
+    pub max_amount: Option<Money>,
+}
+
+impl RuleConditions {
+    pub fn is_empty(&self) -> bool {
+        [&self.title_contains, &self.title_regex, &self.payee].iter().all(|text| text.trim().is_empty())
+            && self.min_amount.is_none()
+            && self.max_amount.is_none()
+    }
+}
+
+impl CategoryRule {
+    pub fn new(category: &str) -> CategoryRule {
+        CategoryRule {
+            id: format!("r-{:016x}", rand::random::<u64>()),
+            enabled: true,
+            conditions: RuleConditions::default(),
+            category: category.to_string(),
+            tags: vec![],
+        }
+    }
+}
+
+// Podpowiedzi o takiej pewności formularz nadaje sam; słabsze tylko pokazuje
+pub const AUTO_APPLY_CONFIDENCE: f64 = 0.7;
+
+// Kategoria podpowiedziana na podstawie historii transakcji; confidence od 0 do 1
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CategorySuggestion {
     pub category: String,
+    pub confidence: f64,
+}
+
+// Jak często powtarza się transakcja cykliczna
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    #[default]
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    pub const ALL: [Frequency; 4] = [Frequency::Daily, Frequency::Weekly, Frequency::Monthly, Frequency::Yearly];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+            Frequency::Monthly => "monthly",
+            Frequency::Yearly => "yearly",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Frequency> {
+        Frequency::ALL.into_iter().find(|f| f.as_str() == text)
+    }
+}
+
+// N-ty dzień tygodnia miesiąca, np. druga środa; nth = -1 to ostatni taki dzień
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NthWeekday {
+    pub nth: i8,
+    pub weekday: Weekday,
+}
+
+// Kiedy kończy się powtarzanie: nigdy, po dacie (włącznie) albo po liczbie wystąpień
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecurrenceEnd {
+    #[default]
+    Never,
+    Until { date: NaiveDate },
+    Count { count: u32 },
+}
+
+// Harmonogram: od daty `start` co `interval` dni/tygodni/miesięcy/lat. Miesięczne i roczne
+// wypadają w dniu miesiąca z daty startu (31. w krótszym miesiącu to jego ostatni dzień),
+// a z `nth_weekday` - w n-tym dniu tygodnia miesiąca (roczne: miesiąca z daty startu).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub start: NaiveDate,
+    pub frequency: Frequency,
+    pub interval: u32,
+    #[serde(default)]
+    pub nth_weekday: Option<NthWeekday>,
+    #[serde(default)]
+    pub end: RecurrenceEnd,
+}
+
+// Zmiana jednego wystąpienia (wskazanego planowaną datą): pominięcie albo inne dane
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OccurrenceChange {
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub skip: bool,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub amount: Option<Money>,
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+impl OccurrenceChange {
+    pub fn is_empty(&self) -> bool {
+        !self.skip && self.title.is_none() && self.amount.is_none() && self.category.is_none()
+    }
+}
+
+// Szablon transakcji cyklicznej (czynsz, rachunki, subskrypcje). Wystąpienia, które już
+// nadeszły, przy starcie aplikacji stają się zwykłymi transakcjami.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecurringTemplate {
+    pub id: String,
+    pub title: String,
+    pub amount: Money,
+    pub kind: TransactionKind,
+    pub category: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub schedule: Schedule,
+    #[serde(default)]
+    pub changes: Vec<OccurrenceChange>,
+    // Data ostatniego wystąpienia zamienionego już na transakcję (albo pominiętego)
+    #[serde(default)]
+    pub materialized_until: Option<NaiveDate>,
+}
+
+impl RecurringTemplate {
+    pub fn new(title: &str, amount: Money, kind: TransactionKind, category: &str, schedule: Schedule) -> RecurringTemplate {
+        RecurringTemplate {
+            id: format!("rt-{:016x}", rand::random::<u64>()),
+            title: title.to_string(),
+            amount,
+            kind,
+            category: category.to_string(),
+            tags: vec![],
+            schedule,
+            changes: vec![],
+            materialized_until: None,
+        }
+    }
+}
+
+// Przyszłe wystąpienie szablonu do pokazania na liście nadchodzących
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Occurrence {
+    pub template: String,
+    // Planowana data - po niej wskazujemy wystąpienie w OccurrenceChange
+    pub date: NaiveDate,
+    // Transakcja, która powstanie (ze zmianami tego wystąpienia)
+    pub transaction: Transaction,
+    pub skipped: bool,
+}
+
+// Wzrost ceny między kolejnymi płatnościami subskrypcji
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriceChange {
+    // Data pierwszej płatności po nowej cenie
+    pub date: NaiveDate,
+    pub from: Money,
+    pub to: Money,
+}
+
+// Subskrypcja wykryta w historii: płatności temu samemu kontrahentowi w podobnej kwocie,
+// w regularnych odstępach. `template` to gotowy szablon transakcji cyklicznej, zaczynający
+// się od następnej spodziewanej płatności.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub payee: String,
+    pub payments: usize,
+    pub first_date: NaiveDate,
+    pub last_date: NaiveDate,
+    // Według ostatniej ceny
+    pub yearly_cost: Money,
+    pub price_increases: Vec<PriceChange>,
+    pub template: RecurringTemplate,
+}
+
+// Przychody i wydatki z danego okresu w jednej walucie
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CashFlow {
+    pub income: Money,
+    pub expenses: Money,
+}
+
+impl CashFlow {
+    pub fn zero(currency: CurrencyCode) -> Self {
+        Self {
+            income: Money::zero(currency),
+            expenses: Money::zero(currency),
+        }
+    }
+
+    // Kwoty w innej walucie (lub takie, które przepełniłyby sumę) są pomijane
+    pub fn of<'a>(transactions: impl IntoIterator<Item = &'a Transaction>, currency: CurrencyCode) -> Self {
+        let mut flow = CashFlow::zero(currency);
+        for tx in transactions {
+            flow.add(tx);
+        }
+        flow
+    }
+
+    pub fn add(&mut self, tx: &Transaction) {
+        let total = match tx.kind {
+            TransactionKind::Expense => &mut self.expenses,
+            TransactionKind::Income => &mut self.income,
+            TransactionKind::Transfer => return,
+        };
+        *total = total.checked_add(tx.amount).unwrap_or(*total);
+    }
+
+    // Bilans: przychody minus wydatki (ujemny, gdy wydano więcej)
+    pub fn net(&self) -> Money {
+        self.income.checked_sub(self.expenses).unwrap_or(self.income)
+    }
+}
+
+// Skąd pochodzi kurs: import tabel EBC/NBP albo ręczna korekta użytkownika
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateSource {
+    Ecb,
+    Nbp,
+    #[default]
+    Manual,
+}
+
+impl RateSource {
+    pub const ALL: [RateSource; 3] = [RateSource::Ecb, RateSource::Nbp, RateSource::Manual];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateSource::Ecb => "ecb",
+            RateSource::Nbp => "nbp",
+            RateSource::Manual => "manual",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<RateSource> {
+        RateSource::ALL.into_iter().find(|source| source.as_str() == value)
+    }
+}
+
+// Kurs z danego dnia: 1 jednostka `base` = `rate` jednostek `quote`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRate {
+    pub date: NaiveDate,
+    pub base: CurrencyCode,
+    pub quote: CurrencyCode,
+    pub rate: f64,
+    pub source: RateSource,
+}
+
+// Tabela kursów do przeliczeń: dla każdej pary waluty kursy wg dnia, w obu kierunkach.
+// Ręczna korekta ma pierwszeństwo przed importem z tego samego dnia.
+#[derive(Clone, Debug, Default)]
+pub struct RateTable {
+    pairs: HashMap<(CurrencyCode, CurrencyCode), BTreeMap<NaiveDate, f64>>,
+}
+
+impl RateTable {
+    pub fn new(rates: &[ExchangeRate]) -> Self {
+        let mut table = RateTable::default();
+        let usable = |r: &&ExchangeRate| r.rate.is_finite() && r.rate > 0.0 && r.base != r.quote;
+        // Późniejszy wpis nadpisuje wcześniejszy: kurs odwrotny < kurs wprost, import < korekta
+        for manual in [false, true] {
+            let group: Vec<&ExchangeRate> = rates
+                .iter()
+                .filter(usable)
+                .filter(|r| (r.source == RateSource::Manual) == manual)
+                .collect();
+            for r in &group {
+                table.insert(r.quote, r.base, r.date, 1.0 / r.rate);
+            }
+            for r in &group {
+                table.insert(r.base, r.quote, r.date, r.rate);
+            }
+        }
+        table
+    }
+
+    fn insert(&mut self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate, rate: f64) {
+        self.pairs.entry((from, to)).or_default().insert(date, rate);
+    }
+
+    // Kurs `from` -> `to` obowiązujący w dniu `date`: z tego dnia albo najbliższego wcześniejszego.
+    // Bez kursu wprost liczymy kurs krzyżowy przez trzecią walutę (np. USD -> EUR przez PLN).
+    // Zwraca dzień, z którego pochodzi kurs (przy krzyżowym - starszy z dwóch), i sam kurs.
+    pub fn lookup(&self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate) -> Option<(NaiveDate, f64)> {
+        if from == to {
+            return Some((date, 1.0));
+        }
+        let on_or_before = |from: CurrencyCode, to: CurrencyCode| {
+            self.pairs
+                .get(&(from, to))
+                .and_then(|days| days.range(..=date).next_back())
+                .map(|(day, rate)| (*day, *rate))
+        };
+        if let Some(direct) = on_or_before(from, to) {
+            return Some(direct);
+        }
+        let mut via: Vec<CurrencyCode> = self
+            .pairs
+            .keys()
+            .filter(|(a, b)| *a == from && *b != to)
+            .map(|(_, b)| *b)
+            .collect();
+        via.sort();
+        via.into_iter()
+            .filter_map(|middle| {
+                let (first_day, first) = on_or_before(from, middle)?;
+                let (second_day, second) = on_or_before(middle, to)?;
+                Some((first_day.min(second_day), first * second))
+            })
+            .max_by_key(|(day, _)| *day)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+// Źródło kursów do przeliczania kwot na walutę raportu. Kwoty zapisujemy zawsze
+// w oryginalnej walucie; przeliczenie dzieje się dopiero przy wyświetlaniu i sumowaniu.
+pub trait RateProvider {
+    fn rate(&self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate) -> Option<f64>;
+
+    // Kwota w walucie `to` po kursie z dnia `date`; None, gdy kursu brak
+    fn convert(&self, amount: Money, to: CurrencyCode, date: NaiveDate) -> Option<Money> {
+        if amount.currency == to {
+            return Some(amount);
+        }
+        let rate = self.rate(amount.currency, to, date)?;
+        Some(Money::from_major(amount.to_major() * rate, to))
+    }
+}
+
+impl RateProvider for RateTable {
+    fn rate(&self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate) -> Option<f64> {
+        self.lookup(from, to, date).map(|(_, rate)| rate)
+    }
+}
+
+// Kursy na walutę raportu tylko dla dni, których potrzebuje widok (dni transakcji
+// i pierwsze dni miesięcy z limitami). Wylicza je backend z pełnej tabeli kursów.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DisplayRates {
+    pub currency: CurrencyCode,
+    pub rates: Vec<(CurrencyCode, NaiveDate, f64)>,
+}
+
+impl DisplayRates {
+    pub fn empty(currency: CurrencyCode) -> Self {
+        Self {
+            currency,
+            rates: vec![],
+        }
+    }
+}
+
+impl RateProvider for DisplayRates {
+    fn rate(&self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        if to != self.currency {
+            return None;
+        }
+        self.rates
+            .iter()
+            .find(|(currency, day, _)| *currency == from && *day == date)
+            .map(|(_, _, rate)| *rate)
+    }
+}
+
+// Transakcje z kwotami przeliczonymi na `currency`. Druga wartość to liczba transakcji
+// pominiętych z braku kursu - widok pokazuje ją jako ostrzeżenie zamiast zaniżać sumy po cichu.
+pub fn in_currency<'a>(
+    transactions: impl IntoIterator<Item = &'a Transaction>,
+    currency: CurrencyCode,
+    rates: &impl RateProvider,
+) -> (Vec<Transaction>, usize) {
+    let mut converted = vec![];
+    let mut missing = 0;
+    for tx in transactions {
+        match rates.convert(tx.amount, currency, tx.date) {
+            Some(amount) => converted.push(Transaction { amount, ..tx.clone() }),
+            None => missing += 1,
+        }
+    }
+    (converted, missing)
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct MonthlyLimitData {
-    pub general: f64,
-    pub categories: HashMap<String, f64>,
+    pub general: Money,
+    pub categories: HashMap<String, Money>,
+}
+
+impl MonthlyLimitData {
+    pub fn empty(currency: CurrencyCode) -> Self {
+        Self {
+            general: Money::zero(currency),
+            categories: HashMap::new(),
+        }
+    }
+
+    // Limity miesiąca "RRRR-MM" w walucie raportu, po kursie z pierwszego dnia miesiąca.
+    // Limit, którego nie da się przeliczyć, traktujemy jak brak limitu.
+    pub fn in_currency(&self, month: &str, currency: CurrencyCode, rates: &impl RateProvider) -> Self {
+        let Ok(day) = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d") else {
+            return MonthlyLimitData::empty(currency);
+        };
+        let convert = |amount: Money| rates.convert(amount, currency, day).unwrap_or(Money::zero(currency));
+        Self {
+            general: convert(self.general),
+            categories: self
+                .categories
+                .iter()
+                .map(|(category, amount)| (category.clone(), convert(*amount)))
+                .collect(),
+        }
+    }
+}
+
+// Ustawienia budżetu wspólne dla wszystkich miesięcy
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    // Limity nowego miesiąca, który nie ma jeszcze własnych (None = nowe miesiące są puste)
+    #[serde(default)]
+    pub template: Option<MonthlyLimitData>,
+    // Kategorie, których niewykorzystany (albo przekroczony) limit przechodzi na następny miesiąc
+    #[serde(default)]
+    pub rollover: Vec<String>,
+}
+
+impl Budget {
+    // Limity miesiąca "RRRR-MM": zapisane, a gdy ich nie ma - szablon, ale dopiero od
+    // bieżącego miesiąca `current` (wcześniejsze miesiące bez limitów zostają bez nich)
+    pub fn limits_for(&self, limits: &HashMap<String, MonthlyLimitData>, month: &str, current: &str) -> Option<MonthlyLimitData> {
+        match limits.get(month) {
+            Some(data) => Some(data.clone()),
+            None if month >= current => self.template.clone(),
+            None => None,
+        }
+    }
+
+    pub fn rolls_over(&self, category: &str) -> bool {
+        self.rollover.iter().any(|id| id == category)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CategoryUsage {
+    pub category: String,
+    // Wydatki w kategorii razem z jej podkategoriami
+    pub spent: Money,
+    // Zero oznacza brak limitu
+    pub limit: Money,
+    // Przeniesione z poprzedniego miesiąca: niewykorzystany limit albo (ujemne) przekroczenie
+    pub carried: Money,
+    // Poziom w drzewie kategorii (0 = główna)
+    pub depth: usize,
+}
+
+impl CategoryUsage {
+    // Do wydania w tym miesiącu: limit razem z kwotą przeniesioną
+    pub fn available(&self) -> Money {
+        self.limit.checked_add(self.carried).unwrap_or(self.limit)
+    }
+
+    fn has_limit(&self) -> bool {
+        (self.limit.is_positive() || self.carried.minor != 0) && self.spent.currency == self.limit.currency
+    }
+
+    // Wykorzystanie limitu w procentach (może przekroczyć 100); None, gdy limitu nie ma.
+    // Gdy przekroczenie z poprzedniego miesiąca zjadło cały limit, wykorzystanie to 100%.
+    pub fn percent(&self) -> Option<u32> {
+        if !self.has_limit() {
+            return None;
+        }
+        let available = self.available();
+        if !available.is_positive() {
+            return Some(100);
+        }
+        let percent = self.spent.minor.max(0) as i128 * 100 / available.minor as i128;
+        Some(percent.min(u32::MAX as i128) as u32)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.has_limit() && self.spent.minor > self.available().minor
+    }
+}
+
+impl MonthlyLimitData {
+    // Zestawienie kategorii z limitem albo z wydatkami w tym miesiącu. Pod uwagę brane są
+    // tylko wydatki; wydatek w podkategorii liczy się też do wszystkich jej nadrzędnych.
+    // Kolejność drzewa, a wśród rodzeństwa: najpierw przekroczone, potem wg wykorzystania i kwoty.
+    // `carried` to kwoty przeniesione z poprzedniego miesiąca (w walucie `currency`).
+    pub fn breakdown<'a>(
+        &self,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+        categories: &[Category],
+        currency: CurrencyCode,
+        carried: &HashMap<String, Money>,
+    ) -> Vec<CategoryUsage> {
+        let mut spent: HashMap<&str, Money> = HashMap::new();
+        for tx in transactions {
+            if tx.kind == TransactionKind::Expense {
+                for id in Category::ancestors(categories, &tx.category) {
+                    let total = spent.entry(id).or_insert(Money::zero(currency));
+                    *total = total.checked_add(tx.amount).unwrap_or(*total);
+                }
+            }
+        }
+
+        let mut included: Vec<&str> = self
+            .categories
+            .iter()
+            .filter(|(id, limit)| limit.is_positive() || spent.contains_key(id.as_str()))
+            .map(|(id, _)| id.as_str())
+            .chain(spent.keys().copied())
+            .chain(carried.iter().filter(|(_, amount)| amount.minor != 0).map(|(id, _)| id.as_str()))
+            .collect();
+        included.sort();
+        included.dedup();
+
+        // Rodzic w zestawieniu: najbliższy przodek, który też się w nim znalazł
+        let parent_of = |id: &str| -> Option<String> {
+            Category::ancestors(categories, id)
+                .into_iter()
+                .skip(1)
+                .find(|ancestor| included.contains(ancestor))
+                .map(str::to_string)
+        };
+        let usage = |id: &str, depth: usize| CategoryUsage {
+            category: id.to_string(),
+            spent: spent.get(id).copied().unwrap_or(Money::zero(currency)),
+            limit: self.categories.get(id).copied().unwrap_or(Money::zero(currency)),
+            carried: carried.get(id).copied().unwrap_or(Money::zero(currency)),
+            depth,
+        };
+        let order = |a: &CategoryUsage, b: &CategoryUsage| {
+            b.is_over()
+                .cmp(&a.is_over())
+                .then(b.percent().cmp(&a.percent()))
+                .then(b.spent.minor.cmp(&a.spent.minor))
+                .then(a.category.cmp(&b.category))
+        };
+
+        let mut usages = vec![];
+        let mut roots: Vec<CategoryUsage> = included
+            .iter()
+            .filter(|id| parent_of(id).is_none())
+            .map(|id| usage(id, 0))
+            .collect();
+        roots.sort_by(order);
+        let mut stack: Vec<CategoryUsage> = roots.into_iter().rev().collect();
+        while let Some(current) = stack.pop() {
+            let mut children: Vec<CategoryUsage> = included
+                .iter()
+                .filter(|id| parent_of(id).as_deref() == Some(current.category.as_str()))
+                .map(|id| usage(id, current.depth + 1))
+                .collect();
+            children.sort_by(order);
+            usages.push(current);
+            stack.extend(children.into_iter().rev());
+        }
+        // Kategorie w cyklu (bez korzenia) na końcu, jako główne
+        for id in included {
+            if !usages.iter().any(|u| u.category == id) {
+                usages.push(usage(id, 0));
+            }
+        }
+        usages
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AppState {
+    pub version: u32,
     pub transactions: Vec<Transaction>,
+    pub categories: Vec<Category>,
     pub limits: HashMap<String, MonthlyLimitData>,
+    pub rates: Vec<ExchangeRate>,
+    // Reguły kategoryzacji w kolejności pierwszeństwa
+    pub rules: Vec<CategoryRule>,
+    // Szablony transakcji cyklicznych
+    pub recurring: Vec<RecurringTemplate>,
+    pub budget: Budget,
+    pub theme: String,
+    pub language: String,
+    pub currency: String,
+}
+
+// Ustawienia interfejsu zapisywane osobno od danych (komenda update_settings).
+// `currency` to tylko waluta wyświetlania i raportów - zapisanych kwot nie zmienia.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
     pub theme: String,
     pub language: String,
     pub currency: String,
 }
 
+impl AppState {
+    pub fn settings(&self) -> Settings {
+        Settings {
+            theme: self.theme.clone(),
+            language: self.language.clone(),
+            currency: self.currency.clone(),
+        }
+    }
+
+    // Transakcje z powtórzonym id (np. odzyskane z uszkodzonego pliku albo poprawiane ręcznie)
+    // dostają nowe id; zostaje pierwsza. Zwraca liczbę zmienionych.
+    pub fn resolve_duplicate_ids(&mut self) -> usize {
+        let mut seen = HashSet::new();
+        let mut changed = 0;
+        for tx in &mut self.transactions {
+            while !seen.insert(tx.id) {
+                tx.id = TransactionId::generate();
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    // Dopisuje brakujące kategorie, do których odwołują się transakcje lub limity
+    // (np. po odzyskaniu części pliku). Zwraca liczbę dodanych.
+    pub fn ensure_categories(&mut self) -> usize {
+        let known: HashSet<String> = self.categories.iter().map(|c| c.id.clone()).collect();
+        let mut missing: Vec<&str> = self
+            .transactions
+            .iter()
+            .map(|tx| tx.category.as_str())
+            .chain(self.limits.values().flat_map(|data| data.categories.keys().map(String::as_str)))
+            .chain(self.recurring.iter().map(|template| template.category.as_str()))
+            .chain(self.budget.template.iter().flat_map(|data| data.categories.keys().map(String::as_str)))
+            .chain(self.budget.rollover.iter().map(String::as_str))
+            .filter(|id| !known.contains(*id))
+            .collect();
+        missing.sort();
+        missing.dedup();
+        let added: Vec<Category> = missing.into_iter().map(Category::placeholder).collect();
+        let count = added.len();
+        self.categories.extend(added);
+        count
+    }
+}
+
 impl Default for AppState {
     fn default() -> Self {
         Self {
+            version: SCHEMA_VERSION,
             transactions: vec![],
+            categories: Category::defaults(),
             limits: HashMap::new(),
+            rates: vec![],
+            rules: vec![],
+            recurring: vec![],
+            budget: Budget::default(),
             theme: "light".to_string(),
             language: "pl".to_string(),
             currency: "PLN".to_string(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub generation: usize,
+    pub saved_at: String,
+    pub transactions: usize,
+}
+
+// Wynik importu z pliku: ile rekordów zapisano, a ile pominięto jako nieczytelne
+// (i przy transakcjach - ile scalono z zapisanymi jako duplikaty)
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub skipped: usize,
+    #[serde(default)]
+    pub merged: usize,
+}
+
+// Decyzja użytkownika, że `duplicate` to ta sama operacja co zapisana już `kept`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateMerge {
+    pub kept: Transaction,
+    pub duplicate: Transaction,
+}
+
+// Zakres eksportu: daty włącznie z obu stron i wybrane kategorie (razem z ich
+// podkategoriami); pusta lista kategorii oznacza wszystkie
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionFilter {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub categories: Vec<String>,
+}
+
+impl TransactionFilter {
+    pub fn matches(&self, t: &Transaction, categories: &[Category]) -> bool {
+        t.date >= self.from
+            && t.date <= self.to
+            && (self.categories.is_empty()
+                || Category::ancestors(categories, &t.category)
+                    .iter()
+                    .any(|id| self.categories.iter().any(|selected| selected == id)))
+    }
+}
+
+// Kodowanie pliku z wyciągiem; polskie banki często eksportują CSV w Windows-1250
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextEncoding {
+    Utf8,
+    Windows1250,
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 2] = [TextEncoding::Utf8, TextEncoding::Windows1250];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "utf8",
+            TextEncoding::Windows1250 => "windows1250",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<TextEncoding> {
+        TextEncoding::ALL.into_iter().find(|e| e.as_str() == s)
+    }
+}
+
+// Numery kolumn pliku CSV (od 0) z poszczególnymi polami transakcji
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    pub title: Option<usize>,
+    pub amount: Option<usize>,
+    pub date: Option<usize>,
+    pub category: Option<usize>,
+}
+
+// Ustawienia odczytu CSV: zgadywane przy otwarciu pliku, poprawiane w kreatorze importu
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub encoding: TextEncoding,
+    // Wiersze przed danymi (np. nagłówek wyciągu z numerem rachunku)
+    pub skip_rows: usize,
+    pub has_header: bool,
+    pub mapping: ColumnMapping,
+    // Waluta kwot z pliku
+    pub currency: CurrencyCode,
+}
+
+// Otwarty plik CSV: wykryte ustawienia, nazwy kolumn i kilka pierwszych wierszy
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CsvFile {
+    pub options: CsvOptions,
+    pub headers: Vec<String>,
+    pub sample: Vec<Vec<String>>,
+}
+
+// Wiersz podglądu importu: odczytana transakcja albo opis błędu
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportRow {
+    // Numer wiersza w pliku (od 1), żeby łatwo go tam znaleźć
+    pub line: usize,
+    pub cells: Vec<String>,
+    pub transaction: Option<Transaction>,
+    pub error: Option<String>,
+    // Zapisana transakcja, którą ten wiersz prawdopodobnie powtarza
+    #[serde(default)]
+    pub duplicate_of: Option<Transaction>,
+}
+
+// Błąd odczytu danych przekazywany z backendu do ekranu odzyskiwania
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LoadError {
+    Corrupted {
+        quarantined: String,
+        reason: String,
+        backups: Vec<BackupInfo>,
+    },
+    BackupUnreadable {
+        generation: usize,
+    },
+    NothingToSalvage,
+    UnsupportedVersion {
+        found: u32,
+        supported: u32,
+    },
+    Io {
+        message: String,
+    },
+}
+
+// Zasady zapisu liczb w języku interfejsu
+struct Locale {
+    decimal: char,
+    group: char,
+    // Najkrótsza część całkowita, którą dzielimy na grupy tysięcy
+    // (po polsku "1234,50", ale "12 345,50")
+    min_grouping: usize,
+    // Po polsku symbol zawsze stoi za kwotą ("12,50 $"); po angielsku wg zwyczaju waluty
+    symbol_after: bool,
+}
+
+impl Locale {
+    fn of(lang: &str) -> Locale {
+        match lang {
+            "pl" => Locale { decimal: ',', group: '\u{a0}', min_grouping: 5, symbol_after: true },
+            _ => Locale { decimal: '.', group: ',', min_grouping: 4, symbol_after: false },
+        }
+    }
+}
+
+// (kod, miejsca po przecinku, symbol, symbol przed kwotą, nazwa pl, nazwa en).
+// Waluty obiegowe z ISO 4217 - bez funduszy, metali i kodów technicznych (XAU, XDR, XXX...).
+pub type CurrencyInfo = (&'static str, u32, &'static str, bool, &'static str, &'static str);
+
+pub const CURRENCIES: &[CurrencyInfo] = &[
+    ("AED", 2, "AED", false, "dirham emiracki", "UAE dirham"),
+    ("AFN", 2, "\u{60b}", false, "afgani", "Afghan afghani"),
+    ("ALL", 2, "L", false, "lek albański", "Albanian lek"),
+    ("AMD", 2, "\u{58f}", false, "dram armeński", "Armenian dram"),
+    ("ANG", 2, "ANG", false, "gulden antylski", "Netherlands Antillean guilder"),
+    ("AOA", 2, "Kz", false, "kwanza angolańska", "Angolan kwanza"),
+    ("ARS", 2, "$", true, "peso argentyńskie", "Argentine peso"),
+    ("AUD", 2, "A$", true, "dolar australijski", "Australian dollar"),
+    ("AWG", 2, "Afl.", true, "florin arubański", "Aruban florin"),
+    ("AZN", 2, "\u{20bc}", false, "manat azerbejdżański", "Azerbaijani manat"),
+    ("BAM", 2, "KM", false, "marka zamienna", "Bosnia and Herzegovina convertible mark"),
+    ("BBD", 2, "Bds$", true, "dolar barbadoski", "Barbados dollar"),
+    ("BDT", 2, "\u{9f3}", true, "taka", "Bangladeshi taka"),
+    ("BGN", 2, "лв", false, "lew bułgarski", "Bulgarian lev"),
+    ("BHD", 3, "BD", true, "dinar bahrajński", "Bahraini dinar"),
+    ("BIF", 0, "FBu", false, "frank burundyjski", "Burundian franc"),
+    ("BMD", 2, "BD$", true, "dolar bermudzki", "Bermudian dollar"),
+    ("BND", 2, "B$", true, "dolar brunejski", "Brunei dollar"),
+    ("BOB", 2, "Bs", true, "boliviano", "Bolivian boliviano"),
+    ("BRL", 2, "R$", true, "real brazylijski", "Brazilian real"),
+    ("BSD", 2, "B$", true, "dolar bahamski", "Bahamian dollar"),
+    ("BTN", 2, "Nu.", true, "ngultrum", "Bhutanese ngultrum"),
+    ("BWP", 2, "P", true, "pula", "Botswana pula"),
+    ("BYN", 2, "Br", false, "rubel białoruski", "Belarusian ruble"),
+    ("BZD", 2, "BZ$", true, "dolar belizeński", "Belize dollar"),
+    ("CAD", 2, "C$", true, "dolar kanadyjski", "Canadian dollar"),
+    ("CDF", 2, "FC", false, "frank kongijski", "Congolese franc"),
+    ("CHF", 2, "CHF", true, "frank szwajcarski", "Swiss franc"),
+    ("CLP", 0, "$", true, "peso chilijskie", "Chilean peso"),
+    ("CNY", 2, "\u{a5}", true, "juan chiński", "Chinese yuan"),
+    ("COP", 2, "$", true, "peso kolumbijskie", "Colombian peso"),
+    ("CRC", 2, "\u{20a1}", true, "colón kostarykański", "Costa Rican colón"),
+    ("CUP", 2, "$", true, "peso kubańskie", "Cuban peso"),
+    ("CVE", 2, "Esc", false, "escudo Zielonego Przylądka", "Cape Verdean escudo"),
+    ("CZK", 2, "K\u{10d}", false, "korona czeska", "Czech koruna"),
+    ("DJF", 0, "Fdj", false, "frank Dżibuti", "Djiboutian franc"),
+    ("DKK", 2, "kr", false, "korona duńska", "Danish krone"),
+    ("DOP", 2, "RD$", true, "peso dominikańskie", "Dominican peso"),
+    ("DZD", 2, "DA", false, "dinar algierski", "Algerian dinar"),
+    ("EGP", 2, "E\u{a3}", true, "funt egipski", "Egyptian pound"),
+    ("ERN", 2, "Nfk", false, "nakfa", "Eritrean nakfa"),
+    ("ETB", 2, "Br", false, "birr", "Ethiopian birr"),
+    ("EUR", 2, "\u{20ac}", true, "euro", "euro"),
+    ("FJD", 2, "FJ$", true, "dolar fidżyjski", "Fiji dollar"),
+    ("FKP", 2, "\u{a3}", true, "funt falklandzki", "Falkland Islands pound"),
+    ("GBP", 2, "\u{a3}", true, "funt szterling", "pound sterling"),
+    ("GEL", 2, "\u{20be}", false, "lari", "Georgian lari"),
+    ("GHS", 2, "GH\u{20b5}", true, "cedi", "Ghanaian cedi"),
+    ("GIP", 2, "\u{a3}", true, "funt gibraltarski", "Gibraltar pound"),
+    ("GMD", 2, "D", false, "dalasi", "Gambian dalasi"),
+    ("GNF", 0, "FG", false, "frank gwinejski", "Guinean franc"),
+    ("GTQ", 2, "Q", true, "quetzal", "Guatemalan quetzal"),
+    ("GYD", 2, "G$", true, "dolar gujański", "Guyanese dollar"),
+    ("HKD", 2, "HK$", true, "dolar hongkoński", "Hong Kong dollar"),
+    ("HNL", 2, "L", true, "lempira", "Honduran lempira"),
+    ("HTG", 2, "G", false, "gourde", "Haitian gourde"),
+    ("HUF", 2, "Ft", false, "forint", "Hungarian forint"),
+    ("IDR", 2, "Rp", true, "rupia indonezyjska", "Indonesian rupiah"),
+    ("ILS", 2, "\u{20aa}", true, "nowy szekel izraelski", "Israeli new shekel"),
+    ("INR", 2, "\u{20b9}", true, "rupia indyjska", "Indian rupee"),
+    ("IQD", 3, "IQD", false, "dinar iracki", "Iraqi dinar"),
+    ("IRR", 2, "IRR", false, "rial irański", "Iranian rial"),
+    ("ISK", 0, "kr", false, "korona islandzka", "Icelandic króna"),
+    ("JMD", 2, "J$", true, "dolar jamajski", "Jamaican dollar"),
+    ("JOD", 3, "JD", true, "dinar jordański", "Jordanian dinar"),
+    ("JPY", 0, "\u{a5}", true, "jen japoński", "Japanese yen"),
+    ("KES", 2, "KSh", true, "szyling kenijski", "Kenyan shilling"),
+    ("KGS", 2, "som", false, "som kirgiski", "Kyrgyzstani som"),
+    ("KHR", 2, "\u{17db}", false, "riel", "Cambodian riel"),
+    ("KMF", 0, "CF", false, "frank komoryjski", "Comorian franc"),
+    ("KPW", 2, "\u{20a9}", true, "won północnokoreański", "North Korean won"),
+    ("KRW", 0, "\u{20a9}", true, "won południowokoreański", "South Korean won"),
+    ("KWD", 3, "KD", true, "dinar kuwejcki", "Kuwaiti dinar"),
+    ("KYD", 2, "CI$", true, "dolar kajmański", "Cayman Islands dollar"),
+    ("KZT", 2, "\u{20b8}", false, "tenge", "Kazakhstani tenge"),
+    ("LAK", 2, "\u{20ad}", false, "kip", "Lao kip"),
+    ("LBP", 2, "LBP", false, "funt libański", "Lebanese pound"),
+    ("LKR", 2, "Rs", true, "rupia lankijska", "Sri Lankan rupee"),
+    ("LRD", 2, "L$", true, "dolar liberyjski", "Liberian dollar"),
+    ("LSL", 2, "L", false, "loti", "Lesotho loti"),
+    ("LYD", 3, "LD", false, "dinar libijski", "Libyan dinar"),
+    ("MAD", 2, "MAD", false, "dirham marokański", "Moroccan dirham"),
+    ("MDL", 2, "L", false, "lej mołdawski", "Moldovan leu"),
+    ("MGA", 2, "Ar", false, "ariary", "Malagasy ariary"),
+    ("MKD", 2, "ден", false, "denar macedoński", "Macedonian denar"),
+    ("MMK", 2, "K", false, "kyat", "Myanmar kyat"),
+    ("MNT", 2, "\u{20ae}", false, "tugrik", "Mongolian tögrög"),
+    ("MOP", 2, "MOP$", true, "pataca", "Macanese pataca"),
+    ("MRU", 2, "UM", false, "ugija", "Mauritanian ouguiya"),
+    ("MUR", 2, "Rs", true, "rupia maurytyjska", "Mauritian rupee"),
+    ("MVR", 2, "Rf", false, "rufiyaa", "Maldivian rufiyaa"),
+    ("MWK", 2, "MK", true, "kwacha malawijska", "Malawian kwacha"),
+    ("MXN", 2, "MX$", true, "peso meksykańskie", "Mexican peso"),
+    ("MYR", 2, "RM", true, "ringgit", "Malaysian ringgit"),
+    ("MZN", 2, "MT", false, "metical", "Mozambican metical"),
+    ("NAD", 2, "N$", true, "dolar namibijski", "Namibian dollar"),
+    ("NGN", 2, "\u{20a6}", true, "naira", "Nigerian naira"),
+    ("NIO", 2, "C$", true, "córdoba", "Nicaraguan córdoba"),
+    ("NOK", 2, "kr", false, "korona norweska", "Norwegian krone"),
+    ("NPR", 2, "Rs", true, "rupia nepalska", "Nepalese rupee"),
+    ("NZD", 2, "NZ$", true, "dolar nowozelandzki", "New Zealand dollar"),
+    ("OMR", 3, "OMR", false, "rial omański", "Omani rial"),
+    ("PAB", 2, "B/.", true, "balboa", "Panamanian balboa"),
+    ("PEN", 2, "S/", true, "sol", "Peruvian sol"),
+    ("PGK", 2, "K", true, "kina", "Papua New Guinean kina"),
+    ("PHP", 2, "\u{20b1}", true, "peso filipińskie", "Philippine peso"),
+    ("PKR", 2, "Rs", true, "rupia pakistańska", "Pakistani rupee"),
+    ("PLN", 2, "z\u{142}", false, "złoty polski", "Polish złoty"),
+    ("PYG", 0, "\u{20b2}", true, "guarani", "Paraguayan guaraní"),
+    ("QAR", 2, "QR", false, "rial katarski", "Qatari riyal"),
+    ("RON", 2, "lei", false, "lej rumuński", "Romanian leu"),
+    ("RSD", 2, "din.", false, "dinar serbski", "Serbian dinar"),
+    ("RUB", 2, "\u{20bd}", false, "rubel rosyjski", "Russian ruble"),
+    ("RWF", 0, "FRw", false, "frank rwandyjski", "Rwandan franc"),
+    ("SAR", 2, "SR", false, "rial saudyjski", "Saudi riyal"),
+    ("SBD", 2, "SI$", true, "dolar Wysp Salomona", "Solomon Islands dollar"),
+    ("SCR", 2, "SR", true, "rupia seszelska", "Seychelles rupee"),
+    ("SDG", 2, "SDG", false, "funt sudański", "Sudanese pound"),
+    ("SEK", 2, "kr", false, "korona szwedzka", "Swedish krona"),
+    ("SGD", 2, "S$", true, "dolar singapurski", "Singapore dollar"),
+    ("SHP", 2, "\u{a3}", true, "funt Świętej Heleny", "Saint Helena pound"),
+    ("SLE", 2, "Le", false, "leone", "Sierra Leonean leone"),
+    ("SOS", 2, "Sh", false, "szyling somalijski", "Somali shilling"),
+    ("SRD", 2, "$", true, "dolar surinamski", "Surinamese dollar"),
+    ("SSP", 2, "SSP", false, "funt południowosudański", "South Sudanese pound"),
+    ("STN", 2, "Db", false, "dobra", "São Tomé and Príncipe dobra"),
+    ("SYP", 2, "SYP", false, "funt syryjski", "Syrian pound"),
+    ("SZL", 2, "E", false, "lilangeni", "Swazi lilangeni"),
+    ("THB", 2, "\u{e3f}", true, "bat tajlandzki", "Thai baht"),
+    ("TJS", 2, "SM", false, "somoni", "Tajikistani somoni"),
+    ("TMT", 2, "m", false, "manat turkmeński", "Turkmenistan manat"),
+    ("TND", 3, "DT", false, "dinar tunezyjski", "Tunisian dinar"),
+    ("TOP", 2, "T$", true, "pa'anga", "Tongan paʻanga"),
+    ("TRY", 2, "\u{20ba}", true, "lira turecka", "Turkish lira"),
+    ("TTD", 2, "TT$", true, "dolar Trynidadu i Tobago", "Trinidad and Tobago dollar"),
+    ("TWD", 2, "NT$", true, "nowy dolar tajwański", "New Taiwan dollar"),
+    ("TZS", 2, "TSh", false, "szyling tanzański", "Tanzanian shilling"),
+    ("UAH", 2, "\u{20b4}", false, "hrywna", "Ukrainian hryvnia"),
+    ("UGX", 0, "USh", false, "szyling ugandyjski", "Ugandan shilling"),
+    ("USD", 2, "$", true, "dolar amerykański", "US dollar"),
+    ("UYU", 2, "$U", true, "peso urugwajskie", "Uruguayan peso"),
+    ("UZS", 2, "so\u{2bb}m", false, "sum uzbecki", "Uzbekistani som"),
+    ("VES", 2, "Bs.", true, "boliwar", "Venezuelan bolívar"),
+    ("VND", 0, "\u{20ab}", false, "dong", "Vietnamese đồng"),
+    ("VUV", 0, "VT", false, "vatu", "Vanuatu vatu"),
+    ("WST", 2, "WS$", true, "tala", "Samoan tālā"),
+    ("XAF", 0, "FCFA", false, "frank CFA (Afryka Środkowa)", "Central African CFA franc"),
+    ("XCD", 2, "EC$", true, "dolar wschodniokaraibski", "East Caribbean dollar"),
+    ("XOF", 0, "CFA", false, "frank CFA (Afryka Zachodnia)", "West African CFA franc"),
+    ("XPF", 0, "CFP", false, "frank CFP", "CFP franc"),
+    ("YER", 2, "YER", false, "rial jemeński", "Yemeni rial"),
+    ("ZAR", 2, "R", true, "rand", "South African rand"),
+    ("ZMW", 2, "ZK", true, "kwacha zambijska", "Zambian kwacha"),
+    ("ZWG", 2, "ZiG", false, "złoto Zimbabwe", "Zimbabwe Gold"),
+];
@@ -0,0 +1,183 @@
+use chrono::{Local, NaiveDate};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::api::{call, pick_file};
+use crate::models::*;
+use crate::translate::t;
+use crate::utils::*;
+
+#[derive(serde::Serialize)]
+struct DateArgs {
+    date: NaiveDate,
+}
+
+#[derive(serde::Serialize)]
+struct RateArgs {
+    rate: ExchangeRate,
+}
+
+#[derive(serde::Serialize)]
+struct PathArgs {
+    path: String,
+}
+
+// Kurs do wyświetlenia i pola formularza: do 6 miejsc po przecinku, bez zbędnych zer
+fn format_rate(rate: f64) -> String {
+    let text = format!("{:.6}", rate);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+// Zakładka kursów walut: import tabel EBC/NBP, podgląd kursów obowiązujących
+// w wybranym dniu i ręczne korekty (zapisywane na ten dzień)
+#[component]
+pub fn RateManager(
+    language: ReadSignal<String>,
+    is_dark: Signal<bool>,
+    on_changed: Callback<()>,
+) -> impl IntoView {
+    let (day, set_day) = signal(Local::now().date_naive());
+    let (rates, set_rates) = signal::<Vec<ExchangeRate>>(vec![]);
+    let (message, set_message) = signal::<Option<String>>(None);
+    let (revision, set_revision) = signal(0u32);
+    // Nowe kursy zmieniają też przeliczenia w App
+    let reload = move || {
+        set_revision.update(|r| *r += 1);
+        on_changed.run(());
+    };
+
+    // Nowa korekta
+    let (new_base, set_new_base) = signal("USD".to_string());
+    let (new_quote, set_new_quote) = signal("PLN".to_string());
+    let (new_rate, set_new_rate) = signal(String::new());
+
+    Effect::new(move |_| {
+        let date = day.get();
+        revision.track();
+        spawn_local(async move {
+            if let Ok(list) = call::<_, Vec<ExchangeRate>>("query_rates", &DateArgs { date }).await {
+                set_rates.set(list);
+            }
+        });
+    });
+
+    let import = move |_| {
+        spawn_local(async move {
+            let Some(path) = pick_file("XML / CSV", &["xml", "csv"]).await else {
+                return;
+            };
+            let lang = language.get_untracked();
+            match call::<_, ImportReport>("import_rates", &PathArgs { path }).await {
+                Ok(report) => {
+                    set_message.set(Some(format!(
+                        "{}: {}, {}: {}",
+                        t("imported", &lang),
+                        report.imported,
+                        t("skipped", &lang),
+                        report.skipped
+                    )));
+                    reload();
+                }
+                Err(e) => set_message.set(Some(e)),
+            }
+        });
+    };
+
+    let save = move |rate: ExchangeRate| {
+        spawn_local(async move {
+            match call::<_, ()>("set_rate", &RateArgs { rate }).await {
+                Ok(()) => {
+                    set_message.set(None);
+                    reload();
+                }
+                Err(e) => set_message.set(Some(e)),
+            }
+        });
+    };
+
+    let remove = move |rate: ExchangeRate| {
+        spawn_local(async move {
+            match call::<_, ()>("delete_rate", &RateArgs { rate }).await {
+                Ok(()) => reload(),
+                Err(e) => set_message.set(Some(e)),
+            }
+        });
+    };
+
+    let add_rate = move |_| {
+        let base = CurrencyCode::new(new_base.get().trim());
+        let quote = CurrencyCode::new(new_quote.get().trim());
+        let rate = new_rate.get().trim().replace(',', ".").parse::<f64>().ok();
+        if let (Some(base), Some(quote), Some(rate)) = (base, quote, rate) {
+            save(ExchangeRate { date: day.get(), base, quote, rate, source: RateSource::Manual });
+            set_new_rate.set(String::new());
+        }
+    };
+
+    view! {
+        <div class={move || get_box_style(is_dark.get())}>
+            <div class="flex justify-between items-center mb-6 gap-4">
+                <h2 class="text-xl font-bold">{move || t("exchange_rates", &language.get())}</h2>
+                <button class="bg-emerald-600 text-white font-bold py-2 px-4 rounded-lg hover:bg-emerald-700 transition" on:click=import>
+                    {move || t("import_rates", &language.get())}
+                </button>
+            </div>
+
+            {move || message.get().map(|msg| view! {
+                <div class="mb-6 p-3 bg-slate-100 dark:bg-slate-700 rounded-lg text-center font-medium">{msg}</div>
+            })}
+
+            <div class="flex justify-end mb-4 items-center gap-2">
+                <label class="text-sm font-bold opacity-70">{move || t("rates_on_day", &language.get())}</label>
+                <input type="date" class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| if let Ok(date) = NaiveDate::parse_from_str(&event_target_value(&ev), "%Y-%m-%d") { set_day.set(date) }
+                    prop:value=move || day.get().format("%Y-%m-%d").to_string() />
+            </div>
+
+            // Korekta ręczna na wybrany dzień
+            <div class="grid grid-cols-1 md:grid-cols-4 gap-3 mb-8">
+                <input type="text" maxlength="3" class={move || get_input_style(is_dark.get())} on:input=move |ev| set_new_base.set(event_target_value(&ev).to_uppercase()) prop:value=new_base />
+                <input type="text" maxlength="3" class={move || get_input_style(is_dark.get())} on:input=move |ev| set_new_quote.set(event_target_value(&ev).to_uppercase()) prop:value=new_quote />
+                <input type="text" inputmode="decimal" placeholder={move || t("rate", &language.get())} class={move || get_input_style(is_dark.get())} on:input=move |ev| set_new_rate.set(event_target_value(&ev)) prop:value=new_rate />
+                <button class="bg-slate-500 text-white font-bold py-2 px-4 rounded-lg hover:bg-slate-600 transition" on:click=add_rate>
+                    {move || t("add_rate", &language.get())}
+                </button>
+            </div>
+
+            {move || {
+                let list = rates.get();
+                if list.is_empty() {
+                    return view! { <p class="text-sm opacity-40">{move || t("no_rates", &language.get())}</p> }.into_any();
+                }
+                view! {
+                    <div class="flex flex-col gap-2">
+                        {list.into_iter().map(|rate| {
+                            let manual = rate.source == RateSource::Manual;
+                            let edited = rate.clone();
+                            let removed = rate.clone();
+                            view! {
+                                <div class="grid grid-cols-2 md:grid-cols-5 gap-2 items-center">
+                                    <span class="font-bold">{format!("1 {} = {} {}", rate.base.as_str(), format_rate(rate.rate), rate.quote.as_str())}</span>
+                                    <span class="text-sm opacity-60">{move || format_date_display(rate.date, &language.get())}</span>
+                                    <span class="text-sm opacity-60">{move || t(&format!("source_{}", rate.source.as_str()), &language.get())}</span>
+                                    <input type="text" inputmode="decimal" class={move || get_input_style(is_dark.get())} prop:value=format_rate(rate.rate)
+                                        on:change=move |ev| {
+                                            if let Ok(value) = event_target_value(&ev).trim().replace(',', ".").parse::<f64>() {
+                                                save(ExchangeRate { date: day.get_untracked(), rate: value, source: RateSource::Manual, ..edited.clone() });
+                                            }
+                                        } />
+                                    <Show when=move || manual>
+                                        <button class="bg-red-500/10 text-red-500 hover:bg-red-500 hover:text-white font-bold py-2 px-3 rounded-lg transition text-sm"
+                                            on:click={let removed = removed.clone(); move |_| remove(removed.clone())}>
+                                            {move || t("remove_override", &language.get())}
+                                        </button>
+                                    </Show>
+                                </div>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+                }.into_any()
+            }}
+        </div>
+    }
+}
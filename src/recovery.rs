@@ -0,0 +1,107 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::prelude::*;
+
+use crate::api::try_invoke;
+use crate::models::*;
+use crate::translate::t;
+
+// Wywołanie jednej z komend odzyskiwania; błąd wraca jako LoadError do wyświetlenia
+async fn run_recovery(cmd: &str, args: JsValue) -> Result<Settings, LoadError> {
+    match try_invoke(cmd, args).await {
+        Ok(value) => serde_wasm_bindgen::from_value(value).map_err(|e| LoadError::Io {
+            message: e.to_string(),
+        }),
+        Err(err) => Err(
+            serde_wasm_bindgen::from_value(err.clone()).unwrap_or(LoadError::Io {
+                message: err.as_string().unwrap_or_default(),
+            }),
+        ),
+    }
+}
+
+fn describe_error(error: &LoadError, lang: &str) -> String {
+    match error {
+        LoadError::Corrupted { reason, .. } => format!("{}: {}", t("data_corrupted", lang), reason),
+        LoadError::BackupUnreadable { generation } => format!("{} #{}", t("backup_unreadable", lang), generation),
+        LoadError::NothingToSalvage => t("nothing_to_salvage", lang),
+        LoadError::UnsupportedVersion { found, supported } => {
+            format!("{} ({} > {})", t("unsupported_version", lang), found, supported)
+        }
+        LoadError::Io { message } => message.clone(),
+    }
+}
+
+#[component]
+pub fn RecoveryScreen(
+    error: LoadError,
+    language: ReadSignal<String>,
+    on_recovered: Callback<Settings>,
+) -> impl IntoView {
+    let (action_error, set_action_error) = signal::<Option<LoadError>>(None);
+
+    let (quarantined, backups) = match &error {
+        LoadError::Corrupted { quarantined, backups, .. } => (quarantined.clone(), backups.clone()),
+        _ => (String::new(), vec![]),
+    };
+
+    let run = move |cmd: &'static str, args: JsValue| {
+        spawn_local(async move {
+            match run_recovery(cmd, args).await {
+                Ok(settings) => on_recovered.run(settings),
+                Err(e) => set_action_error.set(Some(e)),
+            }
+        });
+    };
+
+    let restore = move |generation: usize| {
+        #[derive(serde::Serialize)]
+        struct RestoreArgs {
+            generation: usize,
+        }
+        run("restore_backup", serde_wasm_bindgen::to_value(&RestoreArgs { generation }).unwrap());
+    };
+
+    let error_for_view = error.clone();
+
+    view! {
+        <div class="min-h-screen p-4 md:p-8 font-sans bg-slate-50 text-slate-800 pt-[env(safe-area-inset-top)]">
+            <div class="max-w-xl mx-auto rounded-3xl shadow-xl p-6 md:p-10 bg-white border border-slate-100">
+                <h1 class="text-2xl font-bold text-red-500 mb-4">{move || t("recovery_title", &language.get())}</h1>
+                <p class="mb-2">{move || describe_error(&error_for_view, &language.get())}</p>
+                <Show when={let q = quarantined.clone(); move || !q.is_empty()}>
+                    <p class="text-xs opacity-60 mb-6 break-all">{let q = quarantined.clone(); move || format!("{}: {}", t("quarantined_as", &language.get()), q)}</p>
+                </Show>
+
+                <h2 class="text-sm font-bold uppercase opacity-60 mb-2">{move || t("restore_backup", &language.get())}</h2>
+                <Show when={let empty = backups.is_empty(); move || empty}>
+                    <p class="text-sm opacity-60 mb-4">{move || t("no_backups", &language.get())}</p>
+                </Show>
+                <div class="flex flex-col gap-2 mb-6">
+                    {backups.iter().map(|backup| {
+                        let generation = backup.generation;
+                        let label = format!("{} • {}", backup.saved_at, backup.transactions);
+                        view! {
+                            <button class="w-full text-left border border-slate-300 rounded-lg px-4 py-3 hover:bg-emerald-50 transition" on:click=move |_| restore(generation)>
+                                {let label = label.clone(); move || format!("{} ({})", label, t("transactions_count", &language.get()).to_lowercase())}
+                            </button>
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+
+                <div class="flex flex-col gap-3">
+                    <button class="w-full bg-emerald-600 text-white font-bold py-3 rounded-lg hover:bg-emerald-700 transition" on:click=move |_| run("salvage_data", JsValue::NULL)>
+                        {move || t("salvage_data", &language.get())}
+                    </button>
+                    <button class="w-full bg-red-500/10 text-red-500 hover:bg-red-500 hover:text-white font-bold py-3 rounded-lg transition" on:click=move |_| run("start_fresh", JsValue::NULL)>
+                        {move || t("start_fresh", &language.get())}
+                    </button>
+                </div>
+
+                {move || action_error.get().map(|e| view! {
+                    <div class="mt-6 p-3 bg-red-100 border border-red-400 text-red-800 rounded-lg text-center font-medium">{describe_error(&e, &language.get())}</div>
+                })}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,492 @@
+use chrono::{Duration, Local, NaiveDate, Weekday};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::api::call;
+use crate::models::*;
+use crate::translate::t;
+use crate::utils::*;
+
+#[derive(serde::Serialize)]
+struct NoArgs {}
+
+#[derive(serde::Serialize)]
+struct TemplateArgs {
+    template: RecurringTemplate,
+}
+
+#[derive(serde::Serialize)]
+struct IdArgs {
+    id: String,
+}
+
+#[derive(serde::Serialize)]
+struct UpcomingArgs {
+    until: NaiveDate,
+}
+
+#[derive(serde::Serialize)]
+struct ChangeArgs {
+    template: String,
+    change: OccurrenceChange,
+}
+
+// Na ile dni naprzód pokazujemy nadchodzące wystąpienia
+const UPCOMING_DAYS: i64 = 60;
+
+const WEEKDAYS: [Weekday; 7] = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun];
+
+// Wybór "który dzień tygodnia miesiąca": pusty = dzień miesiąca z daty startu
+const NTH_CHOICES: [i8; 6] = [1, 2, 3, 4, 5, -1];
+
+fn nth_label(nth: i8, lang: &str) -> String {
+    if nth < 0 {
+        t("nth_last", lang)
+    } else {
+        format!("{}.", nth)
+    }
+}
+
+// Harmonogram jednym ciągiem, np. "Co miesiąc · 2. Środa · do 31.12.2024"
+fn schedule_summary(schedule: &Schedule, lang: &str) -> String {
+    let mut parts = vec![t(&format!("frequency_{}", schedule.frequency.as_str()), lang)];
+    if schedule.interval > 1 {
+        parts[0] = format!("{} ×{}", parts[0], schedule.interval);
+    }
+    if let Some(nth) = schedule.nth_weekday {
+        parts.push(format!("{} {}", nth_label(nth.nth, lang), t(&format!("weekday_{}", nth.weekday), lang)));
+    }
+    parts.push(format!("{} {}", t("recurring_from", lang), format_date_display(schedule.start, lang)));
+    match schedule.end {
+        RecurrenceEnd::Never => {}
+        RecurrenceEnd::Until { date } => parts.push(format!("{} {}", t("recurring_until", lang), format_date_display(date, lang))),
+        RecurrenceEnd::Count { count } => parts.push(format!("{}: {}", t("recurring_count", lang), count)),
+    }
+    parts.join(" · ")
+}
+
+// Zakładka transakcji cyklicznych: szablony (czynsz, rachunki, subskrypcje) i ich
+// nadchodzące wystąpienia, które można pominąć albo zmienić pojedynczo. Wystąpienia,
+// które nadeszły, backend zamienia w transakcje przy starcie i po zapisie szablonu.
+#[component]
+pub fn RecurringManager(
+    categories: ReadSignal<Vec<Category>>,
+    language: ReadSignal<String>,
+    is_dark: Signal<bool>,
+    currency: Signal<CurrencyCode>,
+    on_changed: Callback<()>,
+) -> impl IntoView {
+    let templates = RwSignal::new(Vec::<RecurringTemplate>::new());
+    let upcoming = RwSignal::new(Vec::<Occurrence>::new());
+    let subscriptions = RwSignal::new(Vec::<Subscription>::new());
+    let (error, set_error) = signal::<Option<String>>(None);
+
+    // Formularz szablonu; `editing` to edytowany szablon (None = nowy)
+    let editing = RwSignal::new(None::<RecurringTemplate>);
+    let title = RwSignal::new(String::new());
+    let amount = RwSignal::new(String::new());
+    let amount_currency = RwSignal::new(currency.get_untracked());
+    let kind = RwSignal::new(TransactionKind::Expense);
+    let category = RwSignal::new(String::new());
+    let tags = RwSignal::new(String::new());
+    let start = RwSignal::new(Local::now().format("%Y-%m-%d").to_string());
+    let frequency = RwSignal::new(Frequency::Monthly);
+    let interval = RwSignal::new("1".to_string());
+    let nth = RwSignal::new(None::<i8>);
+    let weekday = RwSignal::new(Weekday::Mon);
+    let end_type = RwSignal::new("never".to_string());
+    let end_date = RwSignal::new(String::new());
+    let end_count = RwSignal::new("12".to_string());
+
+    let reload = move || {
+        spawn_local(async move {
+            if let Ok(list) = call::<_, Vec<RecurringTemplate>>("load_recurring", &NoArgs {}).await {
+                templates.set(list);
+            }
+            let until = Local::now().date_naive() + Duration::days(UPCOMING_DAYS);
+            if let Ok(list) = call::<_, Vec<Occurrence>>("upcoming_recurring", &UpcomingArgs { until }).await {
+                upcoming.set(list);
+            }
+            if let Ok(list) = call::<_, Vec<Subscription>>("detect_subscriptions", &NoArgs {}).await {
+                subscriptions.set(list);
+            }
+        });
+    };
+    reload();
+
+    // Kategoria musi pasować do rodzaju (po zmianie rodzaju wybieramy pierwszą aktywną)
+    Effect::new(move |_| {
+        let all = categories.get();
+        let selected_kind = kind.get();
+        if !all.iter().any(|c| c.id == category.get_untracked() && c.kind == selected_kind) {
+            if let Some((first, _)) = Category::tree(&all).into_iter().find(|(c, _)| c.kind == selected_kind && !c.archived) {
+                category.set(first.id.clone());
+            }
+        }
+    });
+
+    let reset_form = move || {
+        editing.set(None);
+        title.set(String::new());
+        amount.set(String::new());
+        tags.set(String::new());
+        interval.set("1".to_string());
+        nth.set(None);
+        end_type.set("never".to_string());
+    };
+
+    let start_edit = move |template: RecurringTemplate| {
+        let schedule = &template.schedule;
+        title.set(template.title.clone());
+        amount.set(template.amount.to_input_string());
+        amount_currency.set(template.amount.currency);
+        kind.set(template.kind);
+        category.set(template.category.clone());
+        tags.set(template.tags.join(", "));
+        start.set(schedule.start.format("%Y-%m-%d").to_string());
+        frequency.set(schedule.frequency);
+        interval.set(schedule.interval.to_string());
+        nth.set(schedule.nth_weekday.map(|n| n.nth));
+        if let Some(n) = schedule.nth_weekday {
+            weekday.set(n.weekday);
+        }
+        match schedule.end {
+            RecurrenceEnd::Never => end_type.set("never".to_string()),
+            RecurrenceEnd::Until { date } => {
+                end_type.set("until".to_string());
+                end_date.set(date.format("%Y-%m-%d").to_string());
+            }
+            RecurrenceEnd::Count { count } => {
+                end_type.set("count".to_string());
+                end_count.set(count.to_string());
+            }
+        }
+        editing.set(Some(template));
+    };
+
+    // Szablon z formularza; edycja zachowuje id, zmiany wystąpień i to, co już utworzono
+    let draft = move || -> Result<RecurringTemplate, String> {
+        let lang = language.get_untracked();
+        let invalid = |key: &str| t(key, &lang);
+        let parsed_amount = Money::parse(&amount.get_untracked(), amount_currency.get_untracked())
+            .filter(|m| m.is_positive())
+            .ok_or_else(|| invalid("recurring_invalid_amount"))?;
+        let parse_date = |text: String| NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(|_| invalid("recurring_invalid_date"));
+        let end = match end_type.get_untracked().as_str() {
+            "until" => RecurrenceEnd::Until { date: parse_date(end_date.get_untracked())? },
+            "count" => RecurrenceEnd::Count {
+                count: end_count.get_untracked().trim().parse().map_err(|_| invalid("recurring_invalid_count"))?,
+            },
+            _ => RecurrenceEnd::Never,
+        };
+        let schedule = Schedule {
+            start: parse_date(start.get_untracked())?,
+            frequency: frequency.get_untracked(),
+            interval: interval.get_untracked().trim().parse().map_err(|_| invalid("recurring_invalid_count"))?,
+            nth_weekday: nth
+                .get_untracked()
+                .filter(|_| matches!(frequency.get_untracked(), Frequency::Monthly | Frequency::Yearly))
+                .map(|n| NthWeekday { nth: n, weekday: weekday.get_untracked() }),
+            end,
+        };
+        let base = editing.get_untracked().unwrap_or_else(|| {
+            RecurringTemplate::new("", parsed_amount, kind.get_untracked(), "", schedule.clone())
+        });
+        Ok(RecurringTemplate {
+            title: title.get_untracked().trim().to_string(),
+            amount: parsed_amount,
+            kind: kind.get_untracked(),
+            category: category.get_untracked(),
+            tags: parse_tags(&tags.get_untracked()),
+            schedule,
+            ..base
+        })
+    };
+
+    // Zapis szablonu z formularza albo z wykrytej subskrypcji (wtedy formularz zostaje)
+    let store = move |template: RecurringTemplate, from_form: bool| {
+        spawn_local(async move {
+            match call::<_, usize>("save_recurring", &TemplateArgs { template }).await {
+                Ok(created) => {
+                    set_error.set(None);
+                    if from_form {
+                        reset_form();
+                    }
+                    reload();
+                    if created > 0 {
+                        on_changed.run(());
+                    }
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let save = move |_| match draft() {
+        Ok(template) => store(template, true),
+        Err(e) => set_error.set(Some(e)),
+    };
+
+    let remove = move |id: String| {
+        spawn_local(async move {
+            match call::<_, ()>("delete_recurring", &IdArgs { id }).await {
+                Ok(()) => {
+                    set_error.set(None);
+                    reload();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let change_occurrence = move |template: String, change: OccurrenceChange| {
+        spawn_local(async move {
+            match call::<_, ()>("change_occurrence", &ChangeArgs { template, change }).await {
+                Ok(()) => {
+                    set_error.set(None);
+                    reload();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let category_name = move |id: &str| categories.with(|all| category_path(all, id, &language.get()));
+
+    view! {
+        <div class={move || get_box_style(is_dark.get())}>
+            <h2 class="text-xl font-bold mb-2">{move || t("recurring", &language.get())}</h2>
+            <p class="text-sm opacity-60 mb-6">{move || t("recurring_hint", &language.get())}</p>
+
+            // Szablon
+            <div class="grid grid-cols-1 md:grid-cols-4 gap-3 mb-3">
+                <input type="text" placeholder={move || t("name_placeholder", &language.get())} class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| title.set(event_target_value(&ev)) prop:value=title />
+                <input type="text" inputmode="decimal" placeholder={move || t("amount_placeholder", &language.get())} class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| amount.set(event_target_value(&ev)) prop:value=amount />
+                <select class={move || get_input_style(is_dark.get())}
+                    on:change=move |ev| { if let Some(c) = CurrencyCode::new(&event_target_value(&ev)) { amount_currency.set(c) } }>
+                    {move || {
+                        let lang = language.get();
+                        let selected = amount_currency.get();
+                        CURRENCIES.iter().map(|info| view! {
+                            <option class="text-slate-800" value=info.0 selected=selected.as_str() == info.0>{currency_label(info, &lang)}</option>
+                        }).collect::<Vec<_>>()
+                    }}
+                </select>
+                <select class={move || get_input_style(is_dark.get())}
+                    on:change=move |ev| { if let Some(k) = TransactionKind::parse(&event_target_value(&ev)) { kind.set(k) } }>
+                    {TransactionKind::ALL.into_iter().map(|k| view! {
+                        <option class="text-slate-800" value=k.as_str() selected=move || kind.get() == k>{move || t(&format!("kind_{}", k.as_str()), &language.get())}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+                <select class={move || get_input_style(is_dark.get())} on:change=move |ev| category.set(event_target_value(&ev))>
+                    {move || {
+                        let all = categories.get();
+                        let selected = category.get();
+                        let selected_kind = kind.get();
+                        Category::tree(&all)
+                            .into_iter()
+                            .filter(|(c, _)| c.kind == selected_kind && (!c.archived || c.id == selected))
+                            .map(|(c, _)| {
+                                let value = c.id.clone();
+                                let is_selected = c.id == selected;
+                                view! { <option class="text-slate-800" value=value selected=is_selected>{format!("{} {}", c.icon, category_path(&all, &c.id, &language.get()))}</option> }
+                            })
+                            .collect::<Vec<_>>()
+                    }}
+                </select>
+                <input type="text" placeholder={move || t("tags_placeholder", &language.get())} class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| tags.set(event_target_value(&ev)) prop:value=tags />
+                <label class="text-sm font-bold opacity-70">{move || t("recurring_start", &language.get())}
+                    <input type="date" class={move || get_input_style(is_dark.get())} on:input=move |ev| start.set(event_target_value(&ev)) prop:value=start />
+                </label>
+                <div class="grid grid-cols-2 gap-2">
+                    <label class="text-sm font-bold opacity-70">{move || t("recurring_frequency", &language.get())}
+                        <select class={move || get_input_style(is_dark.get())}
+                            on:change=move |ev| { if let Some(f) = Frequency::parse(&event_target_value(&ev)) { frequency.set(f) } }>
+                            {Frequency::ALL.into_iter().map(|f| view! {
+                                <option class="text-slate-800" value=f.as_str() selected=move || frequency.get() == f>{move || t(&format!("frequency_{}", f.as_str()), &language.get())}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                    </label>
+                    <label class="text-sm font-bold opacity-70">{move || t("recurring_interval", &language.get())}
+                        <input type="number" min="1" class={move || get_input_style(is_dark.get())} on:input=move |ev| interval.set(event_target_value(&ev)) prop:value=interval />
+                    </label>
+                </div>
+                <Show when=move || matches!(frequency.get(), Frequency::Monthly | Frequency::Yearly)>
+                    <div class="grid grid-cols-2 gap-2 md:col-span-2">
+                        <select class={move || get_input_style(is_dark.get())} on:change=move |ev| nth.set(event_target_value(&ev).parse().ok())>
+                            <option class="text-slate-800" value="" selected=move || nth.get().is_none()>{move || t("recurring_same_day", &language.get())}</option>
+                            {NTH_CHOICES.into_iter().map(|n| view! {
+                                <option class="text-slate-800" value=n.to_string() selected=move || nth.get() == Some(n)>{move || nth_label(n, &language.get())}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <select class={move || get_input_style(is_dark.get())} disabled=move || nth.get().is_none()
+                            on:change=move |ev| { if let Ok(w) = event_target_value(&ev).parse::<Weekday>() { weekday.set(w) } }>
+                            {WEEKDAYS.into_iter().map(|w| view! {
+                                <option class="text-slate-800" value=w.to_string() selected=move || weekday.get() == w>{move || t(&format!("weekday_{}", w), &language.get())}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                    </div>
+                </Show>
+                <div class="grid grid-cols-2 gap-2 md:col-span-2">
+                    <select class={move || get_input_style(is_dark.get())} on:change=move |ev| end_type.set(event_target_value(&ev))>
+                        {["never", "until", "count"].into_iter().map(|end| view! {
+                            <option class="text-slate-800" value=end selected=move || end_type.get() == end>{move || t(&format!("recurring_end_{}", end), &language.get())}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                    {move || match end_type.get().as_str() {
+                        "until" => view! { <input type="date" class={move || get_input_style(is_dark.get())} on:input=move |ev| end_date.set(event_target_value(&ev)) prop:value=end_date /> }.into_any(),
+                        "count" => view! { <input type="number" min="1" class={move || get_input_style(is_dark.get())} on:input=move |ev| end_count.set(event_target_value(&ev)) prop:value=end_count /> }.into_any(),
+                        _ => view! { <span></span> }.into_any(),
+                    }}
+                </div>
+            </div>
+            <div class="flex gap-3 mb-6">
+                <button class="bg-emerald-600 text-white font-bold py-2 px-4 rounded-lg hover:bg-emerald-700 transition" on:click=save>
+                    {move || if editing.get().is_some() { t("save_changes", &language.get()) } else { t("add_recurring", &language.get()) }}
+                </button>
+                <Show when=move || editing.get().is_some()>
+                    <button class="bg-slate-500 text-white font-bold py-2 px-4 rounded-lg hover:bg-slate-600 transition" on:click=move |_| reset_form()>
+                        {move || t("cancel_edit", &language.get())}
+                    </button>
+                </Show>
+            </div>
+
+            {move || error.get().map(|e| view! {
+                <div class="mb-6 p-3 bg-red-100 border border-red-400 text-red-800 rounded-lg text-center font-medium">{e}</div>
+            })}
+
+            // Szablony
+            <div class="flex flex-col gap-2 mb-8">
+                {move || templates.get().into_iter().map(|template| {
+                    let lang = language.get();
+                    let summary = schedule_summary(&template.schedule, &lang);
+                    let heading = format!("{} · {} · {}", template.title, format_currency(template.amount, &lang), category_name(&template.category));
+                    let id = template.id.clone();
+                    view! {
+                        <div class="flex flex-col md:flex-row md:items-center gap-2 justify-between">
+                            <div class="min-w-0">
+                                <p class="font-bold truncate">{heading}</p>
+                                <p class="text-sm opacity-60 truncate">{summary}</p>
+                            </div>
+                            <div class="flex gap-2">
+                                <button class="bg-slate-500 text-white font-bold py-2 px-3 rounded-lg hover:bg-slate-600 transition text-sm"
+                                    on:click=move |_| start_edit(template.clone())>
+                                    {move || t("edit", &language.get())}
+                                </button>
+                                <button class="bg-red-500/10 text-red-500 hover:bg-red-500 hover:text-white font-bold py-2 px-3 rounded-lg transition text-sm"
+                                    on:click=move |_| remove(id.clone())>
+                                    {move || t("delete", &language.get())}
+                                </button>
+                            </div>
+                        </div>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+
+            // Subskrypcje wykryte w historii, do dodania jako szablony
+            <h3 class="text-sm font-bold uppercase opacity-60 mb-1">{move || t("subscriptions", &language.get())}</h3>
+            <p class="text-sm opacity-60 mb-2">{move || t("subscriptions_hint", &language.get())}</p>
+            <div class="flex flex-col gap-2 mb-8">
+                {move || {
+                    let lang = language.get();
+                    let list = subscriptions.get();
+                    if list.is_empty() {
+                        return vec![view! { <p class="text-sm opacity-60">{t("no_subscriptions", &lang)}</p> }.into_any()];
+                    }
+                    list.into_iter().map(|subscription| {
+                        let heading = format!(
+                            "{} · {} · {}: {}",
+                            subscription.payee,
+                            format_currency(subscription.template.amount, &lang),
+                            t("yearly_cost", &lang),
+                            format_currency(subscription.yearly_cost, &lang)
+                        );
+                        let details = format!(
+                            "{} · {} {} {}",
+                            schedule_summary(&subscription.template.schedule, &lang),
+                            subscription.payments,
+                            t("subscription_payments", &lang),
+                            format_date_display(subscription.first_date, &lang)
+                        );
+                        let increases: Vec<String> = subscription.price_increases.iter().map(|change| format!(
+                            "{} {}: {} → {}",
+                            t("price_increase", &lang),
+                            format_date_display(change.date, &lang),
+                            format_currency(change.from, &lang),
+                            format_currency(change.to, &lang)
+                        )).collect();
+                        let template = subscription.template.clone();
+                        view! {
+                            <div class="flex flex-col md:flex-row md:items-center gap-2 justify-between">
+                                <div class="min-w-0">
+                                    <p class="font-bold truncate">{heading}</p>
+                                    <p class="text-sm opacity-60 truncate">{details}</p>
+                                    {increases.into_iter().map(|text| view! { <p class="text-sm font-bold text-amber-500">{text}</p> }).collect::<Vec<_>>()}
+                                </div>
+                                <button class="bg-emerald-600 text-white font-bold py-2 px-3 rounded-lg hover:bg-emerald-700 transition text-sm"
+                                    on:click=move |_| store(template.clone(), false)>
+                                    {t("add_subscription", &lang)}
+                                </button>
+                            </div>
+                        }.into_any()
+                    }).collect::<Vec<_>>()
+                }}
+            </div>
+
+            // Nadchodzące wystąpienia
+            <h3 class="text-sm font-bold uppercase opacity-60 mb-2">{move || t("upcoming", &language.get())}</h3>
+            <div class="flex flex-col gap-2">
+                {move || upcoming.get().into_iter().map(|occurrence| {
+                    view! { <OccurrenceRow occurrence=occurrence language=language is_dark=is_dark on_change=Callback::new(move |(template, change)| change_occurrence(template, change))/> }
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
+
+// Jedno nadchodzące wystąpienie: pominięcie/przywrócenie albo inny tytuł i kwota tylko dla niego
+#[component]
+fn OccurrenceRow(
+    occurrence: Occurrence,
+    language: ReadSignal<String>,
+    is_dark: Signal<bool>,
+    on_change: Callback<(String, OccurrenceChange)>,
+) -> impl IntoView {
+    let Occurrence { template, date, transaction, skipped } = occurrence;
+    let title = RwSignal::new(transaction.title.clone());
+    let amount = RwSignal::new(transaction.amount.to_input_string());
+    let currency = transaction.amount.currency;
+    let (template_skip, template_save) = (template.clone(), template);
+
+    let toggle_skip = move |_| {
+        on_change.run((template_skip.clone(), OccurrenceChange { date, skip: !skipped, ..Default::default() }));
+    };
+    let save = move |_| {
+        let change = OccurrenceChange {
+            date,
+            title: Some(title.get_untracked().trim().to_string()).filter(|t| !t.is_empty()),
+            amount: Money::parse(&amount.get_untracked(), currency),
+            ..Default::default()
+        };
+        on_change.run((template_save.clone(), change));
+    };
+
+    view! {
+        <div class={if skipped { "grid grid-cols-1 md:grid-cols-6 gap-2 items-center opacity-50" } else { "grid grid-cols-1 md:grid-cols-6 gap-2 items-center" }}>
+            <span class="text-sm font-bold">{move || format_date_display(date, &language.get())}</span>
+            <input type="text" class={move || format!("md:col-span-2 {}", get_input_style(is_dark.get()))} disabled=skipped
+                on:input=move |ev| title.set(event_target_value(&ev)) prop:value=title />
+            <input type="text" inputmode="decimal" class={move || get_input_style(is_dark.get())} disabled=skipped
+                on:input=move |ev| amount.set(event_target_value(&ev)) prop:value=amount />
+            <button class="bg-emerald-600 text-white font-bold py-2 px-3 rounded-lg hover:bg-emerald-700 transition text-sm" disabled=skipped on:click=save>
+                {move || t("save_occurrence", &language.get())}
+            </button>
+            <button class="bg-slate-500 text-white font-bold py-2 px-3 rounded-lg hover:bg-slate-600 transition text-sm" on:click=toggle_skip>
+                {move || if skipped { t("restore_occurrence", &language.get()) } else { t("skip_occurrence", &language.get()) }}
+            </button>
+        </div>
+    }
+}
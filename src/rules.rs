@@ -0,0 +1,321 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::api::call;
+use crate::duplicates::duplicate_summary;
+use crate::models::*;
+use crate::translate::t;
+use crate::utils::*;
+
+#[derive(serde::Serialize)]
+struct NoArgs {}
+
+#[derive(serde::Serialize)]
+struct RulesArgs {
+    rules: Vec<CategoryRule>,
+}
+
+#[derive(serde::Serialize)]
+struct RuleArgs {
+    rule: CategoryRule,
+}
+
+#[derive(serde::Serialize)]
+struct ReapplyArgs {
+    only_uncategorized: bool,
+}
+
+// Ile dopasowań z historii pokazujemy pod formularzem
+const PREVIEW_MATCHES: usize = 10;
+
+// Reguły automatycznej kategoryzacji: lista w kolejności pierwszeństwa (pierwsza pasująca
+// wygrywa), formularz nowej reguły ze sprawdzeniem na historii i ponowne zastosowanie
+// reguł do zapisanych transakcji
+#[component]
+pub fn RuleManager(
+    categories: ReadSignal<Vec<Category>>,
+    language: ReadSignal<String>,
+    is_dark: Signal<bool>,
+    currency: Signal<CurrencyCode>,
+    on_changed: Callback<()>,
+) -> impl IntoView {
+    let rules = RwSignal::new(Vec::<CategoryRule>::new());
+    let (error, set_error) = signal::<Option<String>>(None);
+    let (message, set_message) = signal::<Option<String>>(None);
+
+    // Formularz nowej reguły
+    let contains = RwSignal::new(String::new());
+    let regex = RwSignal::new(String::new());
+    let payee = RwSignal::new(String::new());
+    let min_amount = RwSignal::new(String::new());
+    let max_amount = RwSignal::new(String::new());
+    let rule_currency = RwSignal::new(currency.get_untracked());
+    let category = RwSignal::new(String::new());
+    let tags = RwSignal::new(String::new());
+    let matches = RwSignal::new(None::<Vec<Transaction>>);
+    let only_uncategorized = RwSignal::new(true);
+
+    spawn_local(async move {
+        match call::<_, Vec<CategoryRule>>("load_rules", &NoArgs {}).await {
+            Ok(list) => rules.set(list),
+            Err(e) => set_error.set(Some(e)),
+        }
+    });
+
+    // Zapis całej listy; lokalnie zmieniamy ją dopiero, gdy backend ją przyjmie
+    let persist = move |list: Vec<CategoryRule>| {
+        spawn_local(async move {
+            match call::<_, ()>("save_rules", &RulesArgs { rules: list.clone() }).await {
+                Ok(()) => {
+                    set_error.set(None);
+                    rules.set(list);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let edit = move |index: usize, change: &dyn Fn(&mut Vec<CategoryRule>)| {
+        let mut list = rules.get_untracked();
+        if index < list.len() {
+            change(&mut list);
+            persist(list);
+        }
+    };
+
+    // Reguła z formularza; pusta kwota to brak granicy
+    let draft = move || -> Result<CategoryRule, String> {
+        let lang = language.get_untracked();
+        let amount = |text: String| -> Result<Option<Money>, String> {
+            if text.trim().is_empty() {
+                return Ok(None);
+            }
+            Money::parse(&text, rule_currency.get_untracked())
+                .map(Some)
+                .ok_or_else(|| t("rule_invalid_amount", &lang))
+        };
+        let mut rule = CategoryRule::new(&category.get_untracked());
+        rule.conditions = RuleConditions {
+            title_contains: contains.get_untracked().trim().to_string(),
+            title_regex: regex.get_untracked().trim().to_string(),
+            payee: payee.get_untracked().trim().to_string(),
+            min_amount: amount(min_amount.get_untracked())?,
+            max_amount: amount(max_amount.get_untracked())?,
+        };
+        rule.tags = parse_tags(&tags.get_untracked());
+        Ok(rule)
+    };
+
+    let test = move |_| {
+        let rule = match draft() {
+            Ok(rule) => rule,
+            Err(e) => return set_error.set(Some(e)),
+        };
+        spawn_local(async move {
+            match call::<_, Vec<Transaction>>("test_rule", &RuleArgs { rule }).await {
+                Ok(found) => {
+                    set_error.set(None);
+                    matches.set(Some(found));
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let add_rule = move |_| {
+        let rule = match draft() {
+            Ok(rule) => rule,
+            Err(e) => return set_error.set(Some(e)),
+        };
+        let mut list = rules.get_untracked();
+        list.push(rule);
+        persist(list);
+        for field in [contains, regex, payee, min_amount, max_amount, tags] {
+            field.set(String::new());
+        }
+        matches.set(None);
+    };
+
+    let reapply = move |_| {
+        let only_uncategorized = only_uncategorized.get_untracked();
+        spawn_local(async move {
+            match call::<_, usize>("reapply_rules", &ReapplyArgs { only_uncategorized }).await {
+                Ok(changed) => {
+                    set_error.set(None);
+                    set_message.set(Some(format!("{}: {}", t("changed_count", &language.get_untracked()), changed)));
+                    on_changed.run(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    // Domyślnie pierwsza aktywna kategoria wydatków
+    Effect::new(move |_| {
+        let all = categories.get();
+        if category.get_untracked().is_empty() {
+            if let Some((first, _)) = Category::tree(&all).into_iter().find(|(c, _)| c.kind == TransactionKind::Expense && !c.archived) {
+                category.set(first.id.clone());
+            }
+        }
+    });
+
+    let category_options = move |selected: String| {
+        let all = categories.get();
+        let lang = language.get();
+        Category::tree(&all)
+            .into_iter()
+            .filter(|(c, _)| !c.archived || c.id == selected)
+            .map(|(c, _)| {
+                let value = c.id.clone();
+                let is_selected = c.id == selected;
+                let kind = t(&format!("kind_{}", c.kind.as_str()), &lang);
+                view! { <option class="text-slate-800" value=value selected=is_selected>{format!("{} {} ({})", c.icon, category_path(&all, &c.id, &lang), kind)}</option> }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    view! {
+        <div class={move || get_box_style(is_dark.get())}>
+            <h2 class="text-xl font-bold mb-2">{move || t("rules", &language.get())}</h2>
+            <p class="text-sm opacity-60 mb-6">{move || t("rules_hint", &language.get())}</p>
+
+            // Nowa reguła
+            <div class="grid grid-cols-1 md:grid-cols-3 gap-3 mb-3">
+                <input type="text" placeholder={move || t("rule_title_contains", &language.get())} class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| contains.set(event_target_value(&ev)) prop:value=contains />
+                <input type="text" placeholder={move || t("rule_title_regex", &language.get())} class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| regex.set(event_target_value(&ev)) prop:value=regex />
+                <input type="text" placeholder={move || t("rule_payee", &language.get())} class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| payee.set(event_target_value(&ev)) prop:value=payee />
+                <input type="text" inputmode="decimal" placeholder={move || t("rule_min_amount", &language.get())} class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| min_amount.set(event_target_value(&ev)) prop:value=min_amount />
+                <input type="text" inputmode="decimal" placeholder={move || t("rule_max_amount", &language.get())} class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| max_amount.set(event_target_value(&ev)) prop:value=max_amount />
+                <select class={move || get_input_style(is_dark.get())}
+                    on:change=move |ev| { if let Some(c) = CurrencyCode::new(&event_target_value(&ev)) { rule_currency.set(c) } }>
+                    {move || {
+                        let lang = language.get();
+                        let selected = rule_currency.get_untracked();
+                        CURRENCIES.iter().map(|info| view! {
+                            <option class="text-slate-800" value=info.0 selected=selected.as_str() == info.0>{currency_label(info, &lang)}</option>
+                        }).collect::<Vec<_>>()
+                    }}
+                </select>
+                <select class={move || get_input_style(is_dark.get())} on:change=move |ev| category.set(event_target_value(&ev))>
+                    {move || category_options(category.get())}
+                </select>
+                <input type="text" placeholder={move || t("tags_placeholder", &language.get())} class={move || get_input_style(is_dark.get())}
+                    on:input=move |ev| tags.set(event_target_value(&ev)) prop:value=tags />
+                <div class="grid grid-cols-2 gap-3">
+                    <button class="bg-slate-500 text-white font-bold py-2 px-4 rounded-lg hover:bg-slate-600 transition" on:click=test>
+                        {move || t("test_rule", &language.get())}
+                    </button>
+                    <button class="bg-emerald-600 text-white font-bold py-2 px-4 rounded-lg hover:bg-emerald-700 transition" on:click=add_rule>
+                        {move || t("add_rule", &language.get())}
+                    </button>
+                </div>
+            </div>
+
+            {move || error.get().map(|e| view! {
+                <div class="mb-6 p-3 bg-red-100 border border-red-400 text-red-800 rounded-lg text-center font-medium">{e}</div>
+            })}
+
+            // Wynik sprawdzenia na historii: liczba i najnowsze dopasowania
+            {move || matches.get().map(|found| {
+                let lang = language.get();
+                let all = categories.get();
+                let target = category.get();
+                let count = found.len();
+                view! {
+                    <div class="mb-6 text-sm">
+                        <p class="font-bold mb-2">{format!("{}: {}", t("rule_matches", &lang), count)}</p>
+                        {found.into_iter().take(PREVIEW_MATCHES).map(|tx| {
+                            let change = format!("{} → {}", category_path(&all, &tx.category, &lang), category_path(&all, &target, &lang));
+                            view! {
+                                <div class="flex justify-between gap-4 opacity-80">
+                                    <span class="truncate">{duplicate_summary(&tx, &lang)}</span>
+                                    <span class="whitespace-nowrap">{change}</span>
+                                </div>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+                }
+            })}
+
+            // Zapisane reguły, od najważniejszej
+            <div class="flex flex-col gap-2 mb-6">
+                {move || {
+                    let list = rules.get();
+                    let last = list.len().saturating_sub(1);
+                    list.into_iter().enumerate().map(|(index, rule)| {
+                        let summary = rule_summary(&rule, &language.get());
+                        let selected = rule.category.clone();
+                        let enabled = rule.enabled;
+                        view! {
+                            <div class={if enabled { "grid grid-cols-1 md:grid-cols-12 gap-2 items-center" } else { "grid grid-cols-1 md:grid-cols-12 gap-2 items-center opacity-50" }}>
+                                <label class="md:col-span-1 flex items-center gap-2 text-sm">
+                                    <input type="checkbox" prop:checked=enabled
+                                        on:change=move |ev| { let on = event_target_checked(&ev); edit(index, &|list| list[index].enabled = on); } />
+                                    {move || t("rule_enabled", &language.get())}
+                                </label>
+                                <span class="md:col-span-5 text-sm truncate">{summary}</span>
+                                <select class={move || format!("md:col-span-3 {}", get_input_style(is_dark.get()))}
+                                    on:change=move |ev| { let id = event_target_value(&ev); edit(index, &|list| list[index].category = id.clone()); }>
+                                    {category_options(selected)}
+                                </select>
+                                <button class="bg-slate-500 text-white font-bold py-2 px-3 rounded-lg hover:bg-slate-600 transition text-sm" disabled=index == 0
+                                    on:click=move |_| edit(index, &|list| list.swap(index - 1, index))>
+                                    {move || t("move_up", &language.get())}
+                                </button>
+                                <button class="bg-slate-500 text-white font-bold py-2 px-3 rounded-lg hover:bg-slate-600 transition text-sm" disabled=index == last
+                                    on:click=move |_| edit(index, &|list| list.swap(index, index + 1))>
+                                    {move || t("move_down", &language.get())}
+                                </button>
+                                <button class="bg-red-500/10 text-red-500 hover:bg-red-500 hover:text-white font-bold py-2 px-3 rounded-lg transition text-sm"
+                                    on:click=move |_| edit(index, &|list| { list.remove(index); })>
+                                    {move || t("delete", &language.get())}
+                                </button>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>()
+                }}
+            </div>
+
+            // Ponowne zastosowanie reguł do historii
+            <div class="flex flex-col md:flex-row md:items-center gap-3">
+                <label class="flex items-center gap-2 text-sm">
+                    <input type="checkbox" prop:checked=only_uncategorized on:change=move |ev| only_uncategorized.set(event_target_checked(&ev)) />
+                    {move || t("only_uncategorized", &language.get())}
+                </label>
+                <button class="bg-emerald-600 text-white font-bold py-2 px-4 rounded-lg hover:bg-emerald-700 transition" on:click=reapply>
+                    {move || t("reapply_rules", &language.get())}
+                </button>
+                {move || message.get().map(|m| view! { <span class="text-sm font-medium">{m}</span> })}
+            </div>
+        </div>
+    }
+}
+
+// Warunki reguły jednym ciągiem, np. "tytuł zawiera „orlen” · kwota ≥ 50,00 zł"
+fn rule_summary(rule: &CategoryRule, lang: &str) -> String {
+    let c = &rule.conditions;
+    let mut parts = vec![];
+    if !c.title_contains.is_empty() {
+        parts.push(format!("{} „{}”", t("rule_title_contains", lang), c.title_contains));
+    }
+    if !c.title_regex.is_empty() {
+        parts.push(format!("{} /{}/", t("rule_title_regex", lang), c.title_regex));
+    }
+    if !c.payee.is_empty() {
+        parts.push(format!("{} „{}”", t("rule_payee", lang), c.payee));
+    }
+    if let Some(min) = c.min_amount {
+        parts.push(format!("≥ {}", format_currency(min, lang)));
+    }
+    if let Some(max) = c.max_amount {
+        parts.push(format!("≤ {}", format_currency(max, lang)));
+    }
+    parts.extend(rule.tags.iter().map(|tag| format!("#{}", tag)));
+    parts.join(" · ")
+}
@@ -27,6 +27,32 @@ pub fn t(key: &str, lang: &str) -> String {
         ("en", "add_transaction") => "Add Transaction",
         ("pl", "remove_transaction") => "Usuń Transakcję",
         ("en", "remove_transaction") => "Remove Transaction",
+        ("pl", "edit_transaction") => "Edytuj Transakcję",
+        ("en", "edit_transaction") => "Edit Transaction",
+        ("pl", "save_changes") => "Zapisz Zmiany",
+        ("en", "save_changes") => "Save Changes",
+        ("pl", "tags_placeholder") => "Tagi, po przecinku (opcjonalnie)",
+        ("en", "tags_placeholder") => "Tags, comma separated (optional)",
+        ("pl", "suggested_category") => "Podpowiedź z historii",
+        ("en", "suggested_category") => "Suggested from history",
+        ("pl", "use_suggestion") => "Użyj",
+        ("en", "use_suggestion") => "Use",
+        ("pl", "cancel_edit") => "Anuluj Edycję",
+        ("en", "cancel_edit") => "Cancel Edit",
+        ("pl", "income") => "Przychody",
+        ("en", "income") => "Income",
+        ("pl", "net_balance") => "Bilans",
+        ("en", "net_balance") => "Net Balance",
+        ("pl", "kind_expense") => "Wydatek",
+        ("en", "kind_expense") => "Expense",
+        ("pl", "kind_income") => "Przychód",
+        ("en", "kind_income") => "Income",
+        ("pl", "kind_transfer") => "Przelew",
+        ("en", "kind_transfer") => "Transfer",
+        ("pl", "no_limit") => "bez limitu",
+        ("en", "no_limit") => "no limit",
+        ("pl", "no_category_spending") => "Brak wydatków i limitów w tym miesiącu",
+        ("en", "no_category_spending") => "No spending or limits this month",
         ("pl", "name_placeholder") => "Nazwa",
         ("en", "name_placeholder") => "Name",
         ("pl", "amount_placeholder") => "Kwota",
@@ -41,6 +67,22 @@ pub fn t(key: &str, lang: &str) -> String {
         // Zakładka Limity
         ("pl", "edit_limits_month") => "Edytuj limity dla miesiąca:",
         ("en", "edit_limits_month") => "Edit limits for month:",
+        ("pl", "copy_previous_limits") => "Kopiuj z poprzedniego miesiąca",
+        ("en", "copy_previous_limits") => "Copy from previous month",
+        ("pl", "no_previous_limits") => "Poprzedni miesiąc nie ma limitów",
+        ("en", "no_previous_limits") => "The previous month has no limits",
+        ("pl", "save_limit_template") => "Zapisz jako szablon",
+        ("en", "save_limit_template") => "Save as template",
+        ("pl", "clear_limit_template") => "Usuń szablon",
+        ("en", "clear_limit_template") => "Remove template",
+        ("pl", "limit_template_set") => "Nowe miesiące zaczynają się z limitami z szablonu.",
+        ("en", "limit_template_set") => "New months start with the limits from the template.",
+        ("pl", "limit_template_none") => "Brak szablonu - nowe miesiące zaczynają się bez limitów.",
+        ("en", "limit_template_none") => "No template - new months start without limits.",
+        ("pl", "rollover") => "Przenoś resztę na następny miesiąc",
+        ("en", "rollover") => "Carry the rest over to next month",
+        ("pl", "carried_over") => "Przeniesione z poprzedniego miesiąca",
+        ("en", "carried_over") => "Carried over from last month",
         ("pl", "cat_limits") => "Limity kategorii",
         ("en", "cat_limits") => "Category Limits",
 
@@ -57,20 +99,253 @@ pub fn t(key: &str, lang: &str) -> String {
         ("en", "dark") => "Dark",
         ("pl", "clear_data") => "Wyczyść dane",
         ("en", "clear_data") => "Clear Data",
+        ("pl", "export_json") => "Eksportuj do JSON",
+        ("en", "export_json") => "Export to JSON",
+        ("pl", "exported_to") => "Zapisano w",
+        ("en", "exported_to") => "Saved to",
+
+        // Odzyskiwanie danych
+        ("pl", "recovery_title") => "Nie udało się odczytać danych",
+        ("en", "recovery_title") => "Your data could not be read",
+        ("pl", "data_corrupted") => "Plik danych jest uszkodzony",
+        ("en", "data_corrupted") => "The data file is corrupted",
+        ("pl", "unsupported_version") => "Dane zapisała nowsza wersja aplikacji. Zaktualizuj aplikację lub wybierz jedną z opcji poniżej - obecny plik zostanie odłożony, nie usunięty",
+        ("en", "unsupported_version") => "The data was saved by a newer version of the app. Update the app or pick an option below - the current file will be set aside, not deleted",
+        ("pl", "quarantined_as") => "Uszkodzony plik zachowano jako",
+        ("en", "quarantined_as") => "The damaged file was kept as",
+        ("pl", "restore_backup") => "Przywróć kopię zapasową",
+        ("en", "restore_backup") => "Restore a backup",
+        ("pl", "no_backups") => "Brak czytelnych kopii zapasowych.",
+        ("en", "no_backups") => "No readable backups found.",
+        ("pl", "backup_unreadable") => "Nie można odczytać kopii",
+        ("en", "backup_unreadable") => "Cannot read backup",
+        ("pl", "salvage_data") => "Odzyskaj co się da z uszkodzonego pliku",
+        ("en", "salvage_data") => "Salvage what's readable from the damaged file",
+        ("pl", "nothing_to_salvage") => "Nie znaleziono pliku do odzyskania.",
+        ("en", "nothing_to_salvage") => "No file found to salvage.",
+        ("pl", "start_fresh") => "Zacznij od nowa (puste dane)",
+        ("en", "start_fresh") => "Start fresh (empty data)",
 
         // Kategorie
-        ("en", "Codzienne Wydatki") => "Daily Expenses",
-        ("pl", "Daily Expenses") => "Codzienne Wydatki",
-        ("en", "Rachunki") => "Bills",
-        ("pl", "Bills") => "Rachunki",
-        ("en", "Jedzenie") => "Food",
-        ("pl", "Food") => "Jedzenie",
-        ("en", "Auto i Transport") => "Car & Transport",
-        ("pl", "Car & Transport") => "Auto i Transport",
-        ("en", "Rozrywka") => "Entertainment",
-        ("pl", "Entertainment") => "Rozrywka",
-        ("en", "Nieskategoryzowane") => "Uncategorized",
-        ("pl", "Uncategorized") => "Nieskategoryzowane",
+        ("pl", "categories") => "Kategorie",
+        ("en", "categories") => "Categories",
+        ("pl", "add_category") => "Dodaj Kategorię",
+        ("en", "add_category") => "Add Category",
+        ("pl", "label_pl") => "Nazwa (polski)",
+        ("en", "label_pl") => "Name (Polish)",
+        ("pl", "label_en") => "Nazwa (angielski)",
+        ("en", "label_en") => "Name (English)",
+        ("pl", "archive") => "Archiwizuj",
+        ("en", "archive") => "Archive",
+        ("pl", "unarchive") => "Przywróć",
+        ("en", "unarchive") => "Restore",
+        ("pl", "merge_into") => "Scal z wybraną",
+        ("en", "merge_into") => "Merge into selected",
+        ("pl", "no_parent") => "(kategoria główna)",
+        ("en", "no_parent") => "(top-level category)",
+
+        // Reguły kategoryzacji
+        ("pl", "rules") => "Reguły kategoryzacji",
+        ("en", "rules") => "Categorization rules",
+        ("pl", "rules_hint") => "Reguły nadają kategorię i tagi importowanym transakcjom bez kategorii i podpowiadają ją w formularzu. Wygrywa pierwsza pasująca.",
+        ("en", "rules_hint") => "Rules assign a category and tags to imported uncategorized transactions and suggest one in the form. The first matching rule wins.",
+        ("pl", "rule_title_contains") => "Tytuł zawiera",
+        ("en", "rule_title_contains") => "Title contains",
+        ("pl", "rule_title_regex") => "Tytuł pasuje do wyrażenia",
+        ("en", "rule_title_regex") => "Title matches regex",
+        ("pl", "rule_payee") => "Kontrahent",
+        ("en", "rule_payee") => "Payee",
+        ("pl", "rule_min_amount") => "Kwota od",
+        ("en", "rule_min_amount") => "Amount from",
+        ("pl", "rule_max_amount") => "Kwota do",
+        ("en", "rule_max_amount") => "Amount to",
+        ("pl", "rule_invalid_amount") => "Niepoprawna kwota w regule",
+        ("en", "rule_invalid_amount") => "Invalid amount in rule",
+        ("pl", "rule_enabled") => "Aktywna",
+        ("en", "rule_enabled") => "Enabled",
+        ("pl", "add_rule") => "Dodaj regułę",
+        ("en", "add_rule") => "Add rule",
+        ("pl", "test_rule") => "Sprawdź na historii",
+        ("en", "test_rule") => "Test on history",
+        ("pl", "rule_matches") => "Pasujące transakcje",
+        ("en", "rule_matches") => "Matching transactions",
+        ("pl", "move_up") => "W górę",
+        ("en", "move_up") => "Up",
+        ("pl", "move_down") => "W dół",
+        ("en", "move_down") => "Down",
+        ("pl", "delete") => "Usuń",
+        ("en", "delete") => "Delete",
+        ("pl", "only_uncategorized") => "Tylko transakcje bez kategorii",
+        ("en", "only_uncategorized") => "Only uncategorized transactions",
+        ("pl", "reapply_rules") => "Zastosuj reguły do historii",
+        ("en", "reapply_rules") => "Apply rules to history",
+        ("pl", "changed_count") => "Zmieniono",
+        ("en", "changed_count") => "Changed",
+
+        // Transakcje cykliczne
+        ("pl", "recurring") => "Cykliczne",
+        ("en", "recurring") => "Recurring",
+        ("pl", "recurring_hint") => "Szablony powtarzających się transakcji. Wystąpienia, które nadeszły, są dodawane przy starcie aplikacji (i od razu po zapisie szablonu).",
+        ("en", "recurring_hint") => "Templates of repeating transactions. Due occurrences are added on app start (and right after saving a template).",
+        ("pl", "add_recurring") => "Dodaj szablon",
+        ("en", "add_recurring") => "Add template",
+        ("pl", "recurring_start") => "Pierwsze wystąpienie",
+        ("en", "recurring_start") => "First occurrence",
+        ("pl", "recurring_frequency") => "Powtarzaj",
+        ("en", "recurring_frequency") => "Repeat",
+        ("pl", "recurring_interval") => "Co ile",
+        ("en", "recurring_interval") => "Every",
+        ("pl", "frequency_daily") => "Co dzień",
+        ("en", "frequency_daily") => "Daily",
+        ("pl", "frequency_weekly") => "Co tydzień",
+        ("en", "frequency_weekly") => "Weekly",
+        ("pl", "frequency_monthly") => "Co miesiąc",
+        ("en", "frequency_monthly") => "Monthly",
+        ("pl", "frequency_yearly") => "Co rok",
+        ("en", "frequency_yearly") => "Yearly",
+        ("pl", "recurring_same_day") => "Ten sam dzień miesiąca",
+        ("en", "recurring_same_day") => "Same day of month",
+        ("pl", "nth_last") => "Ostatni",
+        ("en", "nth_last") => "Last",
+        ("pl", "weekday_Mon") => "Poniedziałek", ("en", "weekday_Mon") => "Monday",
+        ("pl", "weekday_Tue") => "Wtorek", ("en", "weekday_Tue") => "Tuesday",
+        ("pl", "weekday_Wed") => "Środa", ("en", "weekday_Wed") => "Wednesday",
+        ("pl", "weekday_Thu") => "Czwartek", ("en", "weekday_Thu") => "Thursday",
+        ("pl", "weekday_Fri") => "Piątek", ("en", "weekday_Fri") => "Friday",
+        ("pl", "weekday_Sat") => "Sobota", ("en", "weekday_Sat") => "Saturday",
+        ("pl", "weekday_Sun") => "Niedziela", ("en", "weekday_Sun") => "Sunday",
+        ("pl", "recurring_end_never") => "Bez końca",
+        ("en", "recurring_end_never") => "No end",
+        ("pl", "recurring_end_until") => "Do dnia",
+        ("en", "recurring_end_until") => "Until date",
+        ("pl", "recurring_end_count") => "Liczba wystąpień",
+        ("en", "recurring_end_count") => "Number of occurrences",
+        ("pl", "recurring_from") => "od",
+        ("en", "recurring_from") => "from",
+        ("pl", "recurring_until") => "do",
+        ("en", "recurring_until") => "until",
+        ("pl", "recurring_count") => "wystąpień",
+        ("en", "recurring_count") => "occurrences",
+        ("pl", "recurring_invalid_amount") => "Podaj kwotę większą od zera",
+        ("en", "recurring_invalid_amount") => "Enter an amount greater than zero",
+        ("pl", "recurring_invalid_date") => "Niepoprawna data",
+        ("en", "recurring_invalid_date") => "Invalid date",
+        ("pl", "recurring_invalid_count") => "Podaj liczbę całkowitą większą od zera",
+        ("en", "recurring_invalid_count") => "Enter a whole number greater than zero",
+        ("pl", "upcoming") => "Nadchodzące",
+        ("en", "upcoming") => "Upcoming",
+        ("pl", "save_occurrence") => "Zmień to wystąpienie",
+        ("en", "save_occurrence") => "Change this one",
+        ("pl", "skip_occurrence") => "Pomiń",
+        ("en", "skip_occurrence") => "Skip",
+        ("pl", "restore_occurrence") => "Przywróć",
+        ("en", "restore_occurrence") => "Restore",
+        ("pl", "edit") => "Edytuj",
+        ("en", "edit") => "Edit",
+        ("pl", "subscriptions") => "Wykryte subskrypcje",
+        ("en", "subscriptions") => "Detected subscriptions",
+        ("pl", "subscriptions_hint") => "Regularne płatności temu samemu kontrahentowi w podobnej kwocie. Dodanie tworzy szablon od następnej spodziewanej płatności.",
+        ("en", "subscriptions_hint") => "Regular payments to the same payee in a similar amount. Adding one creates a template starting from the next expected payment.",
+        ("pl", "no_subscriptions") => "Nie wykryto nowych subskrypcji",
+        ("en", "no_subscriptions") => "No new subscriptions detected",
+        ("pl", "subscription_payments") => "płatności od",
+        ("en", "subscription_payments") => "payments since",
+        ("pl", "yearly_cost") => "Rocznie",
+        ("en", "yearly_cost") => "Per year",
+        ("pl", "price_increase") => "Podwyżka",
+        ("en", "price_increase") => "Price increase",
+        ("pl", "add_subscription") => "Dodaj jako cykliczną",
+        ("en", "add_subscription") => "Add as recurring",
+
+        // Kursy walut
+        ("pl", "exchange_rates") => "Kursy Walut",
+        ("en", "exchange_rates") => "Exchange Rates",
+        ("pl", "import_rates") => "Importuj kursy (EBC/NBP)",
+        ("en", "import_rates") => "Import rates (ECB/NBP)",
+        ("pl", "rates_on_day") => "Kursy z dnia:",
+        ("en", "rates_on_day") => "Rates on:",
+        ("pl", "rate") => "Kurs",
+        ("en", "rate") => "Rate",
+        ("pl", "add_rate") => "Zapisz kurs",
+        ("en", "add_rate") => "Save Rate",
+        ("pl", "no_rates") => "Brak kursów z tego dnia ani wcześniejszych",
+        ("en", "no_rates") => "No rates on or before this day",
+        ("pl", "remove_override") => "Usuń korektę",
+        ("en", "remove_override") => "Remove Override",
+        ("pl", "source_ecb") => "EBC",
+        ("en", "source_ecb") => "ECB",
+        ("pl", "source_nbp") => "NBP",
+        ("en", "source_nbp") => "NBP",
+        ("pl", "source_manual") => "ręcznie",
+        ("en", "source_manual") => "manual",
+        ("pl", "no_rate") => "brak kursu",
+        ("en", "no_rate") => "no rate",
+        ("pl", "missing_rates") => "Transakcje pominięte w sumach z braku kursu waluty",
+        ("en", "missing_rates") => "Transactions left out of totals for lack of an exchange rate",
+        ("pl", "imported") => "Zaimportowano",
+        ("en", "imported") => "Imported",
+        ("pl", "skipped") => "pominięto",
+        ("en", "skipped") => "skipped",
+        ("pl", "merged") => "scalono",
+        ("en", "merged") => "merged",
+        ("pl", "possible_duplicate") => "Podobna transakcja jest już zapisana",
+        ("en", "possible_duplicate") => "A similar transaction is already saved",
+        ("pl", "merge_duplicate") => "Scal",
+        ("en", "merge_duplicate") => "Merge",
+        ("pl", "keep_both") => "Zachowaj obie",
+        ("en", "keep_both") => "Keep both",
+
+        // Import wyciągów i eksport
+        ("pl", "import_export") => "Import i Eksport",
+        ("en", "import_export") => "Import & Export",
+        ("pl", "import_statement") => "Import wyciągu",
+        ("en", "import_statement") => "Statement Import",
+        ("pl", "statement_currency") => "Waluta wyciągu (jeśli plik jej nie podaje)",
+        ("en", "statement_currency") => "Statement currency (if the file has none)",
+        ("pl", "choose_file") => "Wybierz plik",
+        ("en", "choose_file") => "Choose File",
+        ("pl", "delimiter") => "Separator",
+        ("en", "delimiter") => "Delimiter",
+        ("pl", "tab_character") => "tabulator",
+        ("en", "tab_character") => "tab",
+        ("pl", "encoding") => "Kodowanie",
+        ("en", "encoding") => "Encoding",
+        ("pl", "skip_rows") => "Pomiń wiersze",
+        ("en", "skip_rows") => "Skip rows",
+        ("pl", "has_header") => "Pierwszy wiersz to nagłówek",
+        ("en", "has_header") => "First row is a header",
+        ("pl", "column_title") => "Kolumna z tytułem",
+        ("en", "column_title") => "Title column",
+        ("pl", "column_amount") => "Kolumna z kwotą",
+        ("en", "column_amount") => "Amount column",
+        ("pl", "column_date") => "Kolumna z datą",
+        ("en", "column_date") => "Date column",
+        ("pl", "column_category") => "Kolumna z kategorią",
+        ("en", "column_category") => "Category column",
+        ("pl", "column") => "Kolumna",
+        ("en", "column") => "Column",
+        ("pl", "not_mapped") => "(brak)",
+        ("en", "not_mapped") => "(none)",
+        ("pl", "show_preview") => "Podgląd",
+        ("en", "show_preview") => "Preview",
+        ("pl", "rows_valid") => "Poprawne wiersze",
+        ("en", "rows_valid") => "Valid rows",
+        ("pl", "rows_invalid") => "z błędami",
+        ("en", "rows_invalid") => "with errors",
+        ("pl", "line") => "Wiersz",
+        ("en", "line") => "Line",
+        ("pl", "commit_import") => "Importuj poprawne wiersze",
+        ("en", "commit_import") => "Import Valid Rows",
+        ("pl", "export") => "Eksport",
+        ("en", "export") => "Export",
+        ("pl", "export_csv") => "Eksportuj transakcje do CSV",
+        ("en", "export_csv") => "Export Transactions to CSV",
+        ("pl", "date_from") => "Od",
+        ("en", "date_from") => "From",
+        ("pl", "date_to") => "Do",
+        ("en", "date_to") => "To",
+        ("pl", "export_categories") => "Kategorie (bez zaznaczenia - wszystkie)",
+        ("en", "export_categories") => "Categories (none selected - all)",
 
         // Miesiące
         ("pl", "month_short_1") => "Styczeń", ("en", "month_short_1") => "January",
@@ -1,35 +1,85 @@
 use chrono::NaiveDate;
 use chrono::Local;
 
-pub fn get_exchange_rate(currency_code: &str) -> f64 {
-    match currency_code {
-        "PLN" => 1.0,
-        "USD" => 4.10,
-        "EUR" => 4.35,
-        _ => 1.0,
-    }
+use crate::models::{Category, CurrencyInfo, Money};
+
+// Pozycja listy walut (formularz transakcji, ustawienia): "PLN – złoty polski"
+pub fn currency_label(info: &CurrencyInfo, lang: &str) -> String {
+    let name = if lang == "pl" { info.4 } else { info.5 };
+    format!("{} – {}", info.0, name)
 }
 
+pub fn format_currency(amount: Money, lang: &str) -> String {
+    amount.format(lang)
+}
+
+// Kwota przeniesiona z poprzedniego miesiąca ze znakiem: "+45,00 zł" albo "−20,00 zł"
+pub fn format_carried(amount: Money, lang: &str) -> String {
+    let sign = if amount.minor < 0 { "−" } else { "+" };
+    format!("{}{}", sign, amount.abs().format(lang))
+}
+
+// Nazwa kategorii o danym id w wybranym języku; nieznane id pokazujemy wprost
+pub fn category_label(categories: &[Category], id: &str, lang: &str) -> String {
+    categories
+        .iter()
+        .find(|c| c.id == id)
+        .map(|c| c.label(lang).to_string())
+        .unwrap_or_else(|| id.to_string())
+}
+
+// Pełna ścieżka kategorii, np. "Jedzenie › Restauracje"
+pub fn category_path(categories: &[Category], id: &str, lang: &str) -> String {
+    Category::ancestors(categories, id)
+        .into_iter()
+        .rev()
+        .map(|ancestor| category_label(categories, ancestor, lang))
+        .collect::<Vec<_>>()
+        .join(" › ")
+}
 
-pub fn format_currency(amount: f64, currency: &str, _lang: &str) -> String {
-    let abs_amount = amount.abs();
-    let sign = if amount < 0.0 { "-" } else { "" };
-    match currency {
-        "USD" => format!("{}${:.2}", sign, abs_amount),
-        "EUR" => format!("{}€{:.2}", sign, abs_amount),
-        _ => format!("{}{:.2} {}", sign, abs_amount, currency),
+pub fn format_date_display(date: NaiveDate, lang: &str) -> String {
+    if lang == "en" {
+        date.format("%m/%d/%Y").to_string()
+    } else {
+        date.format("%d.%m.%Y").to_string()
+    }
+}
+
+// Kwota wpisywana w formularzu: same cyfry i jedna kropka, najwyżej `digits` miejsc po
+// przecinku (0 dla JPY - wtedy bez separatora, 3 dla KWD)
+pub fn sanitize_amount(value: &str, digits: usize) -> String {
+    let mut sanitized_value = String::new();
+    let mut decimal_point_found = false;
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            sanitized_value.push(c);
+        } else if (c == '.' || c == ',') && !decimal_point_found && digits > 0 {
+            sanitized_value.push('.');
+            decimal_point_found = true;
+        }
+    }
+    if let Some((integer_part, decimal_part)) = sanitized_value.split_once('.') {
+        let trimmed_decimal = decimal_part.chars().take(digits).collect::<String>();
+        sanitized_value = format!("{}.{}", integer_part, trimmed_decimal);
     }
+    sanitized_value
 }
 
-pub fn format_date_display(date_str: &str, lang: &str) -> String {
-    if let Ok(date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-        if lang == "en" {
-            return date.format("%m/%d/%Y").to_string();
-        } else {
-            return date.format("%d.%m.%Y").to_string();
+// Tagi wpisane po przecinku: bez pustych i powtórzeń
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = vec![];
+    for tag in text.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
         }
     }
-    date_str.to_string()
+    tags
+}
+
+// Czy data należy do miesiąca w formacie "RRRR-MM" (wartość <input type="month">)
+pub fn is_in_month(date: NaiveDate, month_str: &str) -> bool {
+    date.format("%Y-%m").to_string() == month_str
 }
 
 pub fn parsed_date_from_str(s: &str) -> NaiveDate {